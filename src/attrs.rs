@@ -0,0 +1,313 @@
+//! Typed parsing of the item attributes in rustdoc JSON.
+//!
+//! Rustdoc JSON lists attributes as their source strings, e.g.
+//! `"#[repr(u8)]"`. We parse the ones which change how an item can be used,
+//! so they can be shown above the item's signature.
+
+use std::fmt::{self, Write};
+
+use crate::{Cfg, RustDocItem};
+
+/// An attribute on an item.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Attribute {
+    /// `#[must_use]` or `#[must_use = "reason"]`
+    MustUse { reason: Option<String> },
+    /// `#[non_exhaustive]`
+    NonExhaustive,
+    /// `#[repr(C)]`, `#[repr(u8)]`, `#[repr(C, packed)]`, ...
+    Repr(Vec<String>),
+    /// `#[doc(hidden)]`
+    DocHidden,
+    /// `#[deprecated(...)]`. The details are in the item's `deprecation`.
+    Deprecated,
+    /// `#[unstable(feature = "...", ...)]`
+    Unstable { feature: Option<String> },
+    /// `#[cfg(...)]`, e.g. `#[cfg(feature = "fs")]`
+    Cfg(Cfg),
+    /// `#[doc(cfg(...))]`, which says how an item is enabled in its docs
+    DocCfg(Cfg),
+    /// `#[macro_export]`, which exports a `macro_rules!` macro at the crate
+    /// root
+    MacroExport,
+    /// `#[no_mangle]`, which exports a function or static by its own name
+    NoMangle,
+    /// `#[export_name = "..."]` or, on foreign items, `#[link_name =
+    /// "..."]`: the name of the symbol
+    SymbolName { key: String, name: String },
+    /// Any other attribute, e.g. `#[inline]`, as written in the source.
+    Other(String),
+}
+
+impl Attribute {
+    /// Parse an attribute from its source string, e.g. `#[repr(C)]`.
+    pub fn parse(attr: &str) -> Self {
+        let Some(content) = attr
+            .trim()
+            .strip_prefix("#[")
+            .and_then(|rest| rest.strip_suffix(']'))
+        else {
+            return Self::Other(attr.to_string());
+        };
+        let content = content.trim();
+
+        if content == "non_exhaustive" {
+            return Self::NonExhaustive;
+        }
+        if content == "macro_export" {
+            return Self::MacroExport;
+        }
+        if content == "no_mangle" {
+            return Self::NoMangle;
+        }
+        if let Some((key, name)) = content.split_once('=') {
+            let key = key.trim();
+            if key == "export_name" || key == "link_name" {
+                return Self::SymbolName {
+                    key: key.to_string(),
+                    name: name.trim().trim_matches('"').to_string(),
+                };
+            }
+        }
+        if content == "must_use" {
+            return Self::MustUse { reason: None };
+        }
+        if let Some(reason) = content
+            .strip_prefix("must_use")
+            .and_then(|rest| rest.trim_start().strip_prefix('='))
+        {
+            let reason = reason.trim().trim_matches('"').to_string();
+            return Self::MustUse {
+                reason: Some(reason),
+            };
+        }
+        if let Some(args) = parenthesized(content, "repr") {
+            let reprs = args
+                .split(',')
+                .map(|repr| repr.trim().to_string())
+                .filter(|repr| !repr.is_empty())
+                .collect();
+            return Self::Repr(reprs);
+        }
+        if let Some(cfg) = parenthesized(content, "cfg").and_then(Cfg::parse) {
+            return Self::Cfg(cfg);
+        }
+        if let Some(args) = parenthesized(content, "doc") {
+            if args.trim() == "hidden" {
+                return Self::DocHidden;
+            }
+            if let Some(cfg) =
+                parenthesized(args.trim(), "cfg").and_then(Cfg::parse)
+            {
+                return Self::DocCfg(cfg);
+            }
+        }
+        if content == "deprecated" || content.starts_with("deprecated(") {
+            return Self::Deprecated;
+        }
+        if let Some(args) = parenthesized(content, "unstable") {
+            let feature = args.split(',').find_map(|arg| {
+                let (key, value) = arg.split_once('=')?;
+                (key.trim() == "feature")
+                    .then(|| value.trim().trim_matches('"').to_string())
+            });
+            return Self::Unstable { feature };
+        }
+
+        Self::Other(attr.to_string())
+    }
+
+    /// Whether this attribute is relevant to users of the item, and should be
+    /// shown above its signature.
+    ///
+    /// Deprecation is shown as a separate note instead, since it also has a
+    /// note and version which read better as prose.
+    pub fn is_relevant(&self) -> bool {
+        match self {
+            Self::MustUse { .. }
+            | Self::NonExhaustive
+            | Self::Repr(_)
+            | Self::DocHidden
+            | Self::Unstable { .. }
+            | Self::MacroExport
+            | Self::NoMangle
+            | Self::SymbolName { .. } => true,
+            Self::Deprecated
+            | Self::Cfg(_)
+            | Self::DocCfg(_)
+            | Self::Other(_) => false,
+        }
+    }
+}
+
+impl fmt::Display for Attribute {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MustUse { reason: None } => write!(f, "#[must_use]"),
+            Self::MustUse {
+                reason: Some(reason),
+            } => write!(f, "#[must_use = {reason:?}]"),
+            Self::NonExhaustive => write!(f, "#[non_exhaustive]"),
+            Self::Repr(reprs) => {
+                let reprs = reprs.join(", ");
+                write!(f, "#[repr({reprs})]")
+            }
+            Self::DocHidden => write!(f, "#[doc(hidden)]"),
+            Self::Deprecated => write!(f, "#[deprecated]"),
+            Self::Unstable { feature: None } => write!(f, "#[unstable]"),
+            Self::Unstable {
+                feature: Some(feature),
+            } => write!(f, "#[unstable(feature = {feature:?})]"),
+            Self::Cfg(cfg) => write!(f, "#[cfg({cfg})]"),
+            Self::DocCfg(cfg) => write!(f, "#[doc(cfg({cfg}))]"),
+            Self::MacroExport => write!(f, "#[macro_export]"),
+            Self::NoMangle => write!(f, "#[no_mangle]"),
+            Self::SymbolName { key, name } => write!(f, "#[{key} = {name:?}]"),
+            Self::Other(attr) => write!(f, "{attr}"),
+        }
+    }
+}
+
+impl RustDocItem {
+    /// The parsed attributes of this item.
+    pub fn attributes(&self) -> Vec<Attribute> {
+        self.attrs
+            .iter()
+            .filter_map(|attr| attr.as_str())
+            .map(Attribute::parse)
+            .collect()
+    }
+
+    /// Whether this item has the given attribute.
+    pub fn has_attribute(&self, attr: &Attribute) -> bool {
+        self.attributes().contains(attr)
+    }
+
+    /// Write the attributes which are relevant to users, one per line, e.g.
+    /// `#[non_exhaustive]` above an enum definition.
+    pub(crate) fn write_attributes(
+        &self,
+        out: &mut String,
+        indent: &str,
+    ) -> fmt::Result {
+        for attr in self.attributes() {
+            if attr.is_relevant() {
+                writeln!(out, "{indent}{attr}")?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Write a note about the deprecation of this item, if it is deprecated.
+    pub(crate) fn write_deprecation(&self, out: &mut String) -> fmt::Result {
+        let Some(deprecation) = &self.deprecation else {
+            return Ok(());
+        };
+
+        write!(out, "> **Deprecated**")?;
+        if let Some(since) = &deprecation.since {
+            write!(out, " since {since}")?;
+        }
+        if let Some(note) = &deprecation.note {
+            write!(out, ": {note}")?;
+        }
+        writeln!(out)?;
+        writeln!(out)
+    }
+}
+
+/// If `content` is `name(args)`, returns `args`.
+fn parenthesized<'a>(content: &'a str, name: &str) -> Option<&'a str> {
+    content
+        .strip_prefix(name)?
+        .trim_start()
+        .strip_prefix('(')?
+        .strip_suffix(')')
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::RustDoc;
+
+    const COMMON_JSON_STR: &str =
+        include_str!("../test-data/common/rustdoc.json");
+
+    #[test]
+    fn test_parse_attribute() {
+        assert_eq!(
+            Attribute::parse("#[non_exhaustive]"),
+            Attribute::NonExhaustive
+        );
+        assert_eq!(
+            Attribute::parse("#[must_use]"),
+            Attribute::MustUse { reason: None }
+        );
+        assert_eq!(
+            Attribute::parse("#[must_use = \"the guard unlocks on drop\"]"),
+            Attribute::MustUse {
+                reason: Some("the guard unlocks on drop".to_string())
+            }
+        );
+        assert_eq!(
+            Attribute::parse("#[repr(C, packed)]"),
+            Attribute::Repr(vec!["C".to_string(), "packed".to_string()])
+        );
+        assert_eq!(Attribute::parse("#[doc(hidden)]"), Attribute::DocHidden);
+        assert_eq!(
+            Attribute::parse("#[deprecated(since = \"0.2.0\")]"),
+            Attribute::Deprecated
+        );
+        assert_eq!(
+            Attribute::parse(
+                "#[unstable(feature = \"ptr_metadata\", issue = \"81513\")]"
+            ),
+            Attribute::Unstable {
+                feature: Some("ptr_metadata".to_string())
+            }
+        );
+        assert_eq!(
+            Attribute::parse("#[cfg(all(unix, feature = \"fs\"))]").to_string(),
+            "#[cfg(all(unix, feature = \"fs\"))]"
+        );
+        assert_eq!(
+            Attribute::parse("#[doc(cfg(feature = \"fs\"))]"),
+            Attribute::DocCfg(Cfg::KeyValue("feature".into(), "fs".into()))
+        );
+        assert_eq!(Attribute::parse("#[macro_export]"), Attribute::MacroExport);
+        assert_eq!(Attribute::parse("#[no_mangle]"), Attribute::NoMangle);
+        assert_eq!(
+            Attribute::parse("#[link_name = \"c_abs\"]"),
+            Attribute::SymbolName {
+                key: "link_name".to_string(),
+                name: "c_abs".to_string()
+            }
+        );
+        assert_eq!(
+            Attribute::parse("#[inline]"),
+            Attribute::Other("#[inline]".to_string())
+        );
+
+        let repr = Attribute::parse("#[repr(C,packed)]");
+        assert_eq!(repr.to_string(), "#[repr(C, packed)]");
+    }
+
+    #[test]
+    fn test_render_attributes() {
+        let doc = serde_json::from_str::<RustDoc>(COMMON_JSON_STR).unwrap();
+        let markdown = doc.to_markdown();
+
+        assert!(markdown.contains(
+            "```rust\n#[repr(u8)]\n#[non_exhaustive]\npub enum Level {"
+        ));
+        assert!(markdown.contains("```rust\n#[must_use]\npub fn greet("));
+        assert!(
+            markdown.contains("```rust\n#[doc(hidden)]\npub fn __log_impl(")
+        );
+        assert!(markdown
+            .contains("> **Deprecated** since 0.2.0: use `greet` instead\n"));
+        // Irrelevant attributes aren't shown.
+        assert!(!markdown.contains("#[inline]"));
+        assert!(!markdown.contains("#[automatically_derived]"));
+    }
+}
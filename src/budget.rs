@@ -0,0 +1,380 @@
+//! Fitting the docs of a crate into a token budget, e.g. an AI's context
+//! window.
+//!
+//! Items are ranked by importance: items at the crate root first, then items
+//! which many signatures refer to, then the rest. Until the docs fit, the
+//! docs of the least important items are shortened to their summary line,
+//! then left out so only signatures remain, and finally whole items are
+//! dropped. A footer lists what was shortened and dropped.
+
+use std::{
+    borrow::Cow,
+    cmp::Reverse,
+    collections::{BTreeMap, BTreeSet},
+    fmt::{self, Write},
+};
+
+use crate::{RustDoc, RustDocItem};
+
+/// How much of an item to print.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum Detail {
+    #[default]
+    Full,
+    /// Only the first paragraph of the docs.
+    Summary,
+    /// No docs, only the signature.
+    Signature,
+    /// Leave the item out.
+    Dropped,
+}
+
+/// An upper bound of the tokens of the budget footer, apart from the labels
+/// of the dropped items: its heading and the counts of shortened items.
+const FOOTER_TOKENS: usize = 48;
+
+/// Estimate the number of tokens an AI's tokenizer splits `text` into.
+///
+/// Words count as one token per four characters, rounded up, and every other
+/// non-whitespace character as one token. This errs on the high side for
+/// English prose, and is close for code.
+pub fn estimate_tokens(text: &str) -> usize {
+    let mut tokens = 0;
+    let mut word_len = 0_usize;
+    for c in text.chars() {
+        if c.is_alphanumeric() || c == '_' {
+            word_len += 1;
+            continue;
+        }
+        tokens += word_len.div_ceil(4);
+        word_len = 0;
+        if !c.is_whitespace() {
+            tokens += 1;
+        }
+    }
+    tokens + word_len.div_ceil(4)
+}
+
+impl RustDoc {
+    /// Write the docs for the whole crate in at most `max_tokens` tokens, as
+    /// estimated by [`estimate_tokens`], if possible.
+    pub(crate) fn write_within_budget(
+        &self,
+        out: &mut String,
+        dependencies: &[RustDoc],
+        max_tokens: usize,
+    ) -> fmt::Result {
+        let render = |details: &BTreeMap<String, Detail>| {
+            let mut out = String::new();
+            self.write_all(&mut out, dependencies, details)?;
+            self.write_budget_footer(&mut out, details)?;
+            Ok::<_, fmt::Error>(out)
+        };
+        let mut details = BTreeMap::new();
+        let full = render(&details)?;
+        let full_tokens = estimate_tokens(&full);
+        if full_tokens <= max_tokens {
+            out.push_str(&full);
+            return Ok(());
+        }
+
+        // The cost of each item at each level of detail, most important first
+        let items = self.ranked_items();
+        let levels = [Detail::Full, Detail::Summary, Detail::Signature];
+        let costs = items
+            .iter()
+            .map(|&(_, item)| {
+                let costs = levels.map(|level| {
+                    let mut rendered = String::new();
+                    item.write_markdown_with(&mut rendered, self, level)
+                        .map(|()| estimate_tokens(&rendered))
+                });
+                Ok::<_, fmt::Error>([costs[0]?, costs[1]?, costs[2]?, 0])
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        // Dropped items cost their label in the footer, unless their parent
+        // is dropped too
+        let labels = items
+            .iter()
+            .map(|&(_, item)| {
+                self.item_label(item)
+                    .map(|label| estimate_tokens(&format!("`{label}`,")))
+                    .unwrap_or_default()
+            })
+            .collect::<Vec<_>>();
+        let parents = items
+            .iter()
+            .map(|&(id, _)| {
+                self.parent(id).map(|(parent_id, _)| parent_id.as_str())
+            })
+            .collect::<Vec<_>>();
+
+        // Degrade all items by one level, least important first, before
+        // degrading any item further. Items without more than a summary line
+        // of docs aren't shortened, so that the footer only counts items
+        // whose docs were actually shortened.
+        let steps = [Detail::Summary, Detail::Signature, Detail::Dropped]
+            .into_iter()
+            .enumerate()
+            .flat_map(|(level_index, level)| {
+                (0..items.len()).rev().map(move |i| (i, level_index, level))
+            })
+            .filter(|&(i, level_index, level)| {
+                level == Detail::Dropped
+                    || costs[i][level_index + 1] < costs[i][level_index]
+            })
+            .collect::<Vec<_>>();
+
+        // Apply steps until the estimated tokens fit, then check the actual
+        // output. The estimate can be off, e.g. for foreign items, which
+        // share their `extern` block, so then apply more steps to make up
+        // for the difference.
+        let mut steps = steps.into_iter();
+        let mut tokens = full_tokens + FOOTER_TOKENS;
+        let mut target = max_tokens;
+        loop {
+            let mut applied = false;
+            for (i, level_index, level) in steps.by_ref() {
+                tokens = (tokens + costs[i][level_index + 1])
+                    .saturating_sub(costs[i][level_index]);
+                if level == Detail::Dropped {
+                    let is_dropped =
+                        |id: &str| details.get(id) == Some(&Detail::Dropped);
+                    if !parents[i].is_some_and(is_dropped) {
+                        tokens += labels[i];
+                    }
+                    let dropped_children = (0..items.len()).filter(|&j| {
+                        parents[j] == Some(items[i].0) && is_dropped(items[j].0)
+                    });
+                    for j in dropped_children {
+                        tokens = tokens.saturating_sub(labels[j]);
+                    }
+                }
+                details.insert(items[i].0.to_string(), level);
+                applied = true;
+                if tokens <= target {
+                    break;
+                }
+            }
+
+            let rendered = render(&details)?;
+            let rendered_tokens = estimate_tokens(&rendered);
+            if rendered_tokens <= max_tokens || !applied {
+                out.push_str(&rendered);
+                return Ok(());
+            }
+            target = tokens.saturating_sub(rendered_tokens - max_tokens);
+        }
+    }
+
+    /// The items listed in the docs, most important first.
+    fn ranked_items(&self) -> Vec<(&str, &RustDocItem)> {
+        let references = self.reference_counts();
+        let api = self.api();
+        let mut items = self
+            .index
+            .iter()
+            .filter(|(id, item)| self.is_listed(id, item))
+            .map(|(id, item)| {
+                let is_at_root =
+                    api.paths(id).iter().any(|path| path.len() == 2);
+                let references = references.get(id.as_str()).copied();
+                let tier = match (is_at_root, references) {
+                    (true, _) => 0,
+                    (false, Some(_)) => 1,
+                    (false, None) => 2,
+                };
+                let key = (tier, Reverse(references.unwrap_or_default()));
+                (key, id.as_str(), item)
+            })
+            .collect::<Vec<_>>();
+        // Stable, so equally important items keep the order of the docs
+        items.sort_by_key(|(key, _, _)| *key);
+        items.into_iter().map(|(_, id, item)| (id, item)).collect()
+    }
+
+    /// The number of items whose signature refers to each local item, e.g.
+    /// functions taking it as a parameter, or structs with a field of its
+    /// type.
+    fn reference_counts(&self) -> BTreeMap<&str, usize> {
+        let mut counts = BTreeMap::new();
+        for (id, item) in &self.index {
+            let Some(inner) = &item.inner else {
+                continue;
+            };
+            let type_paths = if let Some(function) = &inner.function {
+                function.decl.type_paths()
+            } else if let Some(type_) = &inner.struct_field {
+                type_.type_paths()
+            } else {
+                continue;
+            };
+
+            let referenced = type_paths
+                .into_iter()
+                .map(|type_path| type_path.id)
+                .filter(|referenced| referenced.starts_with("0:"))
+                .filter(|referenced| *referenced != id)
+                .collect::<BTreeSet<_>>();
+            for referenced in referenced {
+                *counts.entry(referenced).or_default() += 1;
+            }
+        }
+        counts
+    }
+
+    /// List the items whose docs were shortened or dropped.
+    fn write_budget_footer(
+        &self,
+        out: &mut String,
+        details: &BTreeMap<String, Detail>,
+    ) -> fmt::Result {
+        if details.is_empty() {
+            return Ok(());
+        }
+        let count = |level| details.values().filter(|d| **d == level).count();
+
+        writeln!(out, "## Omitted to fit the token budget")?;
+        writeln!(out)?;
+        let shortened = [
+            (count(Detail::Summary), "shortened to their summary"),
+            (count(Detail::Signature), "left out"),
+        ]
+        .into_iter()
+        .filter(|(count, _)| *count > 0)
+        .map(|(count, what)| match count {
+            1 => format!("{what} for 1 item"),
+            _ => format!("{what} for {count} items"),
+        })
+        .collect::<Vec<_>>();
+        if !shortened.is_empty() {
+            writeln!(out, "Docs were {}.", shortened.join(", and "))?;
+            writeln!(out)?;
+        }
+
+        // Members of dropped items, e.g. fields, go without saying.
+        let is_dropped = |id: &str| details.get(id) == Some(&Detail::Dropped);
+        let dropped = details
+            .keys()
+            .filter(|id| is_dropped(id))
+            .filter(|id| !self.parent(id).is_some_and(|(p, _)| is_dropped(p)))
+            .filter_map(|id| self.index.get(id))
+            .filter_map(|item| self.item_label(item))
+            .map(|label| format!("`{label}`"))
+            .collect::<Vec<_>>();
+        if !dropped.is_empty() {
+            writeln!(out, "Left out entirely: {}.", dropped.join(", "))?;
+            writeln!(out)?;
+        }
+
+        Ok(())
+    }
+
+    /// How to refer to an item in a list, e.g. `common::greet` or `impl Draw
+    /// for Point`, unless it has no path of its own, e.g. a field of an enum
+    /// variant.
+    pub(crate) fn item_label(&self, item: &RustDocItem) -> Option<String> {
+        let impl_ = item.inner.as_ref().and_then(|inner| inner.impl_.as_ref());
+        if let Some(impl_) = impl_ {
+            let for_ = impl_.for_.as_ref()?;
+            return Some(match &impl_.trait_ {
+                Some(trait_) => format!("impl {} for {for_}", trait_.name),
+                None => format!("impl {for_}"),
+            });
+        }
+
+        let path = self
+            .public_path(&item.id)
+            .or_else(|| self.item_path(&item.id))?;
+        (path.len() > 1).then(|| path.join("::"))
+    }
+}
+
+impl RustDocItem {
+    /// The docs of this item in the given detail, which is that of the item
+    /// it is printed in, e.g. the struct of a field.
+    pub(crate) fn docs_to_print(&self, detail: Detail) -> Option<Cow<'_, str>> {
+        let docs = self.docs.as_deref()?;
+        match detail {
+            Detail::Full => Some(Cow::Borrowed(docs)),
+            Detail::Summary => {
+                let summary = docs
+                    .lines()
+                    .take_while(|line| !line.trim().is_empty())
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                Some(Cow::Owned(summary))
+            }
+            Detail::Signature | Detail::Dropped => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::PrintOptions;
+
+    const COMMON_JSON_STR: &str =
+        include_str!("../test-data/common/rustdoc.json");
+
+    fn doc_with_budget(max_tokens: Option<usize>) -> RustDoc {
+        serde_json::from_str::<RustDoc>(COMMON_JSON_STR)
+            .unwrap()
+            .with_options(PrintOptions {
+                max_tokens,
+                ..Default::default()
+            })
+    }
+
+    #[test]
+    fn test_estimate_tokens() {
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("pub fn greet"), 4);
+        // `greeting` is two tokens; `(`, `:`, `&` and `)` one each
+        assert_eq!(estimate_tokens("(greeting: &str)"), 7);
+    }
+
+    #[test]
+    fn test_max_tokens() {
+        let full = doc_with_budget(None).to_markdown();
+        let full_tokens = estimate_tokens(&full);
+
+        // Everything fits
+        let doc = doc_with_budget(Some(full_tokens));
+        assert_eq!(doc.to_markdown(), full);
+
+        for max_tokens in
+            [full_tokens * 3 / 4, full_tokens / 2, full_tokens / 4]
+        {
+            let markdown = doc_with_budget(Some(max_tokens)).to_markdown();
+            assert!(
+                estimate_tokens(&markdown) <= max_tokens,
+                "{max_tokens} tokens:\n{markdown}"
+            );
+            assert!(markdown.contains("## Omitted to fit the token budget"));
+        }
+
+        // Docs are shortened before items are dropped
+        let markdown = doc_with_budget(Some(full_tokens - 50)).to_markdown();
+        assert!(markdown.contains("Docs were shortened to their summary for"));
+        assert!(!markdown.contains("Left out entirely"));
+
+        // The most important items are kept longest
+        let markdown = doc_with_budget(Some(full_tokens / 2)).to_markdown();
+        assert!(markdown.contains("### pub greet\n"));
+        let (_, footer) = markdown.split_once("Left out entirely: ").unwrap();
+        assert!(footer.contains("`common::imp::unreachable_helper`"));
+        assert!(!footer.contains("`common::greet`"));
+
+        // Renders don't share their budget
+        let doc = doc_with_budget(Some(full_tokens / 4));
+        let markdown = doc.to_markdown();
+        std::thread::scope(|scope| {
+            let renders = [(); 4].map(|()| scope.spawn(|| doc.to_markdown()));
+            for render in renders {
+                assert_eq!(render.join().unwrap(), markdown);
+            }
+        });
+    }
+}
@@ -0,0 +1,428 @@
+//! Conditional compilation: the `#[cfg(...)]` and `#[doc(cfg(...))]`
+//! attributes of items, so the docs can say e.g. which Cargo features an
+//! item needs.
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt::{self, Write},
+};
+
+use crate::{Attribute, RustDoc, RustDocItem};
+
+/// A `cfg` predicate, e.g. `all(feature = "fs", unix)`.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Cfg {
+    /// A name, e.g. `unix` or `docsrs`.
+    Name(String),
+    /// A key and value, e.g. `feature = "fs"` or `target_os = "linux"`.
+    KeyValue(String, String),
+    All(Vec<Cfg>),
+    Any(Vec<Cfg>),
+    Not(Box<Cfg>),
+}
+
+/// The effective `cfg` of each item which has one, by id, including the
+/// `cfg`s of the modules, types and impls it is in.
+pub(crate) type ItemCfgs = BTreeMap<String, Cfg>;
+
+impl Cfg {
+    /// Parse a predicate, e.g. `all(feature = "fs", unix)`.
+    pub fn parse(predicate: &str) -> Option<Self> {
+        let mut input = predicate;
+        let cfg = parse_predicate(&mut input)?;
+        input.trim().is_empty().then_some(cfg)
+    }
+
+    /// Both of two predicates, without nesting `all`s.
+    pub fn and(self, other: Cfg) -> Cfg {
+        let mut predicates = Vec::new();
+        for cfg in [self, other] {
+            match cfg {
+                Cfg::All(all) => predicates.extend(all),
+                cfg => predicates.push(cfg),
+            }
+        }
+        predicates.dedup();
+        match predicates.len() {
+            1 => predicates.remove(0),
+            _ => Cfg::All(predicates),
+        }
+    }
+
+    /// The Cargo features which enable the item, i.e. which aren't under a
+    /// `not(...)`, e.g. `fs` and `tls` for `all(feature = "fs", feature =
+    /// "tls")`.
+    pub fn features(&self) -> Vec<&str> {
+        match self {
+            Cfg::KeyValue(key, value) if key == "feature" => vec![value],
+            Cfg::All(predicates) | Cfg::Any(predicates) =>
+                predicates.iter().flat_map(Cfg::features).collect(),
+            Cfg::Name(_) | Cfg::KeyValue(..) | Cfg::Not(_) => Vec::new(),
+        }
+    }
+
+    /// A description of the predicate, as in "Available on crate feature
+    /// `fs` only", e.g. "crate features `fs` and `tls`" or "non-`windows`".
+    pub fn description(&self) -> String {
+        match self {
+            Cfg::KeyValue(key, value) if key == "feature" =>
+                format!("crate feature `{value}`"),
+            Cfg::Name(_) | Cfg::KeyValue(..) => format!("`{self}`"),
+            Cfg::Not(cfg) => match **cfg {
+                Cfg::Name(_) | Cfg::KeyValue(..) =>
+                    format!("non-{}", cfg.description()),
+                _ => format!("not ({})", cfg.description()),
+            },
+            Cfg::All(predicates) => describe_list(predicates, "and"),
+            Cfg::Any(predicates) => describe_list(predicates, "or"),
+        }
+    }
+
+    fn is_feature(&self) -> bool {
+        matches!(self, Cfg::KeyValue(key, _) if key == "feature")
+    }
+}
+
+/// The predicate as written in source, e.g. `all(feature = "fs", unix)`.
+impl fmt::Display for Cfg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let list = |f: &mut fmt::Formatter<'_>, name, predicates: &[Cfg]| {
+            let predicates = predicates
+                .iter()
+                .map(Cfg::to_string)
+                .collect::<Vec<_>>()
+                .join(", ");
+            write!(f, "{name}({predicates})")
+        };
+        match self {
+            Cfg::Name(name) => write!(f, "{name}"),
+            Cfg::KeyValue(key, value) => write!(f, "{key} = {value:?}"),
+            Cfg::All(predicates) => list(f, "all", predicates),
+            Cfg::Any(predicates) => list(f, "any", predicates),
+            Cfg::Not(cfg) => write!(f, "not({cfg})"),
+        }
+    }
+}
+
+/// Describe predicates joined by "and" or "or", e.g. "crate features `a`,
+/// `b` and `c`".
+fn describe_list(predicates: &[Cfg], conjunction: &str) -> String {
+    let (prefix, descriptions) = if predicates.iter().all(Cfg::is_feature) {
+        (
+            "crate features ",
+            predicates
+                .iter()
+                .map(|cfg| format!("`{}`", cfg.features().concat()))
+                .collect::<Vec<_>>(),
+        )
+    } else {
+        (
+            "",
+            predicates
+                .iter()
+                .map(|cfg| match cfg {
+                    Cfg::All(_) | Cfg::Any(_) =>
+                        format!("({})", cfg.description()),
+                    cfg => cfg.description(),
+                })
+                .collect(),
+        )
+    };
+    let joined = match descriptions.split_last() {
+        Some((last, [])) => last.clone(),
+        Some((last, rest)) =>
+            format!("{} {conjunction} {last}", rest.join(", ")),
+        None => String::new(),
+    };
+    format!("{prefix}{joined}")
+}
+
+/// Parse a predicate from the start of `input`, moving `input` past it.
+fn parse_predicate(input: &mut &str) -> Option<Cfg> {
+    *input = input.trim_start();
+    let end = input
+        .find(|c: char| !c.is_alphanumeric() && c != '_')
+        .unwrap_or(input.len());
+    if end == 0 {
+        return None;
+    }
+    let (name, rest) = input.split_at(end);
+    *input = rest.trim_start();
+
+    if let Some(rest) = input.strip_prefix('=') {
+        let value = rest.trim_start().strip_prefix('"')?;
+        let (value, rest) = value.split_once('"')?;
+        *input = rest;
+        return Some(Cfg::KeyValue(name.to_string(), value.to_string()));
+    }
+    let Some(rest) = input.strip_prefix('(') else {
+        return Some(Cfg::Name(name.to_string()));
+    };
+    *input = rest;
+
+    let mut predicates = Vec::new();
+    loop {
+        *input = input.trim_start();
+        if let Some(rest) = input.strip_prefix(')') {
+            *input = rest;
+            break;
+        }
+        predicates.push(parse_predicate(input)?);
+        *input = input.trim_start();
+        match input.strip_prefix(',') {
+            Some(rest) => *input = rest,
+            None if input.starts_with(')') => {}
+            None => return None,
+        }
+    }
+    match name {
+        "all" => Some(Cfg::All(predicates)),
+        "any" => Some(Cfg::Any(predicates)),
+        "not" if predicates.len() == 1 =>
+            Some(Cfg::Not(Box::new(predicates.remove(0)))),
+        _ => None,
+    }
+}
+
+impl RustDocItem {
+    /// The `cfg` of this item alone: its `#[doc(cfg(...))]`, which crates
+    /// use to say how the item is enabled, or otherwise its `#[cfg(...)]`.
+    pub fn own_cfg(&self) -> Option<Cfg> {
+        let attributes = self.attributes();
+        let doc_cfgs = attributes
+            .iter()
+            .filter_map(|attr| match attr {
+                Attribute::DocCfg(cfg) => Some(cfg.clone()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let cfgs = if doc_cfgs.is_empty() {
+            attributes
+                .into_iter()
+                .filter_map(|attr| match attr {
+                    Attribute::Cfg(cfg) => Some(cfg),
+                    _ => None,
+                })
+                .collect()
+        } else {
+            doc_cfgs
+        };
+        cfgs.into_iter().reduce(Cfg::and)
+    }
+
+    /// The ids of the items within this item, whose `cfg`s include this
+    /// item's `cfg`.
+    pub(crate) fn cfg_children(&self) -> Vec<&String> {
+        let Some(inner) = &self.inner else {
+            return Vec::new();
+        };
+        let mut children = Vec::new();
+        if let Some(module) = &inner.module {
+            children.extend(&module.items);
+        }
+        if let Some(struct_) = &inner.struct_ {
+            children.extend(&struct_.fields);
+            children.extend(&struct_.impls);
+        }
+        if let Some(enum_) = &inner.enum_ {
+            children.extend(&enum_.variants);
+            children.extend(&enum_.impls);
+        }
+        if let Some(union_) = &inner.union_ {
+            children.extend(&union_.fields);
+            children.extend(&union_.impls);
+        }
+        if let Some(trait_) = &inner.trait_ {
+            children.extend(&trait_.items);
+        }
+        if let Some(impl_) = &inner.impl_ {
+            children.extend(&impl_.items);
+        }
+        children
+    }
+}
+
+impl RustDoc {
+    /// The `cfg` an item is enabled by, including the `cfg`s of the modules,
+    /// types and impls it is in, e.g. `feature = "fs"` for the functions of
+    /// a `#[cfg(feature = "fs")] mod fs`.
+    pub fn cfg(&self, id: &str) -> Option<&Cfg> {
+        self.item_cfgs.get_or_init(|| self.find_cfgs()).get(id)
+    }
+
+    /// Find the effective `cfg` of every item, from the crate root down.
+    fn find_cfgs(&self) -> ItemCfgs {
+        let mut cfgs = ItemCfgs::new();
+        let mut stack = vec![(&self.root, None::<Cfg>)];
+        let mut visited = HashSet::new();
+        while let Some((id, parent_cfg)) = stack.pop() {
+            if !visited.insert(id) {
+                continue;
+            }
+            let Some(item) = self.index.get(id) else {
+                continue;
+            };
+            let cfg = match (parent_cfg, item.own_cfg()) {
+                (Some(parent), Some(own)) => Some(parent.and(own)),
+                (parent, own) => parent.or(own),
+            };
+            for child in item.cfg_children() {
+                stack.push((child, cfg.clone()));
+            }
+            if let Some(cfg) = cfg {
+                cfgs.insert(id.clone(), cfg);
+            }
+        }
+        cfgs
+    }
+
+    /// The Cargo features of the crate, each with the paths of the items
+    /// which need it, e.g. `fs` with `tokio::fs`.
+    pub fn features(&self) -> BTreeMap<String, Vec<String>> {
+        let mut features = BTreeMap::<String, Vec<String>>::new();
+        for (id, item) in &self.index {
+            if !self.is_listed(id, item) || item.omitted_reason(self).is_some()
+            {
+                continue;
+            }
+            let Some(cfg) = item.own_cfg() else {
+                continue;
+            };
+            let Some(path) =
+                self.public_path(id).or_else(|| self.item_path(id))
+            else {
+                continue;
+            };
+            for feature in cfg.features() {
+                let paths = features.entry(feature.to_string()).or_default();
+                let path = path.join("::");
+                if !paths.contains(&path) {
+                    paths.push(path);
+                }
+            }
+        }
+        for paths in features.values_mut() {
+            paths.sort();
+        }
+        features
+    }
+
+    /// Write a note on how an item is enabled, e.g. "Available on crate
+    /// feature `fs` only."
+    pub(crate) fn write_cfg(&self, out: &mut String, id: &str) -> fmt::Result {
+        let Some(cfg) = self.cfg(id) else {
+            return Ok(());
+        };
+        writeln!(out, "> Available on {} only.\n", cfg.description())
+    }
+
+    /// Write an index of the crate's Cargo features, with the items which
+    /// each one enables.
+    pub(crate) fn write_features(&self, out: &mut String) -> fmt::Result {
+        let features = self.features();
+        if features.is_empty() {
+            return Ok(());
+        }
+
+        writeln!(out, "## Features")?;
+        writeln!(out)?;
+        for (feature, paths) in features {
+            let paths = paths
+                .iter()
+                .map(|path| format!("`{path}`"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            writeln!(out, "- `{feature}`: {paths}")?;
+        }
+        writeln!(out)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const FEATURES_JSON_STR: &str =
+        include_str!("../test-data/features/rustdoc.json");
+
+    fn feature(name: &str) -> Cfg {
+        Cfg::KeyValue("feature".to_string(), name.to_string())
+    }
+
+    #[test]
+    fn test_parse_cfg() {
+        assert_eq!(Cfg::parse(r#"feature = "fs""#), Some(feature("fs")));
+        assert_eq!(Cfg::parse("unix"), Some(Cfg::Name("unix".to_string())));
+        let cfg = Cfg::parse(
+            r#"all(feature = "tls", any(feature = "rustls",
+            feature = "native-tls"), not(windows))"#,
+        )
+        .unwrap();
+        assert_eq!(
+            cfg,
+            Cfg::All(vec![
+                feature("tls"),
+                Cfg::Any(vec![feature("rustls"), feature("native-tls")]),
+                Cfg::Not(Box::new(Cfg::Name("windows".to_string()))),
+            ])
+        );
+        assert_eq!(
+            cfg.to_string(),
+            r#"all(feature = "tls", any(feature = "rustls", feature = "native-tls"), not(windows))"#
+        );
+        assert_eq!(cfg.features(), ["tls", "rustls", "native-tls"]);
+        assert_eq!(
+            cfg.description(),
+            "crate feature `tls`, (crate features `rustls` or `native-tls`) \
+             and non-`windows`"
+        );
+
+        assert_eq!(Cfg::parse("all(unix"), None);
+        assert_eq!(Cfg::parse("unix windows"), None);
+    }
+
+    #[test]
+    fn test_item_cfgs() {
+        let doc = serde_json::from_str::<RustDoc>(FEATURES_JSON_STR).unwrap();
+        let cfg = |path: &[&str]| {
+            let path = path.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+            doc.cfg(doc.id_by_path(&path).unwrap()).cloned()
+        };
+        assert_eq!(cfg(&["features", "connect"]), None);
+        assert_eq!(cfg(&["features", "fs"]), Some(feature("fs")));
+        // Including the `cfg`s of the modules an item is in
+        assert_eq!(cfg(&["features", "fs", "read"]), Some(feature("fs")));
+
+        let features = doc.features();
+        assert_eq!(
+            features["fs"],
+            [
+                "features::Connection::send_file",
+                "features::fs",
+                "features::read_uring",
+            ]
+        );
+        assert_eq!(features["native-tls"], ["features::TlsConnection"]);
+    }
+
+    #[test]
+    fn test_write_cfg() {
+        let doc = serde_json::from_str::<RustDoc>(FEATURES_JSON_STR).unwrap();
+        let markdown = doc.to_markdown();
+        assert!(markdown.contains(
+            "### pub send_file\n\n> Available on crate feature `fs` only.\n"
+        ));
+        assert!(markdown.contains(
+            "> Available on crate feature `tls` and (crate features \
+             `rustls` or `native-tls`) only.\n"
+        ));
+        assert!(markdown.contains(
+            "> Available on `target_os = \"linux\"` or `target_os = \
+             \"macos\"` only.\n"
+        ));
+        assert!(markdown.contains(
+            "## Features\n\n- `fs`: `features::Connection::send_file`, \
+             `features::fs`, `features::read_uring`\n"
+        ));
+    }
+}
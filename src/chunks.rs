@@ -0,0 +1,247 @@
+//! Exporting the docs as one JSON object per item, e.g. to feed a vector
+//! index for retrieval, where markdown headings make poor chunk boundaries.
+
+use std::collections::BTreeSet;
+
+use serde::Serialize;
+
+use crate::{RustDoc, RustDocItem};
+
+/// The docs of one item, with what is needed to embed and link it.
+#[derive(Clone, Debug, Serialize)]
+pub struct Chunk {
+    /// The rustdoc id of the item, e.g. `0:12:345`.
+    pub id: String,
+    /// The path of the item, e.g. `common::shapes::Point::new`.
+    pub path: String,
+    /// What kind of item it is, e.g. `struct` or `method`.
+    pub kind: &'static str,
+    /// The signature, as shown in the markdown docs.
+    pub signature: Option<String>,
+    /// The first paragraph of the docs, on one line.
+    pub summary: Option<String>,
+    /// The full docs.
+    pub docs: Option<String>,
+    /// The path of the module, type or trait which contains the item.
+    pub parent: Option<String>,
+    /// The impls of a type, or of a trait, e.g. `impl Display for Error`.
+    pub impls: Vec<String>,
+    /// The items which the docs link to.
+    pub links: Vec<ChunkLink>,
+    /// The text to embed: the path, signature and docs.
+    pub text: String,
+}
+
+/// An item which the docs of a [`Chunk`] link to.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct ChunkLink {
+    pub id: String,
+    /// The path of the item, if it is known, e.g. `core::fmt::Display`.
+    pub path: Option<String>,
+}
+
+impl RustDoc {
+    /// One chunk per item, ordered by path. Fields, enum variants and impls
+    /// are part of the chunks of their type, rather than chunks of their own.
+    pub fn chunks(&self) -> Vec<Chunk> {
+        let mut chunks = self
+            .index
+            .iter()
+            .filter(|(id, item)| self.is_listed(id, item))
+            .filter(|(_, item)| item.skip(self).is_none())
+            .filter(|(_, item)| {
+                !matches!(item.kind_name(self), "field" | "variant" | "impl")
+            })
+            .filter_map(|(_, item)| self.chunk(item))
+            .collect::<Vec<_>>();
+        chunks.sort_by(|a, b| a.path.cmp(&b.path));
+        chunks
+    }
+
+    /// The chunks as a pretty-printed JSON array.
+    pub fn to_chunks_json(&self) -> String {
+        serde_json::to_string_pretty(&self.chunks())
+            .expect("Chunks can always be serialized")
+    }
+
+    /// The chunks as JSON Lines: one JSON object per line.
+    pub fn to_chunks_jsonl(&self) -> String {
+        self.chunks()
+            .iter()
+            .map(|chunk| {
+                let json = serde_json::to_string(chunk)
+                    .expect("Chunks can always be serialized");
+                json + "\n"
+            })
+            .collect()
+    }
+
+    fn chunk(&self, item: &RustDocItem) -> Option<Chunk> {
+        let path = self
+            .public_path(&item.id)
+            .or_else(|| self.item_path(&item.id))?;
+        let parent = match path.as_slice() {
+            [parent @ .., _] if !parent.is_empty() => Some(parent.join("::")),
+            _ => None,
+        };
+        let path = path.join("::");
+        let kind = item.kind_name(self);
+        let signature = item.signature(self);
+
+        let text = [
+            Some(format!("{path} ({kind})")),
+            signature.clone(),
+            item.docs.clone(),
+        ]
+        .into_iter()
+        .flatten()
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+        Some(Chunk {
+            id: item.id.clone(),
+            path,
+            kind,
+            signature,
+            summary: item.summary(),
+            docs: item.docs.clone(),
+            parent,
+            impls: self.chunk_impls(item),
+            links: self.chunk_links(item),
+            text,
+        })
+    }
+
+    /// The impls of a type, or the impls of a trait, leaving out impls which
+    /// rustdoc adds for auto traits and blanket impls.
+    fn chunk_impls(&self, item: &RustDocItem) -> Vec<String> {
+        let Some(inner) = &item.inner else {
+            return Vec::new();
+        };
+        let impl_ids = if let Some(struct_) = &inner.struct_ {
+            &struct_.impls
+        } else if let Some(enum_) = &inner.enum_ {
+            &enum_.impls
+        } else if let Some(union_) = &inner.union_ {
+            &union_.impls
+        } else if let Some(trait_) = &inner.trait_ {
+            &trait_.implementations
+        } else {
+            return Vec::new();
+        };
+
+        impl_ids
+            .iter()
+            .filter_map(|id| self.index.get(id))
+            .filter(|impl_item| {
+                impl_item
+                    .inner
+                    .as_ref()
+                    .and_then(|i| i.impl_.as_ref())
+                    .is_some_and(|impl_| {
+                        !impl_.synthetic && impl_.blanket_impl.is_none()
+                    })
+            })
+            .filter(|impl_item| impl_item.omitted_reason(self).is_none())
+            .filter_map(|impl_item| self.item_label(impl_item))
+            .collect()
+    }
+
+    /// The items which the docs link to, e.g. with ``[`Point`]``.
+    fn chunk_links(&self, item: &RustDocItem) -> Vec<ChunkLink> {
+        item.links
+            .values()
+            .filter_map(|id| id.as_str())
+            .map(|id| ChunkLink {
+                id: id.to_string(),
+                path: self
+                    .public_path(id)
+                    .or_else(|| self.item_path(id))
+                    .map(|path| path.join("::")),
+            })
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const COMMON_JSON_STR: &str =
+        include_str!("../test-data/common/rustdoc.json");
+
+    fn chunks() -> Vec<Chunk> {
+        serde_json::from_str::<RustDoc>(COMMON_JSON_STR)
+            .unwrap()
+            .chunks()
+    }
+
+    #[test]
+    fn test_chunks() {
+        let chunks = chunks();
+        let chunk = |path: &str| {
+            chunks
+                .iter()
+                .find(|chunk| chunk.path == path)
+                .unwrap_or_else(|| panic!("No chunk for {path}"))
+        };
+
+        let greet = chunk("common::greet");
+        assert_eq!(greet.kind, "function");
+        assert_eq!(greet.parent.as_deref(), Some("common"));
+        assert_eq!(
+            greet.summary.as_deref(),
+            Some(
+                "Greets someone by name. Prefer this over the deprecated \
+                 [`hello`]."
+            )
+        );
+        assert!(greet
+            .signature
+            .as_deref()
+            .unwrap()
+            .contains("pub fn greet(name: &str) -> String"));
+        assert_eq!(greet.links[0].path.as_deref(), Some("common::hello"));
+        assert!(greet.text.starts_with(
+            "common::greet (function)\n\n#[must_use]\npub fn greet"
+        ));
+
+        let new = chunk("common::shapes::Point::new");
+        assert_eq!(new.kind, "method");
+        assert_eq!(new.parent.as_deref(), Some("common::shapes::Point"));
+
+        let point = chunk("common::shapes::Point");
+        assert!(point
+            .impls
+            .contains(&"impl Draw for shapes::Point".to_string()));
+        assert!(point.impls.contains(&"impl Clone for Point".to_string()));
+        assert!(!point.impls.iter().any(|impl_| impl_.contains("Send")));
+
+        // Fields and variants are part of their type's chunk
+        assert!(chunks
+            .iter()
+            .all(|chunk| chunk.path != "common::shapes::Point::x"));
+        assert!(chunks.iter().all(|chunk| chunk.kind != "variant"));
+    }
+
+    #[test]
+    fn test_chunks_jsonl() {
+        let doc = serde_json::from_str::<RustDoc>(COMMON_JSON_STR).unwrap();
+        let jsonl = doc.to_chunks_jsonl();
+        assert_eq!(jsonl.lines().count(), doc.chunks().len());
+        for line in jsonl.lines() {
+            let chunk =
+                serde_json::from_str::<serde_json::Value>(line).unwrap();
+            for field in ["id", "path", "kind", "signature", "text"] {
+                assert!(chunk.get(field).is_some(), "{field} in {line}");
+            }
+        }
+
+        let json = doc.to_chunks_json();
+        let chunks = serde_json::from_str::<Vec<serde_json::Value>>(&json);
+        assert_eq!(chunks.unwrap().len(), doc.chunks().len());
+    }
+}
@@ -0,0 +1,571 @@
+//! Documenting a crate in several build configurations, e.g. with its
+//! default features, with `--all-features` and for other targets, and
+//! merging the docs into one, noting which configurations have each item.
+//!
+//! Each configuration is documented by running `cargo rustdoc`. The docs are
+//! merged by the path of each item within its module, type, trait or impl,
+//! since rustdoc's ids differ between runs.
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    ffi::OsString,
+    fmt::{self, Write},
+    fs, io,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use crate::{conversions::impl_label, ExternalCrate, RustDoc, RustDocItem};
+
+/// The nightly toolchain which emits the rustdoc JSON format we parse, as
+/// for the test fixtures.
+const TOOLCHAIN: &str = "+nightly-2024-06-20";
+
+/// A build configuration to document a crate in.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BuildConfig {
+    /// A short name for the configuration, e.g. `all-features`, shown on
+    /// items which only some configurations have.
+    pub name: String,
+    /// The arguments to `cargo rustdoc`, e.g. `--all-features`.
+    pub cargo_args: Vec<String>,
+    /// The target triple, if not the host's.
+    pub target: Option<String>,
+}
+
+impl BuildConfig {
+    /// The crate's default features, for the host.
+    pub fn default_features() -> Self {
+        Self {
+            name: "default".into(),
+            cargo_args: Vec::new(),
+            target: None,
+        }
+    }
+
+    /// All of the crate's features, for the host.
+    pub fn all_features() -> Self {
+        Self {
+            name: "all-features".into(),
+            cargo_args: vec!["--all-features".into()],
+            target: None,
+        }
+    }
+
+    /// The given features on top of the default ones, e.g. `fs,tls`.
+    pub fn features(features: &str) -> Self {
+        Self {
+            name: format!("features={features}"),
+            cargo_args: vec!["--features".into(), features.into()],
+            target: None,
+        }
+    }
+
+    /// The crate's default features, for the given target triple, e.g.
+    /// `x86_64-pc-windows-msvc`.
+    pub fn target(triple: &str) -> Self {
+        Self {
+            name: triple.into(),
+            cargo_args: vec!["--target".into(), triple.into()],
+            target: Some(triple.into()),
+        }
+    }
+
+    /// Parse a configuration as given on the command line: `default`,
+    /// `all-features`, `features=<features>` or a target triple.
+    pub fn parse(spec: &str) -> Self {
+        match spec {
+            "default" => Self::default_features(),
+            "all-features" => Self::all_features(),
+            _ => match spec.strip_prefix("features=") {
+                Some(features) => Self::features(features),
+                None => Self::target(spec),
+            },
+        }
+    }
+
+    /// The arguments to `cargo` to document the library of the crate at
+    /// `manifest_path` in this configuration, as JSON in `target_dir`.
+    fn cargo_command(
+        &self,
+        manifest_path: &Path,
+        target_dir: &Path,
+    ) -> Vec<OsString> {
+        let mut args = [TOOLCHAIN, "rustdoc", "--lib", "--manifest-path"]
+            .map(OsString::from)
+            .to_vec();
+        args.push(manifest_path.into());
+        args.push("--target-dir".into());
+        args.push(target_dir.into());
+        args.extend(self.cargo_args.iter().map(OsString::from));
+        args.extend(
+            ["--", "-Z", "unstable-options", "--output-format", "json"]
+                .map(OsString::from),
+        );
+        args
+    }
+
+    /// Document the crate at `manifest_path` in this configuration, with
+    /// `cargo rustdoc`. Each configuration is built in its own directory
+    /// under `target/ai-rustdoc`, so builds don't invalidate each other.
+    pub fn document(
+        &self,
+        manifest_path: impl AsRef<Path>,
+    ) -> io::Result<RustDoc> {
+        let manifest_path = manifest_path.as_ref();
+        let target_dir = manifest_path
+            .parent()
+            .unwrap_or(Path::new("."))
+            .join("target")
+            .join("ai-rustdoc")
+            .join(&self.name);
+        // Cargo's output is progress for the user, not our output
+        duct::cmd("cargo", self.cargo_command(manifest_path, &target_dir))
+            .stdout_to_stderr()
+            .run()?;
+
+        let doc_dir = match &self.target {
+            Some(triple) => target_dir.join(triple).join("doc"),
+            None => target_dir.join("doc"),
+        };
+        RustDoc::load(json_output(&doc_dir)?)
+    }
+}
+
+/// The JSON file rustdoc wrote to `doc_dir`, named after the crate.
+fn json_output(doc_dir: &Path) -> io::Result<PathBuf> {
+    let mut outputs = Vec::new();
+    for entry in fs::read_dir(doc_dir)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "json") {
+            outputs.push(path);
+        }
+    }
+    match outputs.len() {
+        1 => Ok(outputs.remove(0)),
+        _ => Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("Expected one rustdoc JSON file in {}", doc_dir.display()),
+        )),
+    }
+}
+
+/// The configurations the docs were merged from, and which of them have
+/// each item.
+#[derive(Debug, Default)]
+pub(crate) struct ItemConfigs {
+    /// The names of all of the configurations, in the order given.
+    names: Vec<String>,
+    /// The names of the configurations which have each item, by id.
+    items: BTreeMap<String, Vec<String>>,
+}
+
+impl RustDoc {
+    /// Document the crate at `manifest_path` in each of the configurations,
+    /// and merge the docs into one. See [`RustDoc::merge_configs`].
+    pub fn document_configs(
+        manifest_path: impl AsRef<Path>,
+        configs: &[BuildConfig],
+    ) -> io::Result<Self> {
+        let docs = configs
+            .iter()
+            .map(|config| {
+                Ok((config.name.clone(), config.document(&manifest_path)?))
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+        Self::merge_configs(docs).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "No configurations")
+        })
+    }
+
+    /// Merge the docs of one crate in several configurations, by the paths
+    /// of the items, into the docs of the first configuration. Each item
+    /// notes which of the configurations have it. `None` if there are no
+    /// docs to merge.
+    pub fn merge_configs(docs: Vec<(String, RustDoc)>) -> Option<Self> {
+        let mut docs = docs.into_iter();
+        let (name, mut merged) = docs.next()?;
+        let mut configs = ItemConfigs {
+            names: vec![name.clone()],
+            items: BTreeMap::new(),
+        };
+        let mut keys = merged.merge_keys();
+        for id in keys.values() {
+            configs.items.insert(id.clone(), vec![name.clone()]);
+        }
+
+        for (name, other) in docs {
+            let new_ids = merged.merge(&other, &name, &keys);
+            for (key, (id, _)) in other.merge_tree() {
+                let id = match keys.get(&key) {
+                    Some(id) => id.clone(),
+                    None => new_ids[&id].clone(),
+                };
+                configs
+                    .items
+                    .entry(id.clone())
+                    .or_default()
+                    .push(name.clone());
+                keys.entry(key).or_insert(id);
+            }
+            configs.names.push(name);
+        }
+
+        merged.configs = configs;
+        merged.public_api = OnceLock::new();
+        merged.error_returns = OnceLock::new();
+        merged.method_impls = OnceLock::new();
+        merged.type_usages = OnceLock::new();
+        merged.item_cfgs = OnceLock::new();
+        Some(merged)
+    }
+
+    /// The names of the configurations which have the item with the given
+    /// id, if the docs were merged from several configurations.
+    pub fn item_configs(&self, id: &str) -> Option<&[String]> {
+        self.configs.items.get(id).map(Vec::as_slice)
+    }
+
+    /// The id of each item by its key: its path through the modules, types,
+    /// traits and impls from the crate root, e.g. `features::Connection::
+    /// impl Connection::send_file`.
+    fn merge_keys(&self) -> HashMap<String, String> {
+        self.merge_tree()
+            .into_iter()
+            .map(|(key, (id, _))| (key, id))
+            .collect()
+    }
+
+    /// The items within the crate root, by key, each with the id of the
+    /// item which lists it.
+    fn merge_tree(&self) -> BTreeMap<String, (String, Option<String>)> {
+        let mut tree = BTreeMap::new();
+        let mut stack = vec![(self.root.clone(), None::<(String, String)>)];
+        let mut visited = HashSet::new();
+        while let Some((id, parent)) = stack.pop() {
+            if !visited.insert(id.clone()) {
+                continue;
+            }
+            let Some(item) = self.index.get(&id) else {
+                continue;
+            };
+            let key = match &parent {
+                Some((parent_key, _)) =>
+                    format!("{parent_key}::{}", merge_segment(item)),
+                None => merge_segment(item),
+            };
+            for child in item.cfg_children() {
+                stack.push((child.clone(), Some((key.clone(), id.clone()))));
+            }
+            tree.entry(key)
+                .or_insert((id, parent.map(|(_, parent_id)| parent_id)));
+        }
+        tree
+    }
+
+    /// Add the items of `other` which these docs don't have, by key, under
+    /// their parents. Returns the ids the added items were given, by their
+    /// ids in `other`.
+    fn merge(
+        &mut self,
+        other: &RustDoc,
+        config: &str,
+        keys: &HashMap<String, String>,
+    ) -> HashMap<String, String> {
+        let tree = other.merge_tree();
+
+        // Ids of other crates' items, by path
+        let paths = self
+            .paths
+            .iter()
+            .filter(|(_, summary)| summary.crate_id != 0)
+            .map(|(id, summary)| ((&summary.path, &summary.kind), id))
+            .collect::<HashMap<_, _>>();
+        let mut crate_ids = self
+            .external_crates
+            .iter()
+            .filter_map(|(crate_id, krate)| {
+                Some((krate.name.clone(), crate_id.parse::<u32>().ok()?))
+            })
+            .collect::<HashMap<_, _>>();
+
+        // Which of `other`'s ids refer to which of ours
+        let mut ids = HashMap::new();
+        let mut new_ids = HashMap::new();
+        for (key, (id, _)) in &tree {
+            let new_id = match keys.get(key) {
+                Some(ours) => ours.clone(),
+                None => {
+                    let new_id = format!("{id}@{config}");
+                    new_ids.insert(id.clone(), new_id.clone());
+                    new_id
+                }
+            };
+            ids.insert(id.clone(), new_id);
+        }
+        for (id, summary) in &other.paths {
+            if ids.contains_key(id) {
+                continue;
+            }
+            let new_id = match paths.get(&(&summary.path, &summary.kind)) {
+                Some(ours) if summary.crate_id != 0 => (*ours).clone(),
+                _ => format!("{id}@{config}"),
+            };
+            ids.insert(id.clone(), new_id);
+        }
+
+        // The paths of added items, and of other crates' items we didn't
+        // know, with their crates
+        for (id, summary) in &other.paths {
+            let new_id = &ids[id];
+            if new_id == id || self.paths.contains_key(new_id) {
+                continue;
+            }
+            let mut summary = remap(summary, &ids);
+            if let Some(krate) =
+                other.external_crates.get(&summary.crate_id.to_string())
+            {
+                summary.crate_id = match crate_ids.get(&krate.name) {
+                    Some(crate_id) => *crate_id,
+                    None => {
+                        let crate_id =
+                            crate_ids.values().max().map_or(1, |max| max + 1);
+                        self.external_crates.insert(
+                            crate_id.to_string(),
+                            ExternalCrate {
+                                name: krate.name.clone(),
+                            },
+                        );
+                        crate_ids.insert(krate.name.clone(), crate_id);
+                        crate_id
+                    }
+                };
+            }
+            self.paths.insert(new_id.clone(), summary);
+        }
+
+        for (id, new_id) in &new_ids {
+            let Some(item) = other.index.get(id) else {
+                continue;
+            };
+            self.index.insert(new_id.clone(), remap(item, &ids));
+        }
+
+        // List the added items under the parents we already had
+        for (id, parent_id) in tree
+            .values()
+            .filter_map(|(id, parent)| Some((id, parent.as_ref()?)))
+        {
+            if !new_ids.contains_key(id) || new_ids.contains_key(parent_id) {
+                continue;
+            }
+            let Some(parent) = other.index.get(parent_id) else {
+                continue;
+            };
+            let Some(ours) = self.index.get_mut(&ids[parent_id]) else {
+                continue;
+            };
+            let Ok(parent) = serde_json::to_value(parent) else {
+                continue;
+            };
+            let Ok(mut value) = serde_json::to_value(&*ours) else {
+                continue;
+            };
+            for pointer in lists_containing(&parent, id, String::new()) {
+                if let Some(serde_json::Value::Array(list)) =
+                    value.pointer_mut(&pointer)
+                {
+                    list.push(ids[id].clone().into());
+                }
+            }
+            if let Ok(item) = serde_json::from_value(value) {
+                *ours = item;
+            }
+        }
+
+        new_ids
+    }
+
+    /// Write a note on which configurations have an item, if not all of
+    /// them, e.g. "Present in the `all-features` configuration only."
+    pub(crate) fn write_item_configs(
+        &self,
+        out: &mut String,
+        id: &str,
+    ) -> fmt::Result {
+        let Some(names) = self.item_configs(id) else {
+            return Ok(());
+        };
+        if names.len() == self.configs.names.len() {
+            return Ok(());
+        }
+        let quoted = names
+            .iter()
+            .map(|name| format!("`{name}`"))
+            .collect::<Vec<_>>();
+        let (list, noun) = match quoted.as_slice() {
+            [name] => (name.clone(), "configuration"),
+            [rest @ .., last] =>
+                (format!("{} and {last}", rest.join(", ")), "configurations"),
+            [] => return Ok(()),
+        };
+        writeln!(out, "> Present in the {list} {noun} only.\n")
+    }
+}
+
+/// The last segment of an item's key: its name, or the impl as written,
+/// e.g. `impl Draw for Point`, or the import, e.g. `use foo::*`.
+fn merge_segment(item: &RustDocItem) -> String {
+    let inner = item.inner.as_ref();
+    if let Some(impl_) = inner.and_then(|inner| inner.impl_.as_ref()) {
+        return impl_label(impl_);
+    }
+    if let Some(import) = inner.and_then(|inner| inner.import.as_ref()) {
+        return if import.glob {
+            format!("use {}::*", import.source)
+        } else {
+            format!("use {} as {}", import.source, import.name)
+        };
+    }
+    item.name.clone().unwrap_or_else(|| item.id.clone())
+}
+
+/// A copy of part of the docs, with the ids of `ids` replaced.
+fn remap<T>(value: &T, ids: &HashMap<String, String>) -> T
+where
+    T: serde::Serialize + serde::de::DeserializeOwned,
+{
+    fn remap_value(
+        value: &mut serde_json::Value,
+        ids: &HashMap<String, String>,
+    ) {
+        match value {
+            serde_json::Value::String(string) => {
+                if let Some(id) = ids.get(string.as_str()) {
+                    *string = id.clone();
+                }
+            }
+            serde_json::Value::Array(values) =>
+                values.iter_mut().for_each(|value| remap_value(value, ids)),
+            serde_json::Value::Object(map) =>
+                map.values_mut().for_each(|value| remap_value(value, ids)),
+            _ => {}
+        }
+    }
+
+    let mut json =
+        serde_json::to_value(value).expect("The docs serialize to JSON");
+    remap_value(&mut json, ids);
+    serde_json::from_value(json).expect("Remapping ids keeps the docs valid")
+}
+
+/// The JSON pointers of the lists within an item which contain the id,
+/// e.g. `/inner/module/items`.
+fn lists_containing(
+    value: &serde_json::Value,
+    id: &str,
+    pointer: String,
+) -> Vec<String> {
+    match value {
+        serde_json::Value::Array(values) => {
+            if values.iter().any(|value| value.as_str() == Some(id)) {
+                return vec![pointer];
+            }
+            values
+                .iter()
+                .enumerate()
+                .flat_map(|(i, value)| {
+                    lists_containing(value, id, format!("{pointer}/{i}"))
+                })
+                .collect()
+        }
+        serde_json::Value::Object(map) => map
+            .iter()
+            .flat_map(|(key, value)| {
+                let key = key.replace('~', "~0").replace('/', "~1");
+                lists_containing(value, id, format!("{pointer}/{key}"))
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const FEATURES_JSON_STR: &str =
+        include_str!("../test-data/features/rustdoc.json");
+    const DEFAULT_JSON_STR: &str =
+        include_str!("../test-data/features/default.json");
+
+    fn merged() -> RustDoc {
+        let default = serde_json::from_str(DEFAULT_JSON_STR).unwrap();
+        let all = serde_json::from_str(FEATURES_JSON_STR).unwrap();
+        RustDoc::merge_configs(vec![
+            ("default".into(), default),
+            ("all-features".into(), all),
+        ])
+        .unwrap()
+    }
+
+    fn configs<'a>(doc: &'a RustDoc, name: &str) -> &'a [String] {
+        let (id, _) = doc
+            .index
+            .iter()
+            .find(|(_, item)| item.name.as_deref() == Some(name))
+            .unwrap();
+        doc.item_configs(id).unwrap()
+    }
+
+    #[test]
+    fn test_build_configs() {
+        assert_eq!(
+            BuildConfig::parse("default"),
+            BuildConfig::default_features()
+        );
+        assert_eq!(
+            BuildConfig::parse("features=fs,tls").cargo_args,
+            ["--features", "fs,tls"]
+        );
+        let windows = BuildConfig::parse("x86_64-pc-windows-msvc");
+        assert_eq!(windows.target.as_deref(), Some("x86_64-pc-windows-msvc"));
+
+        let args = BuildConfig::all_features()
+            .cargo_command(Path::new("Cargo.toml"), Path::new("target/all"));
+        assert_eq!(
+            args.join(" ".as_ref()),
+            "+nightly-2024-06-20 rustdoc --lib --manifest-path Cargo.toml \
+             --target-dir target/all --all-features -- -Z unstable-options \
+             --output-format json"
+        );
+    }
+
+    #[test]
+    fn test_merge_configs() {
+        let doc = merged();
+        assert_eq!(configs(&doc, "connect"), ["default", "all-features"]);
+        assert_eq!(configs(&doc, "no_fs"), ["default"]);
+        // Items of either configuration, and the items within them
+        assert_eq!(configs(&doc, "read"), ["all-features"]);
+        assert_eq!(configs(&doc, "send_file"), ["all-features"]);
+
+        let markdown = doc.to_markdown();
+        assert!(markdown.contains(
+            "### pub no_fs\n\n> Available on non-crate feature `fs` \
+             only.\n\n> Present in the `default` configuration only.\n"
+        ));
+        assert!(markdown
+            .contains("> Present in the `all-features` configuration only.\n"));
+        // Under the module and impl which both configurations have
+        assert!(markdown.contains(
+            "**Methods:**\n- `pub fn send_file(&self, path: &str)`: Sends the \
+             contents of a file.\n"
+        ));
+        assert!(markdown.contains(
+            "- `fs`: `features::Connection::send_file`, \
+             `features::fs`, `features::read_uring`"
+        ));
+        assert_eq!(markdown.matches("### pub connect\n").count(), 1);
+    }
+}
@@ -0,0 +1,333 @@
+//! How to get a value of a type, and what it converts into: the functions
+//! which return it, and the impls of conversion traits such as `From`.
+
+use std::fmt::{self, Write};
+
+use serde::Serialize;
+
+use crate::{
+    format_angle_bracketed_args,
+    methods::{has_receiver, impl_type_id, method_signature, type_impls},
+    typesearch::WRAPPERS,
+    GenericArg, GenericArgs, Impl, ReturnType, RustDoc,
+};
+
+/// The traits whose impls for a type make values of it.
+const CONSTRUCTOR_TRAITS: &[&str] =
+    &["From", "TryFrom", "FromStr", "Default", "FromIterator"];
+
+/// A way to get a value of a type.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub enum Constructor {
+    /// A function or associated function which returns the type, possibly
+    /// in an `Option` or `Result`.
+    Function {
+        /// The path of the function, e.g. `common::shapes::Point::new`.
+        path: String,
+        /// The signature, e.g. `pub fn new(x: i32, y: i32) -> Self`.
+        signature: String,
+    },
+    /// An impl of a trait which makes the type, e.g.
+    /// `impl From<[i32; 2]> for Point` or `impl Default for Point`.
+    Impl(String),
+}
+
+impl fmt::Display for Constructor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Function { path, signature } =>
+                write!(f, "`{path}`: `{signature}`"),
+            Self::Impl(impl_) => write!(f, "`{impl_}`"),
+        }
+    }
+}
+
+/// A type which a type converts into.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct Conversion {
+    /// The type converted into, e.g. `String` or `&Point`.
+    pub target: String,
+    /// The trait of the conversion, e.g. `From<Point>` or `AsRef<Point>`.
+    pub via: String,
+}
+
+impl RustDoc {
+    /// The ways to get a value of the struct or enum with the given id: the
+    /// associated and free functions which return it, then the impls of
+    /// `From`, `TryFrom`, `FromStr`, `Default` and `FromIterator` for it.
+    /// Methods which take `self`, such as builder methods, are left out, as
+    /// they need a value already.
+    pub fn constructors(&self, id: &str) -> Vec<Constructor> {
+        let mut functions = self
+            .index
+            .iter()
+            .filter(|(item_id, item)| self.is_listed(item_id, item))
+            .filter(|(_, item)| item.omitted_reason(self).is_none())
+            .filter(|(_, item)| !has_receiver(item))
+            .filter_map(|(item_id, item)| {
+                let function = item.inner.as_ref()?.function.as_ref()?;
+                let impl_ = self.method_impl(item_id);
+                // Trait impls are listed as impls
+                if impl_.is_some_and(|impl_| impl_.trait_.is_some()) {
+                    return None;
+                }
+                let self_id = impl_.and_then(|impl_| impl_type_id(impl_));
+                if !returns_type(function.decl.output.as_ref()?, id, self_id) {
+                    return None;
+                }
+                let path = self
+                    .public_path(item_id)
+                    .or_else(|| self.item_path(item_id))?
+                    .join("::");
+                Some(Constructor::Function {
+                    path,
+                    signature: method_signature(item, false),
+                })
+            })
+            .collect::<Vec<_>>();
+        functions.sort();
+
+        let mut impls = self
+            .trait_impls(id)
+            .filter(|(trait_, impl_)| {
+                CONSTRUCTOR_TRAITS.contains(&trait_.as_str())
+                    && impl_type_id(impl_) == Some(id)
+            })
+            .map(|(_, impl_)| Constructor::Impl(impl_label(impl_)))
+            .collect::<Vec<_>>();
+        impls.sort();
+        impls.dedup();
+
+        functions.extend(impls);
+        functions
+    }
+
+    /// The types which the struct or enum with the given id converts into:
+    /// with `From<Type>` and `TryFrom<Type>` impls for other types, and its
+    /// own impls of `Into`, `TryInto`, `AsRef`, `AsMut` and `Display`, which
+    /// gives `to_string`.
+    pub fn conversions(&self, id: &str) -> Vec<Conversion> {
+        let mut conversions = Vec::new();
+        for (trait_name, impl_) in self.trait_impls(id) {
+            let Some(trait_) = &impl_.trait_ else {
+                continue;
+            };
+            let via = format!(
+                "{trait_name}{}",
+                format_angle_bracketed_args(trait_.args.as_ref())
+            );
+            let arg = first_type_arg(trait_.args.as_ref());
+            let is_for_type = impl_type_id(impl_) == Some(id);
+            let target = match (trait_name.as_str(), arg) {
+                ("Into" | "TryInto", Some(arg)) if is_for_type =>
+                    arg.to_string(),
+                ("AsRef", Some(arg)) if is_for_type => format!("&{arg}"),
+                ("AsMut", Some(arg)) if is_for_type => format!("&mut {arg}"),
+                ("Display", _) if is_for_type => "String".to_string(),
+                // Other types' impls which mention the type, e.g.
+                // `impl From<Point> for [i32; 2]`
+                (
+                    "From" | "TryFrom",
+                    Some(ReturnType::ResolvedPath { resolved_path }),
+                ) if !is_for_type
+                    && resolved_path.id.as_deref() == Some(id) =>
+                {
+                    let Some(for_) = &impl_.for_ else {
+                        continue;
+                    };
+                    for_.to_string()
+                }
+                _ => continue,
+            };
+            conversions.push(Conversion { target, via });
+        }
+        conversions.sort();
+        conversions.dedup();
+        conversions
+    }
+
+    /// The trait impls which are written in the source and listed with a
+    /// struct or enum: its own, and those of other types which mention it,
+    /// e.g. `impl From<Point> for [i32; 2]` of `Point`. Each has the last
+    /// segment of the trait's name, e.g. `FromStr`.
+    fn trait_impls(
+        &self,
+        id: &str,
+    ) -> impl Iterator<Item = (String, &Impl)> + '_ {
+        self.index
+            .get(id)
+            .and_then(type_impls)
+            .into_iter()
+            .flatten()
+            .filter_map(|impl_id| self.index.get(impl_id))
+            .filter(|impl_item| impl_item.omitted_reason(self).is_none())
+            .filter_map(|impl_item| {
+                let impl_ = impl_item.inner.as_ref()?.impl_.as_ref()?;
+                let trait_ = impl_.trait_.as_ref()?;
+                is_written_impl(impl_)
+                    .then(|| (trait_name(&trait_.name).to_string(), impl_))
+            })
+    }
+
+    /// Write how to get a value of a struct or enum, and what it converts
+    /// into, as lists.
+    pub(crate) fn write_conversions(
+        &self,
+        out: &mut String,
+        id: &str,
+    ) -> fmt::Result {
+        let constructors = self.constructors(id);
+        if !constructors.is_empty() {
+            writeln!(out, "**How to obtain:**")?;
+            for constructor in constructors {
+                writeln!(out, "- {constructor}")?;
+            }
+            writeln!(out)?;
+        }
+
+        let conversions = self.conversions(id);
+        if !conversions.is_empty() {
+            writeln!(out, "**Converts into:**")?;
+            for Conversion { target, via } in conversions {
+                writeln!(out, "- `{target}` (via `{via}`)")?;
+            }
+            writeln!(out)?;
+        }
+        Ok(())
+    }
+}
+
+/// Whether a function returning `output` returns the type with the given
+/// id, possibly wrapped, e.g. in `Result<Self, E>`. `self_id` is the type
+/// of the impl of the function, if any.
+fn returns_type(output: &ReturnType, id: &str, self_id: Option<&str>) -> bool {
+    match output {
+        ReturnType::ResolvedPath { resolved_path } => {
+            if resolved_path.id.as_deref() == Some(id) {
+                return true;
+            }
+            // The first argument of `Result` is the `Ok` type
+            WRAPPERS.contains(&trait_name(&resolved_path.name))
+                && first_type_arg(resolved_path.args.as_ref())
+                    .is_some_and(|arg| returns_type(arg, id, self_id))
+        }
+        ReturnType::Generic { generic } =>
+            generic == "Self" && self_id == Some(id),
+        _ => false,
+    }
+}
+
+/// The first type argument of generic args, e.g. `T` of `Option<T>`.
+fn first_type_arg(args: Option<&GenericArgs>) -> Option<&ReturnType> {
+    let Some(GenericArgs::AngleBracketed { angle_bracketed }) = args else {
+        return None;
+    };
+    angle_bracketed.args.iter().find_map(|arg| match arg {
+        GenericArg::Type { type_inner } => Some(type_inner),
+        _ => None,
+    })
+}
+
+/// Whether an impl is written in the source, rather than being a blanket
+/// impl or one which rustdoc adds, e.g. `Send`.
+fn is_written_impl(impl_: &Impl) -> bool {
+    impl_.blanket_impl.is_none() && !impl_.synthetic
+}
+
+/// The last segment of a path, e.g. `FromStr` of `std::str::FromStr`.
+fn trait_name(path: &str) -> &str {
+    path.rsplit("::").next().unwrap_or(path)
+}
+
+/// An impl as written, e.g. `impl From<[i32; 2]> for Point`.
+pub(crate) fn impl_label(impl_: &Impl) -> String {
+    let trait_ = impl_.trait_.as_ref().map(|trait_| {
+        format!(
+            "{}{} for ",
+            trait_name(&trait_.name),
+            format_angle_bracketed_args(trait_.args.as_ref())
+        )
+    });
+    let for_ = impl_.for_.as_ref().map(ToString::to_string);
+    format!(
+        "impl {}{}",
+        trait_.unwrap_or_default(),
+        for_.unwrap_or_default()
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const COMMON_JSON_STR: &str =
+        include_str!("../test-data/common/rustdoc.json");
+
+    fn type_id<'a>(doc: &'a RustDoc, name: &str) -> &'a str {
+        doc.index
+            .iter()
+            .find(|(_, item)| {
+                item.name.as_deref() == Some(name) && type_impls(item).is_some()
+            })
+            .map(|(id, _)| id.as_str())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_constructors() {
+        let doc = serde_json::from_str::<RustDoc>(COMMON_JSON_STR).unwrap();
+        let point = doc.constructors(type_id(&doc, "Point"));
+        assert_eq!(
+            point,
+            vec![
+                Constructor::Function {
+                    path: "common::shapes::Point::new".into(),
+                    signature: "pub fn new(x: i32, y: i32) -> Self".into(),
+                },
+                Constructor::Impl("impl Default for Point".into()),
+                Constructor::Impl("impl From<[i32; 2]> for Point".into()),
+                Constructor::Impl("impl FromStr for Point".into()),
+            ]
+        );
+
+        // Through `Result`
+        let config = doc.constructors(type_id(&doc, "Config"));
+        assert!(matches!(
+            &config[..],
+            [Constructor::Function { path, .. }] if path == "common::Config::load"
+        ));
+        assert!(doc.constructors(type_id(&doc, "ConfigError")).is_empty());
+    }
+
+    #[test]
+    fn test_conversions() {
+        let doc = serde_json::from_str::<RustDoc>(COMMON_JSON_STR).unwrap();
+        assert_eq!(
+            doc.conversions(type_id(&doc, "Point")),
+            vec![Conversion {
+                target: "[i32; 2]".into(),
+                via: "From<Point>".into(),
+            }]
+        );
+        assert_eq!(
+            doc.conversions(type_id(&doc, "Labeled")),
+            vec![Conversion {
+                target: "&Point".into(),
+                via: "AsRef<Point>".into(),
+            }]
+        );
+        assert_eq!(
+            doc.conversions(type_id(&doc, "ConfigError"))[0].target,
+            "String"
+        );
+
+        let markdown = doc.to_markdown();
+        assert!(markdown.contains(
+            "**How to obtain:**\n- `common::shapes::Point::new`: `pub fn \
+             new(x: i32, y: i32) -> Self`\n- `impl Default for Point`\n"
+        ));
+        assert!(markdown.contains(
+            "**Converts into:**\n- `[i32; 2]` (via `From<Point>`)\n"
+        ));
+    }
+}
@@ -0,0 +1,661 @@
+//! Comparing the public APIs of two versions of a crate.
+//!
+//! Items are matched by their public path, relative to the crate root, and
+//! compared by their rendered signatures. The result is rendered as markdown
+//! migration notes, for an AI to read before updating code which uses the
+//! crate.
+
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Write},
+};
+
+use serde::Serialize;
+
+use crate::{
+    format_angle_bracketed_args, skeleton::SourceStyle, Parameter,
+    ProcMacroKind, RustDoc, RustDocItem,
+};
+
+/// Signatures are compared without their docs or impls, which are items of
+/// their own.
+const SIGNATURE: SourceStyle = SourceStyle {
+    impls: false,
+    ..SourceStyle::SKELETON
+};
+
+/// How an item changed between two versions of a crate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    /// The item is no longer part of the public API.
+    Removed,
+    /// The item's signature changed.
+    Changed,
+    /// The item is new to the public API.
+    Added,
+}
+
+/// A change to one item of the public API.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ItemChange {
+    /// The public path of the item, e.g. `common::shapes::Point`, or the
+    /// header of a trait impl, e.g. `impl Clone for common::Token`.
+    pub path: String,
+    /// What kind of item it is, e.g. `function` or `variant`.
+    pub item_kind: &'static str,
+    pub change: ChangeKind,
+    /// The signature in the old version, if the item was in it.
+    pub before: Option<String>,
+    /// The signature in the new version, if the item is in it.
+    pub after: Option<String>,
+    /// The deprecation note of a removed item in the old version, which
+    /// often says what to use instead.
+    pub deprecation_note: Option<String>,
+    /// The id of the item in the old version, if it was in it.
+    #[serde(skip)]
+    pub(crate) old_id: Option<String>,
+    /// The id of the item in the new version, if it is in it.
+    #[serde(skip)]
+    pub(crate) new_id: Option<String>,
+}
+
+/// The changes to the public API between two versions of a crate.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ApiDiff {
+    pub crate_name: String,
+    pub old_version: String,
+    pub new_version: String,
+    /// Removed items first, then changed items, then added items, each
+    /// sorted by path.
+    pub changes: Vec<ItemChange>,
+}
+
+/// An item of the public API.
+struct ApiItem<'a> {
+    item: &'a RustDocItem,
+    kind: &'static str,
+    signature: Option<String>,
+    /// The path of the type or trait which this is a field, variant, method
+    /// or impl of, relative to the crate root.
+    owner: Option<String>,
+}
+
+impl RustDoc {
+    /// The changes to the public API from this version of the crate to the
+    /// `new` version.
+    pub fn diff(&self, new: &RustDoc) -> ApiDiff {
+        let old_items = self.api_items();
+        let new_items = new.api_items();
+        let mut changes = Vec::new();
+
+        for (path, old_item) in &old_items {
+            let new_item = new_items.get(path);
+            let change = match new_item {
+                None => ChangeKind::Removed,
+                Some(new_item) if new_item.signature != old_item.signature =>
+                    ChangeKind::Changed,
+                Some(_) => continue,
+            };
+            let deprecation_note = match change {
+                ChangeKind::Removed => old_item
+                    .item
+                    .deprecation
+                    .as_ref()
+                    .and_then(|deprecation| deprecation.note.clone()),
+                _ => None,
+            };
+            changes.push((
+                old_item.owner.as_deref(),
+                ItemChange {
+                    path: qualified_path(
+                        if new_item.is_some() { new } else { self },
+                        path,
+                    ),
+                    item_kind: old_item.kind,
+                    change,
+                    before: old_item.signature.clone(),
+                    after: new_item.and_then(|new| new.signature.clone()),
+                    deprecation_note,
+                    old_id: Some(old_item.item.id.clone()),
+                    new_id: new_item.map(|new| new.item.id.clone()),
+                },
+            ));
+        }
+        for (path, new_item) in &new_items {
+            if old_items.contains_key(path) {
+                continue;
+            }
+            changes.push((
+                new_item.owner.as_deref(),
+                ItemChange {
+                    path: qualified_path(new, path),
+                    item_kind: new_item.kind,
+                    change: ChangeKind::Added,
+                    before: None,
+                    after: new_item.signature.clone(),
+                    deprecation_note: None,
+                    old_id: None,
+                    new_id: Some(new_item.item.id.clone()),
+                },
+            ));
+        }
+
+        // Fields, methods and impls of added or removed types are implied by
+        // the type being added or removed.
+        let is_implied = |owner: Option<&str>, change: ChangeKind| {
+            let Some(owner) = owner else {
+                return false;
+            };
+            match change {
+                ChangeKind::Added => !old_items.contains_key(owner),
+                ChangeKind::Removed => !new_items.contains_key(owner),
+                ChangeKind::Changed => false,
+            }
+        };
+        let mut changes = changes
+            .into_iter()
+            .filter(|(owner, change)| !is_implied(*owner, change.change))
+            .map(|(_, change)| change)
+            .collect::<Vec<_>>();
+        changes.sort_by(|a, b| (a.change, &a.path).cmp(&(b.change, &b.path)));
+
+        ApiDiff {
+            crate_name: new.crate_name().to_string(),
+            old_version: self.crate_version.clone(),
+            new_version: new.crate_version.clone(),
+            changes,
+        }
+    }
+
+    /// The items of the public API, keyed by their path without the crate
+    /// name. Trait impls are keyed by their header, e.g.
+    /// `impl Clone for Token`, since they don't have a path.
+    fn api_items(&self) -> BTreeMap<String, ApiItem<'_>> {
+        let mut items = BTreeMap::new();
+
+        for (id, item) in &self.index {
+            let Some(inner) = &item.inner else {
+                continue;
+            };
+            if inner.import.is_some() || item.omitted_reason(self).is_some() {
+                continue;
+            }
+
+            if let Some(impl_) = &inner.impl_ {
+                // Blanket impls follow from the other impls of a type
+                if impl_.blanket_impl.is_some() {
+                    continue;
+                }
+                let Some((header, for_path)) = self.impl_header(id) else {
+                    continue;
+                };
+                items.insert(
+                    header.clone(),
+                    ApiItem {
+                        item,
+                        kind: "impl",
+                        signature: Some(header),
+                        owner: Some(for_path),
+                    },
+                );
+                continue;
+            }
+
+            if !id.starts_with("0:") {
+                continue;
+            }
+            // Methods of trait impls are determined by the trait
+            let in_trait_impl = self.parent(id).is_some_and(|(_, parent)| {
+                parent
+                    .inner
+                    .as_ref()
+                    .and_then(|inner| inner.impl_.as_ref())
+                    .is_some_and(|impl_| impl_.trait_.is_some())
+            });
+            if in_trait_impl {
+                continue;
+            }
+            let Some(path) = self.public_path(id) else {
+                continue;
+            };
+            let Some((_, relative_path)) = path.split_first() else {
+                continue;
+            };
+            if relative_path.is_empty() {
+                continue;
+            }
+            let owner = match self.parent(id) {
+                Some(_) =>
+                    Some(relative_path[..relative_path.len() - 1].join("::")),
+                None => None,
+            };
+
+            items.insert(
+                relative_path.join("::"),
+                ApiItem {
+                    item,
+                    kind: item.kind_name(self),
+                    signature: item.signature(self),
+                    owner,
+                },
+            );
+        }
+
+        items
+    }
+
+    /// The header of a public trait impl, with the path of the type relative
+    /// to the crate root, e.g. `impl Clone for Token`, and that path.
+    fn impl_header(&self, id: &str) -> Option<(String, String)> {
+        let impl_ = self.index.get(id)?.inner.as_ref()?.impl_.as_ref()?;
+        let trait_ = impl_.trait_.as_ref()?;
+        let Some(Parameter::ResolvedPath { resolved_path }) = &impl_.for_
+        else {
+            return None;
+        };
+        let for_id = resolved_path.id.as_deref()?;
+        if !for_id.starts_with("0:") {
+            return None;
+        }
+        self.public_path(id)?;
+
+        let for_path = self.public_path(for_id)?;
+        let for_path = for_path.get(1..)?.join("::");
+        let for_args = format_angle_bracketed_args(resolved_path.args.as_ref());
+        let trait_args = format_angle_bracketed_args(trait_.args.as_ref());
+        let header = format!(
+            "impl {}{trait_args} for {for_path}{for_args}",
+            trait_.name
+        );
+        Some((header, for_path))
+    }
+}
+
+/// Prefix a path relative to the crate root with the crate name.
+fn qualified_path(doc: &RustDoc, relative_path: &str) -> String {
+    match relative_path.strip_prefix("impl ") {
+        Some(_) => relative_path.to_string(),
+        None => format!("{}::{relative_path}", doc.crate_name()),
+    }
+}
+
+impl RustDocItem {
+    /// What kind of item this is, e.g. `function`, `method` or `variant`.
+    pub(crate) fn kind_name(&self, doc: &RustDoc) -> &'static str {
+        let Some(inner) = &self.inner else {
+            return "item";
+        };
+        if inner.function.is_some() {
+            match doc.parent(&self.id) {
+                Some(_) => "method",
+                None => "function",
+            }
+        } else if inner.struct_.is_some() {
+            "struct"
+        } else if inner.enum_.is_some() {
+            "enum"
+        } else if inner.union_.is_some() {
+            "union"
+        } else if inner.trait_.is_some() {
+            "trait"
+        } else if inner.variant.is_some() {
+            "variant"
+        } else if inner.struct_field.is_some() {
+            "field"
+        } else if inner.module.is_some() {
+            "module"
+        } else if inner.type_alias.is_some() {
+            "type alias"
+        } else if inner.static_.is_some() {
+            "static"
+        } else if inner.assoc_type.is_some() {
+            "associated type"
+        } else if inner.assoc_const.is_some() {
+            "associated constant"
+        } else if let Some(proc_macro) = &inner.proc_macro {
+            match proc_macro.kind {
+                ProcMacroKind::Bang => "macro",
+                ProcMacroKind::Attr => "attribute macro",
+                ProcMacroKind::Derive => "derive macro",
+            }
+        } else if inner.macro_.is_some() {
+            "macro"
+        } else if inner.impl_.is_some() {
+            "impl"
+        } else {
+            "item"
+        }
+    }
+
+    /// The signature of an item, as written in the skeleton but without doc
+    /// comments or impls, e.g. `pub fn greet(name: &str) -> String { ... }`.
+    pub fn signature(&self, doc: &RustDoc) -> Option<String> {
+        let inner = self.inner.as_ref()?;
+        let name = self.name.as_ref()?;
+
+        let mut out = String::new();
+        if inner.function.is_some()
+            || inner.enum_.is_some()
+            || inner.struct_.is_some()
+            || inner.trait_.is_some()
+            || inner.union_.is_some()
+        {
+            doc.write_item_source(&mut out, self, SIGNATURE).ok()?;
+        } else if inner.variant.is_some() {
+            return Some(name.clone());
+        } else if let Some(type_) = &inner.struct_field {
+            let visibility = if self.is_public() { "pub " } else { "" };
+            return Some(format!("{visibility}{name}: {type_}"));
+        } else if inner.module.is_some() {
+            return Some(format!("pub mod {name}"));
+        } else if inner.type_alias.is_some() {
+            return self.type_alias_source();
+        } else if inner.static_.is_some() {
+            return self.static_source();
+        } else if inner.assoc_type.is_some() || inner.assoc_const.is_some() {
+            let ids = [self.id.clone()];
+            doc.write_assoc_items_source(
+                &mut out,
+                &ids,
+                SourceStyle::SKELETON,
+                true,
+            )
+            .ok()?;
+            let signature = out
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.starts_with("///"))
+                .collect::<Vec<_>>()
+                .join("\n");
+            return Some(signature);
+        } else if inner.macro_.is_some() || inner.proc_macro.is_some() {
+            return self.macro_source();
+        } else {
+            return None;
+        }
+
+        let signature = out
+            .lines()
+            .filter(|line| !line.trim_start().starts_with("///"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        Some(signature)
+    }
+}
+
+impl ApiDiff {
+    /// Whether the public API is unchanged.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// The changes of the given kind.
+    pub fn changes_of(
+        &self,
+        kind: ChangeKind,
+    ) -> impl Iterator<Item = &ItemChange> {
+        self.changes
+            .iter()
+            .filter(move |change| change.change == kind)
+    }
+
+    /// Render the changes as markdown migration notes.
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        self.write_markdown(&mut out)
+            .expect("Writing to a String can't fail");
+        out
+    }
+
+    fn write_markdown(&self, out: &mut String) -> fmt::Result {
+        writeln!(
+            out,
+            "# Migration notes for `{}` v{} to v{}",
+            self.crate_name, self.old_version, self.new_version
+        )?;
+        writeln!(out)?;
+
+        if self.is_empty() {
+            writeln!(out, "The public API is unchanged.")?;
+            return Ok(());
+        }
+
+        let count = |kind| self.changes_of(kind).count();
+        writeln!(
+            out,
+            "{} removed, {} changed and {} added items.",
+            count(ChangeKind::Removed),
+            count(ChangeKind::Changed),
+            count(ChangeKind::Added),
+        )?;
+        writeln!(out)?;
+
+        let sections = [
+            (ChangeKind::Removed, "Removed"),
+            (ChangeKind::Changed, "Changed"),
+            (ChangeKind::Added, "Added"),
+        ];
+        for (kind, heading) in sections {
+            if self.changes_of(kind).next().is_none() {
+                continue;
+            }
+            writeln!(out, "## {heading}")?;
+            writeln!(out)?;
+            for change in self.changes_of(kind) {
+                change.write_markdown(out)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl ItemChange {
+    fn write_markdown(&self, out: &mut String) -> fmt::Result {
+        writeln!(out, "### `{}` ({})", self.path, self.item_kind)?;
+        writeln!(out)?;
+
+        if let Some(note) = &self.deprecation_note {
+            writeln!(out, "> Deprecated before removal: {note}")?;
+            writeln!(out)?;
+        }
+
+        let write_signature = |out: &mut String, signature: &str| {
+            writeln!(out, "```rust")?;
+            writeln!(out, "{signature}")?;
+            writeln!(out, "```")?;
+            writeln!(out)
+        };
+        match (&self.before, &self.after) {
+            (Some(before), Some(after)) => {
+                writeln!(out, "Before:")?;
+                writeln!(out)?;
+                write_signature(out, before)?;
+                writeln!(out, "After:")?;
+                writeln!(out)?;
+                write_signature(out, after)?;
+            }
+            (Some(signature), None) | (None, Some(signature)) =>
+                write_signature(out, signature)?,
+            (None, None) => {}
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const API_V1_JSON_STR: &str =
+        include_str!("../test-data/api_v1/rustdoc.json");
+    const API_V2_JSON_STR: &str =
+        include_str!("../test-data/api_v2/rustdoc.json");
+    const SIGNATURES_V1_JSON_STR: &str =
+        include_str!("../test-data/signatures_v1/rustdoc.json");
+    const SIGNATURES_V2_JSON_STR: &str =
+        include_str!("../test-data/signatures_v2/rustdoc.json");
+
+    fn diff() -> ApiDiff {
+        let old = serde_json::from_str::<RustDoc>(API_V1_JSON_STR).unwrap();
+        let new = serde_json::from_str::<RustDoc>(API_V2_JSON_STR).unwrap();
+        old.diff(&new)
+    }
+
+    fn paths(diff: &ApiDiff, kind: ChangeKind) -> Vec<&str> {
+        diff.changes_of(kind)
+            .map(|change| change.path.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_diff() {
+        let diff = diff();
+
+        assert_eq!(
+            paths(&diff, ChangeKind::Removed),
+            ["api::hello", "impl Clone for Token"]
+        );
+        assert_eq!(
+            paths(&diff, ChangeKind::Changed),
+            [
+                "api::Color",
+                "api::Convert",
+                "api::ErrorKind",
+                "api::Event",
+                "api::Named",
+                "api::Point",
+                "api::Shape",
+                "api::greet",
+            ]
+        );
+        assert_eq!(
+            paths(&diff, ChangeKind::Added),
+            [
+                "api::Color::Blue",
+                "api::Convert::BASE",
+                "api::Convert::MAX",
+                "api::Convert::Output",
+                "api::ErrorKind::Timeout",
+                "api::Event::Click::y",
+                "api::Event::Key::shift",
+                "api::Named::id",
+                "api::Shape::perimeter",
+                "api::Size",
+                "api::subtract",
+            ]
+        );
+
+        let greet = diff
+            .changes
+            .iter()
+            .find(|change| change.path == "api::greet")
+            .unwrap();
+        assert_eq!(greet.item_kind, "function");
+        assert_eq!(
+            greet.before.as_deref(),
+            Some("pub fn greet(name: &str) -> String { ... }")
+        );
+        assert_eq!(
+            greet.after.as_deref(),
+            Some("pub fn greet(name: &str, excited: bool) -> String { ... }")
+        );
+
+        let kind = |path: &str| {
+            diff.changes
+                .iter()
+                .find(|change| change.path == path)
+                .map(|change| change.item_kind)
+        };
+        assert_eq!(kind("api::Convert::Output"), Some("associated type"));
+        assert_eq!(kind("api::Convert::MAX"), Some("associated constant"));
+        assert_eq!(kind("api::Event::Key::shift"), Some("field"));
+
+        // Doc changes don't change the API
+        assert!(!diff
+            .changes
+            .iter()
+            .any(|change| change.path.ends_with("add")));
+
+        let old = serde_json::from_str::<RustDoc>(API_V1_JSON_STR).unwrap();
+        assert!(old.diff(&old).is_empty());
+    }
+
+    /// Generics and the types of tuple fields are part of signatures.
+    #[test]
+    fn test_diff_generics() {
+        let old =
+            serde_json::from_str::<RustDoc>(SIGNATURES_V1_JSON_STR).unwrap();
+        let new =
+            serde_json::from_str::<RustDoc>(SIGNATURES_V2_JSON_STR).unwrap();
+        let diff = old.diff(&new);
+
+        assert_eq!(
+            paths(&diff, ChangeKind::Changed),
+            [
+                "signatures::Id",
+                "signatures::Id::0",
+                "signatures::Store",
+                "signatures::Store::get",
+                "signatures::convert",
+                "signatures::defaults",
+                "signatures::parse",
+            ]
+        );
+        let change = |path: &str| {
+            let change = diff
+                .changes
+                .iter()
+                .find(|change| change.path == path)
+                .unwrap();
+            (change.before.as_deref(), change.after.as_deref())
+        };
+        assert_eq!(
+            change("signatures::Id::0"),
+            (Some("pub 0: u32"), Some("pub 0: u64"))
+        );
+        assert_eq!(
+            change("signatures::convert"),
+            (
+                Some("pub fn convert<T: Clone>(x: &T) -> T { ... }"),
+                Some("pub fn convert<T: Clone + Send>(x: &T) -> T { ... }")
+            )
+        );
+        assert_eq!(
+            change("signatures::parse").1,
+            Some(
+                "pub fn parse<T>(s: &str) -> Option<T> where T: FromStr + \
+                 Default { ... }"
+            )
+        );
+        assert!(change("signatures::Store")
+            .1
+            .unwrap()
+            .starts_with("pub trait Store<K, V> {\n"));
+    }
+
+    #[test]
+    fn test_diff_markdown() {
+        let markdown = diff().to_markdown();
+
+        assert!(markdown.starts_with(
+            "# Migration notes for `api` v0.1.0 to v0.1.1\n\n\
+             2 removed, 8 changed and 11 added items.\n"
+        ));
+        assert!(markdown.contains(
+            "### `api::hello` (function)\n\n\
+             > Deprecated before removal: use `greet` instead\n\n\
+             ```rust\npub fn hello() -> String { ... }\n```\n"
+        ));
+        assert!(markdown.contains(
+            "### `api::Point` (struct)\n\n\
+             Before:\n\n\
+             ```rust\npub struct Point {\n    pub x: i32,\n    pub y: i32,\n}\n```\n\n\
+             After:\n\n\
+             ```rust\npub struct Point {\n    pub x: i32,\n    pub y: i32,\n    \
+             // Some fields are private\n}\n```\n"
+        ));
+    }
+}
@@ -0,0 +1,284 @@
+//! Documenting several crates together, e.g. a crate and its dependencies.
+//!
+//! On its own, a [`RustDoc`] only knows the paths of other crates' items.
+//! Within a [`RustDocSet`], ids of other crates' items resolve to those items
+//! in the other crates' JSON, via `external_crates` and `paths`, so that
+//! signatures and doc links can link to the other crates' docs.
+
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Write},
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::{visit::TypePath, PrintOptions, RustDoc, RustDocItem};
+
+/// The docs of several crates, with items resolved across crates.
+#[derive(Debug)]
+pub struct RustDocSet {
+    docs: Vec<RustDoc>,
+}
+
+impl RustDocSet {
+    pub fn new(mut docs: Vec<RustDoc>) -> Self {
+        let links = docs
+            .iter()
+            .map(|doc| doc.cross_crate_links(&docs))
+            .collect::<Vec<_>>();
+        for (doc, links) in docs.iter_mut().zip(links) {
+            doc.external_links = links;
+        }
+        Self { docs }
+    }
+
+    /// Load the rustdoc JSON outputs at the given paths.
+    pub fn load(
+        paths: impl IntoIterator<Item = impl AsRef<Path>>,
+    ) -> io::Result<Self> {
+        let docs = paths
+            .into_iter()
+            .map(RustDoc::load)
+            .collect::<io::Result<Vec<_>>>()?;
+        Ok(Self::new(docs))
+    }
+
+    /// Use the given options when printing any of the crates.
+    pub fn with_options(mut self, options: PrintOptions) -> Self {
+        for doc in &mut self.docs {
+            doc.options = options.clone();
+        }
+        self
+    }
+
+    /// The docs of each crate in the set.
+    pub fn docs(&self) -> &[RustDoc] {
+        &self.docs
+    }
+
+    /// The docs of the crate with the given name.
+    pub fn get(&self, crate_name: &str) -> Option<&RustDoc> {
+        self.docs.iter().find(|doc| doc.crate_name() == crate_name)
+    }
+
+    /// Resolve an id from `doc`'s JSON, which may refer to an item of another
+    /// crate in the set, to the item and the docs of the crate defining it.
+    pub fn resolve<'a>(
+        &'a self,
+        doc: &'a RustDoc,
+        id: &str,
+    ) -> Option<(&'a RustDoc, &'a RustDocItem)> {
+        if !id.starts_with("0:") {
+            if let Some((other, other_id)) = doc.find_in(&self.docs, id) {
+                return Some((other, other.index.get(other_id)?));
+            }
+        }
+        Some((doc, doc.index.get(id)?))
+    }
+
+    /// Find an item by its definition or public path, e.g.
+    /// `common::shapes::Point`, following re-exports across crates.
+    pub fn lookup(&self, path: &str) -> Option<(&RustDoc, &RustDocItem)> {
+        let path = path.split("::").map(String::from).collect::<Vec<_>>();
+        let doc = self.get(path.first()?)?;
+
+        if let Some(id) = doc.id_by_path(&path) {
+            return self.resolve(doc, id);
+        }
+
+        // Glob re-exports of other crates' modules aren't in the public API
+        // of `doc`, since they can only be expanded with the other crates.
+        let reexport = doc
+            .reexports(&self.docs)
+            .into_iter()
+            .find(|reexport| reexport.path == path)?;
+        let target = reexport.target?;
+        let other = self.get(&reexport.target_crate)?;
+        let id = other.id_by_path(&target)?;
+        Some((other, other.index.get(id)?))
+    }
+
+    /// Render the docs of one crate of the set as markdown, with links to the
+    /// docs of the other crates.
+    pub fn to_markdown(&self, crate_name: &str) -> Option<String> {
+        let doc = self.get(crate_name)?;
+        let mut out = String::new();
+        doc.write_markdown(&mut out, &self.docs)
+            .expect("Writing to a String can't fail");
+        Some(out)
+    }
+
+    /// Write the docs of each crate to `<crate_name>.md` in `dir`, so that
+    /// the links between them resolve. Returns the paths written.
+    pub fn write_markdown_files(&self, dir: &Path) -> io::Result<Vec<PathBuf>> {
+        fs::create_dir_all(dir)?;
+        let mut written = Vec::new();
+        for doc in &self.docs {
+            let path = dir.join(doc.markdown_file_name());
+            let markdown = self
+                .to_markdown(doc.crate_name())
+                .expect("Every crate of the set is in the set");
+            fs::write(&path, markdown)?;
+            written.push(path);
+        }
+        Ok(written)
+    }
+}
+
+impl RustDoc {
+    /// The name of the markdown file the docs of this crate are written to.
+    pub fn markdown_file_name(&self) -> String {
+        format!("{}.md", self.crate_name())
+    }
+
+    /// A link to the docs of one of this crate's items, from the docs of
+    /// another crate.
+    pub(crate) fn link_to(&self, item: &RustDocItem) -> String {
+        format!("{}#{}", self.markdown_file_name(), item.anchor())
+    }
+
+    /// Find an item of another crate in that crate's JSON, using the crate
+    /// name and path which our JSON lists for the id.
+    pub(crate) fn find_in<'a>(
+        &self,
+        docs: &'a [RustDoc],
+        id: &str,
+    ) -> Option<(&'a RustDoc, &'a str)> {
+        let summary = self.paths.get(id)?;
+        let crate_name = &self
+            .external_crates
+            .get(&summary.crate_id.to_string())?
+            .name;
+        let other = docs.iter().find(|doc| doc.crate_name() == crate_name)?;
+        let id = other.id_by_path(&summary.path)?;
+        Some((other, id))
+    }
+
+    /// Links to the docs of the items of other crates in `docs` which this
+    /// crate refers to, keyed by the ids we know them by.
+    fn cross_crate_links(&self, docs: &[RustDoc]) -> BTreeMap<String, String> {
+        self.paths
+            .iter()
+            .filter(|(_, summary)| summary.crate_id != 0)
+            .filter_map(|(id, _)| {
+                let (other, other_id) = self.find_in(docs, id)?;
+                let item = other.index.get(other_id)?;
+                Some((id.clone(), other.link_to(item)))
+            })
+            .collect()
+    }
+
+    /// Write links to the docs of the types from other crates in a
+    /// signature, e.g. after `pub fn draw(point: Point)`.
+    pub(crate) fn write_external_links(
+        &self,
+        out: &mut String,
+        type_paths: &[TypePath<'_>],
+    ) -> fmt::Result {
+        let mut links = Vec::new();
+        for type_path in type_paths {
+            let Some(url) = self.external_links.get(type_path.id) else {
+                continue;
+            };
+            let link = format!("[`{}`]({url})", type_path.name);
+            if !links.contains(&link) {
+                links.push(link);
+            }
+        }
+        if links.is_empty() {
+            return Ok(());
+        }
+
+        writeln!(out, "Types from other crates: {}", links.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const COMMON_JSON_STR: &str =
+        include_str!("../test-data/common/rustdoc.json");
+    const FACADE_JSON_STR: &str =
+        include_str!("../test-data/facade/rustdoc.json");
+
+    fn docset() -> RustDocSet {
+        RustDocSet::new(vec![
+            serde_json::from_str(FACADE_JSON_STR).unwrap(),
+            serde_json::from_str(COMMON_JSON_STR).unwrap(),
+        ])
+    }
+
+    #[test]
+    fn test_lookup() {
+        let set = docset();
+
+        let (doc, point) = set.lookup("common::shapes::Point").unwrap();
+        assert_eq!(doc.crate_name(), "common");
+        assert_eq!(point.name.as_deref(), Some("Point"));
+
+        // Through a re-export of another crate's item, and of another
+        // crate's module
+        let (doc, greet) = set.lookup("facade::greet").unwrap();
+        assert_eq!(doc.crate_name(), "common");
+        assert_eq!(greet.name.as_deref(), Some("greet"));
+        let (_, shape) = set.lookup("facade::Shape").unwrap();
+        assert_eq!(shape.name.as_deref(), Some("Shape"));
+
+        let (doc, draw) = set.lookup("facade::draw").unwrap();
+        assert_eq!(doc.crate_name(), "facade");
+        assert_eq!(draw.name.as_deref(), Some("draw"));
+
+        assert!(set.lookup("facade::Missing").is_none());
+        assert!(set.lookup("serde::Serialize").is_none());
+    }
+
+    #[test]
+    fn test_resolve() {
+        let set = docset();
+        let facade = set.get("facade").unwrap();
+        let (draw_id, _) = facade
+            .index
+            .iter()
+            .find(|(_, item)| item.name.as_deref() == Some("draw"))
+            .unwrap();
+        let (_, draw) = set.resolve(facade, draw_id).unwrap();
+
+        // The id of `common::Draw` in the facade's JSON
+        let draw_trait_id = draw.links.values().next().unwrap();
+        let draw_trait_id = draw_trait_id.as_str().unwrap();
+        assert!(!facade.index.contains_key(draw_trait_id));
+        let (doc, draw_trait) = set.resolve(facade, draw_trait_id).unwrap();
+        assert_eq!(doc.crate_name(), "common");
+        assert_eq!(draw_trait.name.as_deref(), Some("Draw"));
+    }
+
+    #[test]
+    fn test_cross_crate_links() {
+        let markdown = docset().to_markdown("facade").unwrap();
+
+        // Types in signatures
+        assert!(markdown.contains(
+            "pub fn draw(point: Point) -> String { ... }\n```\n\
+             Types from other crates: [`Point`](common.md#point-struct)\n"
+        ));
+        // Intra-doc links
+        assert!(markdown.contains(
+            "Draws a point as text, using [`common::Draw`](common.md#draw-trait)."
+        ));
+        // Re-exports, including of other crates' modules
+        assert!(markdown.contains(
+            "- `pub use common::greet;`: [`greet`](common.md#greet-function)\n"
+        ));
+        assert!(markdown.contains(
+            "- `pub use common::shapes::*;`: [`Labeled`](common.md#labeled-struct), \
+             [`Point`](common.md#point-struct), [`Shape`](common.md#shape-enum)\n"
+        ));
+
+        // Standalone, there is nothing to link to.
+        let facade = serde_json::from_str::<RustDoc>(FACADE_JSON_STR).unwrap();
+        let markdown = facade.to_markdown();
+        assert!(!markdown.contains("common.md"));
+        assert!(markdown.contains("using [`common::Draw`]."));
+    }
+}
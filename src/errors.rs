@@ -0,0 +1,341 @@
+//! Which functions return which error types, e.g. that `hex::decode` and
+//! `hex::decode_to_slice` both return `DecodeError`.
+
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Write},
+};
+
+use serde::Serialize;
+
+use crate::{
+    methods::{method_signature, type_impls},
+    GenericArg, GenericArgs, Impl, Parameter, ReturnType, RustDoc, RustDocItem,
+};
+
+/// An error type of the crate, and the functions which return it.
+#[derive(Clone, Debug, Serialize)]
+pub struct ErrorType {
+    /// The rustdoc id of the error type.
+    pub id: String,
+    /// The path of the error type, e.g. `hex::DecodeError`.
+    pub path: String,
+    /// The paths of the functions which return it in a `Result`, e.g.
+    /// `hex::decode`.
+    pub returned_by: Vec<String>,
+    /// Whether it implements `std::error::Error`.
+    pub implements_error: bool,
+    /// Whether it implements `Display`.
+    pub implements_display: bool,
+}
+
+/// The functions which return each error type in a `Result`, by the id of
+/// the error type, each with whether it is a method of a trait impl.
+pub(crate) type ErrorReturns = BTreeMap<String, Vec<(String, bool)>>;
+
+impl RustDoc {
+    /// The error types of the crate: its types which are returned in the
+    /// `Err` of a `Result` or implement `std::error::Error`.
+    pub fn error_types(&self) -> Vec<ErrorType> {
+        self.index
+            .iter()
+            .filter(|(id, item)| self.is_listed(id, item))
+            .filter_map(|(id, _)| self.error_type(id))
+            .collect()
+    }
+
+    /// The functions which return the type with the given id as an error,
+    /// and whether it implements `std::error::Error` and `Display`, if it
+    /// is returned as an error or implements `std::error::Error`.
+    pub fn error_type(&self, id: &str) -> Option<ErrorType> {
+        let item = self.index.get(id)?;
+        type_impls(item)?;
+        let returned_by = self
+            .error_returning_fns(id)
+            .into_iter()
+            .map(|(path, _, _)| path)
+            .collect::<Vec<_>>();
+        let implements_error = self.implements(id, &["error", "Error"]);
+        if returned_by.is_empty() && !implements_error {
+            return None;
+        }
+        let path = self
+            .public_path(id)
+            .or_else(|| self.item_path(id))?
+            .join("::");
+        Some(ErrorType {
+            id: id.to_string(),
+            path,
+            returned_by,
+            implements_error,
+            implements_display: self.implements(id, &["fmt", "Display"]),
+        })
+    }
+
+    /// The functions which return the type with the given id as an error,
+    /// with their paths and whether they are methods of trait impls.
+    fn error_returning_fns(
+        &self,
+        id: &str,
+    ) -> Vec<(String, &RustDocItem, bool)> {
+        let returns = self.error_returns.get_or_init(|| self.find_errors());
+        let mut functions = returns
+            .get(id)
+            .into_iter()
+            .flatten()
+            .filter_map(|(fn_id, is_trait_impl)| {
+                let item = self.index.get(fn_id)?;
+                if item.omitted_reason(self).is_some() {
+                    return None;
+                }
+                let path = self
+                    .public_path(fn_id)
+                    .or_else(|| self.item_path(fn_id))?
+                    .join("::");
+                Some((path, item, *is_trait_impl))
+            })
+            .collect::<Vec<_>>();
+        functions.sort_by(|(a, ..), (b, ..)| a.cmp(b));
+        functions
+    }
+
+    /// Find the error type of every function which returns a `Result`.
+    fn find_errors(&self) -> ErrorReturns {
+        let mut returns = ErrorReturns::new();
+        for (id, item) in &self.index {
+            let Some(function) =
+                item.inner.as_ref().and_then(|i| i.function.as_ref())
+            else {
+                continue;
+            };
+            if !self.is_listed(id, item) {
+                continue;
+            }
+            // The impl of a method, to know what `Self::Error` is
+            let impl_ = self.method_impl(id);
+            let Some(output) = &function.decl.output else {
+                continue;
+            };
+            let Some(error_id) = self.error_of(output, impl_) else {
+                continue;
+            };
+            let is_trait_impl = impl_.is_some_and(|i| i.trait_.is_some());
+            returns
+                .entry(error_id.to_string())
+                .or_default()
+                .push((id.clone(), is_trait_impl));
+        }
+        returns
+    }
+
+    /// The id of the error type of a `Result`, including through type
+    /// aliases, e.g. `type Result<T> = std::result::Result<T, Error>`.
+    fn error_of<'a>(
+        &'a self,
+        output: &'a ReturnType,
+        impl_: Option<&'a Impl>,
+    ) -> Option<&'a str> {
+        let ReturnType::ResolvedPath { resolved_path } = output else {
+            return None;
+        };
+        if resolved_path.name.rsplit("::").next() == Some("Result") {
+            if let Some(error) = type_args(resolved_path.args.as_ref()).nth(1) {
+                return self.type_id(error, impl_);
+            }
+        }
+        let alias = self
+            .index
+            .get(resolved_path.id.as_deref()?)?
+            .inner
+            .as_ref()?
+            .type_alias
+            .as_ref()?;
+        self.error_of(&alias.type_, None)
+    }
+
+    /// The id of a type, looking up associated types of an impl, e.g.
+    /// `Self::Error`.
+    fn type_id<'a>(
+        &'a self,
+        type_: &'a ReturnType,
+        impl_: Option<&'a Impl>,
+    ) -> Option<&'a str> {
+        match type_ {
+            ReturnType::ResolvedPath { resolved_path } =>
+                resolved_path.id.as_deref(),
+            ReturnType::Qualified { qualified_path } => {
+                let Parameter::Generic { generic } = &*qualified_path.self_type
+                else {
+                    return None;
+                };
+                if generic != "Self" {
+                    return None;
+                }
+                let assoc_type = impl_?
+                    .items
+                    .iter()
+                    .filter_map(|id| self.index.get(id))
+                    .find(|item| {
+                        item.name.as_deref() == Some(&qualified_path.name)
+                    })?
+                    .inner
+                    .as_ref()?
+                    .assoc_type
+                    .as_ref()?;
+                self.type_id(assoc_type.default.as_ref()?, None)
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether a type has an impl of the trait with the given path suffix,
+    /// e.g. `["fmt", "Display"]`.
+    fn implements(&self, id: &str, trait_path: &[&str]) -> bool {
+        self.trait_impl(id, trait_path).is_some()
+    }
+
+    /// The impl of the trait with the given path suffix for a type.
+    fn trait_impl(&self, id: &str, trait_path: &[&str]) -> Option<&Impl> {
+        let is_trait = |impl_: &Impl| {
+            let Some(summary) = impl_
+                .trait_
+                .as_ref()
+                .and_then(|trait_| self.paths.get(trait_.id.as_ref()?))
+            else {
+                return false;
+            };
+            let path = &summary.path;
+            path.len() >= trait_path.len()
+                && path[path.len() - trait_path.len()..] == *trait_path
+        };
+        self.index
+            .get(id)
+            .and_then(type_impls)?
+            .iter()
+            .filter_map(|impl_id| self.index.get(impl_id))
+            .filter_map(|item| item.inner.as_ref()?.impl_.as_ref())
+            .find(|impl_| impl_.blanket_impl.is_none() && is_trait(impl_))
+    }
+
+    /// Write the functions which return a type as an error, and which of
+    /// the error traits it implements.
+    pub(crate) fn write_error_type(
+        &self,
+        out: &mut String,
+        id: &str,
+    ) -> fmt::Result {
+        let Some(error) = self.error_type(id) else {
+            return Ok(());
+        };
+
+        let functions = self.error_returning_fns(id);
+        if !functions.is_empty() {
+            writeln!(out, "**Returned by:**")?;
+            for (path, item, is_trait_impl) in functions {
+                let signature = method_signature(item, is_trait_impl);
+                writeln!(out, "- `{path}`: `{signature}`")?;
+            }
+            writeln!(out)?;
+        }
+
+        let has_source =
+            self.trait_impl(id, &["error", "Error"])
+                .is_some_and(|impl_| {
+                    impl_.items.iter().any(|item_id| {
+                        self.index.get(item_id).and_then(|i| i.name.as_deref())
+                            == Some("source")
+                    })
+                });
+        let error_trait = match (error.implements_error, has_source) {
+            (true, true) => "implements `std::error::Error`, with `source`",
+            (true, false) => "implements `std::error::Error`",
+            (false, _) => "doesn't implement `std::error::Error`",
+        };
+        let display = if error.implements_display {
+            "implements `Display`"
+        } else {
+            "doesn't implement `Display`"
+        };
+        writeln!(out, "**As an error:** {error_trait}; {display}.\n")
+    }
+}
+
+/// The type arguments of generic args, e.g. `T` and `E` of `Result<T, E>`.
+fn type_args(args: Option<&GenericArgs>) -> impl Iterator<Item = &ReturnType> {
+    let args = match args {
+        Some(GenericArgs::AngleBracketed { angle_bracketed }) =>
+            angle_bracketed.args.as_slice(),
+        _ => &[],
+    };
+    args.iter().filter_map(|arg| match arg {
+        GenericArg::Type { type_inner } => Some(type_inner),
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const COMMON_JSON_STR: &str =
+        include_str!("../test-data/common/rustdoc.json");
+    const HEX_JSON_STR: &str = include_str!("../test-data/hex/rustdoc.json");
+
+    fn error_type(doc: &RustDoc, name: &str) -> ErrorType {
+        let (id, _) = doc
+            .index
+            .iter()
+            .find(|(_, item)| {
+                item.name.as_deref() == Some(name) && type_impls(item).is_some()
+            })
+            .unwrap();
+        doc.error_type(id).unwrap()
+    }
+
+    #[test]
+    fn test_error_types() {
+        let doc = serde_json::from_str::<RustDoc>(HEX_JSON_STR).unwrap();
+        let error = error_type(&doc, "DecodeError");
+        assert_eq!(error.path, "hex::DecodeError");
+        // Including the impls of `FromHex`
+        assert_eq!(
+            error.returned_by,
+            [
+                "alloc::borrow::Cow::from_hex",
+                "alloc::vec::Vec::from_hex",
+                "hex::FromHex::from_hex",
+                "hex::decode",
+                "hex::decode_to_slice",
+            ]
+        );
+        assert!(error.implements_error && error.implements_display);
+        let types = doc.error_types();
+        assert_eq!(types.len(), 1);
+
+        // Through a type alias
+        let doc = serde_json::from_str::<RustDoc>(COMMON_JSON_STR).unwrap();
+        let error = error_type(&doc, "ConfigError");
+        assert_eq!(
+            error.returned_by,
+            ["common::Config::load", "common::Config::parse"]
+        );
+    }
+
+    #[test]
+    fn test_write_error_type() {
+        let doc = serde_json::from_str::<RustDoc>(HEX_JSON_STR).unwrap();
+        let markdown = doc.to_markdown();
+        assert!(markdown.contains(
+            "**Returned by:**\n- `alloc::borrow::Cow::from_hex`: `fn \
+             from_hex(s: &str) -> Result<Self, DecodeError>`\n"
+        ));
+        assert!(markdown.contains(
+            "- `hex::decode`: `pub fn decode(hex: &str) -> Result<Vec<u8>, \
+             DecodeError>`\n"
+        ));
+        assert!(markdown.contains(
+            "**As an error:** implements `std::error::Error`; implements \
+             `Display`.\n"
+        ));
+    }
+}
@@ -0,0 +1,410 @@
+//! Extract the Rust code blocks in item docs as standalone example programs.
+//!
+//! Each code block is turned into a complete `.rs` file the same way
+//! `rustdoc --test` sees it: hidden `# ` lines are restored, and the code is
+//! wrapped in `fn main` unless it already defines one.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::RustDoc;
+
+/// Code block attributes which rustdoc understands. A code block whose info
+/// string has any other token (e.g. `text`, `toml`) is not Rust code.
+const RUST_ATTRS: &[&str] = &[
+    "rust",
+    "ignore",
+    "should_panic",
+    "no_run",
+    "compile_fail",
+    "test_harness",
+    "standalone_crate",
+];
+
+/// A Rust code block from the docs of an item, as a standalone program.
+#[derive(Debug)]
+pub struct DocExample {
+    /// The id of the documented item in the rustdoc JSON `index`.
+    pub item_id: String,
+    /// The full path of the documented item, e.g. `["hex", "display"]`.
+    pub item_path: Vec<String>,
+    /// Zero-based position of this code block among the examples of the
+    /// items with this path. Several items can share a path, e.g. the `fmt`
+    /// methods of a type's `Debug` and `Display` impls.
+    pub index: usize,
+    /// The attributes from the code block's info string, e.g. `no_run`.
+    pub attrs: Vec<String>,
+    /// The complete source of the example, including hidden lines.
+    pub code: String,
+}
+
+impl RustDoc {
+    /// Collect every Rust code block in the docs of this crate's items.
+    ///
+    /// The examples are ordered by item path, then by position in the docs.
+    pub fn examples(&self) -> Vec<DocExample> {
+        let mut examples = Vec::new();
+
+        for (id, item) in &self.index {
+            if !id.starts_with("0:") {
+                continue;
+            }
+            let Some(docs) = &item.docs else { continue };
+            let Some(item_path) = self.item_path(id) else {
+                continue;
+            };
+
+            let blocks = rust_code_blocks(docs);
+            for (index, (attrs, lines)) in blocks.into_iter().enumerate() {
+                examples.push(DocExample {
+                    item_id: id.clone(),
+                    item_path: item_path.clone(),
+                    index,
+                    attrs,
+                    code: make_standalone(&lines),
+                });
+            }
+        }
+
+        examples.sort_by(|a, b| {
+            (&a.item_path, &a.item_id, a.index).cmp(&(
+                &b.item_path,
+                &b.item_id,
+                b.index,
+            ))
+        });
+        // Number the examples per path, so that items with the same path
+        // don't get the same file
+        for i in 1..examples.len() {
+            if examples[i].item_path == examples[i - 1].item_path {
+                examples[i].index = examples[i - 1].index + 1;
+            }
+        }
+        examples
+    }
+
+    /// Write every example to `dir/<item_path>.rs`, e.g.
+    /// `dir/hex/display.rs`. Returns the paths of the written files.
+    pub fn write_examples(&self, dir: &Path) -> io::Result<Vec<PathBuf>> {
+        let mut written = Vec::new();
+
+        for example in self.examples() {
+            let path = dir.join(example.file_path());
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&path, example.to_file_contents())?;
+            written.push(path);
+        }
+
+        Ok(written)
+    }
+}
+
+impl DocExample {
+    /// The relative file path of this example, e.g. `hex/display.rs`.
+    /// Subsequent examples for the same path get a `_2`, `_3`, ... suffix.
+    pub fn file_path(&self) -> PathBuf {
+        let mut path = self.item_path.iter().collect::<PathBuf>();
+        let name = self.item_path.last().unwrap();
+        let file_name = match self.index {
+            0 => format!("{name}.rs"),
+            n => {
+                let number = n + 1;
+                format!("{name}_{number}.rs")
+            }
+        };
+        path.set_file_name(file_name);
+        path
+    }
+
+    /// The contents of the example file: a back-reference to the documented
+    /// item followed by the example source.
+    pub fn to_file_contents(&self) -> String {
+        let item_path = self.item_path.join("::");
+        let item_id = &self.item_id;
+        let mut contents =
+            format!("// Example from the docs of `{item_path}` ({item_id})\n");
+        if !self.attrs.is_empty() {
+            let attrs = self.attrs.join(", ");
+            contents.push_str(&format!("// Code block attributes: {attrs}\n"));
+        }
+        contents.push('\n');
+        contents.push_str(&self.code);
+        contents
+    }
+}
+
+/// Find the Rust code blocks in a markdown doc string. Returns the info string
+/// attributes and the raw lines of each block.
+fn rust_code_blocks(docs: &str) -> Vec<(Vec<String>, Vec<&str>)> {
+    let mut blocks = Vec::new();
+    let mut current = None::<(&str, Vec<String>, Vec<&str>)>;
+
+    for line in docs.lines() {
+        let trimmed = line.trim_start();
+
+        if let Some((fence, _, lines)) = &mut current {
+            let fence_char = fence.chars().next().unwrap();
+            let closes = trimmed.starts_with(*fence)
+                && trimmed.trim_start_matches(fence_char).trim().is_empty();
+            if !closes {
+                lines.push(line);
+                continue;
+            }
+
+            let (_, attrs, lines) = current.take().unwrap();
+            if let Some(attrs) = attrs_if_rust(attrs) {
+                blocks.push((attrs, lines));
+            }
+            continue;
+        }
+
+        let fence_char = match trimmed.chars().next() {
+            Some(c @ ('`' | '~')) => c,
+            _ => continue,
+        };
+        let fence_len =
+            trimmed.chars().take_while(|&c| c == fence_char).count();
+        if fence_len < 3 {
+            continue;
+        }
+
+        let (fence, info) = trimmed.split_at(fence_len);
+        let attrs = info
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|attr| !attr.is_empty())
+            .map(str::to_string)
+            .collect();
+        current = Some((fence, attrs, Vec::new()));
+    }
+
+    // An unclosed code block runs until the end of the docs.
+    if let Some((_, attrs, lines)) = current {
+        if let Some(attrs) = attrs_if_rust(attrs) {
+            blocks.push((attrs, lines));
+        }
+    }
+
+    blocks
+}
+
+/// Returns the attributes without the `rust` marker if the info string
+/// describes a Rust code block, otherwise `None`.
+fn attrs_if_rust(attrs: Vec<String>) -> Option<Vec<String>> {
+    let is_rust = attrs.iter().all(|attr| {
+        RUST_ATTRS.contains(&attr.as_str())
+            || attr.starts_with("edition")
+            || attr.starts_with("ignore-")
+    });
+    if !is_rust {
+        return None;
+    }
+
+    Some(attrs.into_iter().filter(|attr| attr != "rust").collect())
+}
+
+/// Restore hidden lines and wrap the code in `fn main` like rustdoc does.
+fn make_standalone(lines: &[&str]) -> String {
+    let lines = lines
+        .iter()
+        .map(|line| unhide_line(line))
+        .collect::<Vec<_>>();
+
+    // Crate attributes and `extern crate`s must stay outside of `fn main`.
+    let mut prelude = vec!["#![allow(unused)]".to_string()];
+    let mut body = Vec::new();
+    for line in lines {
+        let trimmed = line.trim_start();
+        let is_prelude = body.iter().all(|l: &String| l.trim().is_empty())
+            && (trimmed.starts_with("#![")
+                || trimmed.starts_with("extern crate"));
+        if is_prelude {
+            prelude.push(line);
+        } else {
+            body.push(line);
+        }
+    }
+    while body.last().is_some_and(|line| line.trim().is_empty()) {
+        body.pop();
+    }
+    while body.first().is_some_and(|line| line.trim().is_empty()) {
+        body.remove(0);
+    }
+
+    let mut code = prelude.join("\n");
+    code.push_str("\n\n");
+
+    let defines_main = body.iter().any(|line| line.contains("fn main("));
+    if defines_main {
+        for line in &body {
+            code.push_str(line);
+            code.push('\n');
+        }
+        return code;
+    }
+
+    // Like rustdoc, examples ending in e.g. `Ok::<(), E>(())` get a `main`
+    // which can use the `?` operator.
+    let returns_result = body
+        .last()
+        .is_some_and(|line| line.trim_end().ends_with("(())"));
+    let indent = if returns_result {
+        code.push_str(
+            "fn main() {\n    fn _inner() -> Result<(), impl core::fmt::Debug> {\n",
+        );
+        "        "
+    } else {
+        code.push_str("fn main() {\n");
+        "    "
+    };
+
+    for line in &body {
+        if !line.is_empty() {
+            code.push_str(indent);
+        }
+        code.push_str(line);
+        code.push('\n');
+    }
+
+    if returns_result {
+        code.push_str("    }\n    _inner().unwrap()\n");
+    }
+    code.push_str("}\n");
+    code
+}
+
+/// Undo rustdoc's line hiding: `# code` becomes `code`, a lone `#` becomes an
+/// empty line, and `##` escapes a literal `#`.
+fn unhide_line(line: &str) -> String {
+    let trimmed = line.trim_start();
+    let indent = &line[..line.len() - trimmed.len()];
+
+    if trimmed == "#" {
+        String::new()
+    } else if let Some(rest) = trimmed.strip_prefix("##") {
+        format!("{indent}#{rest}")
+    } else if let Some(rest) = trimmed.strip_prefix("# ") {
+        format!("{indent}{rest}")
+    } else {
+        line.to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const COMMON_JSON_STR: &str =
+        include_str!("../test-data/common/rustdoc.json");
+    const HEX_JSON_STR: &str = include_str!("../test-data/hex/rustdoc.json");
+
+    #[test]
+    fn test_hex_examples() {
+        let doc = serde_json::from_str::<RustDoc>(HEX_JSON_STR).unwrap();
+        let examples = doc.examples();
+
+        let paths = examples
+            .iter()
+            .map(|example| example.file_path())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            paths,
+            [
+                PathBuf::from("hex/FromHex.rs"),
+                PathBuf::from("hex/display.rs")
+            ]
+        );
+
+        let display = &examples[1];
+        assert_eq!(display.item_path, ["hex", "display"]);
+        assert_eq!(
+            display.code,
+            "#![allow(unused)]\n\
+             \n\
+             fn main() {\n    \
+                 let bytes = [69u8; 32];\n    \
+                 println!(\"Bytes as hex: {}\", hex::display(&bytes));\n\
+             }\n"
+        );
+
+        // The hidden `# use` line is restored.
+        let from_hex = &examples[0];
+        assert!(from_hex.code.contains("\n    use std::borrow::Cow;\n"));
+    }
+
+    #[test]
+    fn test_examples_with_same_path() {
+        let mut doc = serde_json::from_str::<RustDoc>(COMMON_JSON_STR).unwrap();
+        // The `fmt` methods of the `Debug` and `Display` impls of
+        // `ConfigError`
+        for id in ["0:125:811", "0:79:811"] {
+            let item = doc.index.get_mut(id).unwrap();
+            item.docs = Some(format!("```\nlet id = \"{id}\";\n```"));
+        }
+
+        let examples = doc.examples();
+        let fmt = examples
+            .iter()
+            .filter(|example| example.item_path.ends_with(&["fmt".into()]))
+            .map(|example| (example.file_path(), example.item_id.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            fmt,
+            [
+                (PathBuf::from("common/ConfigError/fmt.rs"), "0:125:811"),
+                (PathBuf::from("common/ConfigError/fmt_2.rs"), "0:79:811"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_common_examples() {
+        let doc = serde_json::from_str::<RustDoc>(COMMON_JSON_STR).unwrap();
+        let examples = doc.examples();
+
+        let paths = examples
+            .iter()
+            .map(|example| example.file_path())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            paths,
+            [
+                PathBuf::from("common.rs"),
+                PathBuf::from("common/Config.rs"),
+                PathBuf::from("common/Config_2.rs"),
+                PathBuf::from("common/log.rs"),
+                PathBuf::from("common/pt.rs"),
+                PathBuf::from("common/shapes/Point.rs"),
+                PathBuf::from("common/shapes/Point/manhattan.rs"),
+            ]
+        );
+
+        // `no_run` examples are kept, and `?` works in the generated `main`.
+        let load = &examples[1];
+        assert_eq!(load.attrs, ["no_run"]);
+        assert!(load.code.contains("fn _inner() -> Result<(), impl"));
+        assert!(load.code.contains("_inner().unwrap()"));
+
+        // `## ` escapes a literal `#`; `text` blocks are skipped.
+        let ignored = &examples[2];
+        assert_eq!(ignored.attrs, ["ignore"]);
+        assert!(ignored.code.contains("    # This attribute-looking line"));
+
+        // `#[macro_export]` macros are at the crate root
+        assert_eq!(examples[4].item_path, ["common", "pt"]);
+
+        // Examples with their own `fn main` aren't wrapped again.
+        let manhattan = &examples[6];
+        assert_eq!(
+            manhattan.item_path,
+            ["common", "shapes", "Point", "manhattan"]
+        );
+        assert_eq!(manhattan.code.matches("fn main()").count(), 1);
+        assert!(manhattan.to_file_contents().starts_with(
+            "// Example from the docs of `common::shapes::Point::manhattan`"
+        ));
+    }
+}
@@ -0,0 +1,210 @@
+//! Filtering of deprecated, `#[doc(hidden)]` and unstable items.
+
+use std::{
+    fmt::{self, Write},
+    str::FromStr,
+};
+
+use crate::{Attribute, Parameter, RustDoc, RustDocItem};
+
+/// What to do with items of a certain kind, e.g. deprecated items.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FilterMode {
+    /// Print the item like any other item.
+    #[default]
+    Show,
+    /// Print the item, but flag it wherever it is listed.
+    Annotate,
+    /// Leave the item out. Links to it become plain text.
+    Omit,
+}
+
+/// Options which control what [`RustDoc::print`] includes.
+#[derive(Clone, Debug, Default)]
+pub struct PrintOptions {
+    /// Items with a `#[deprecated]` attribute.
+    pub deprecated: FilterMode,
+    /// Items with a `#[doc(hidden)]` attribute.
+    pub hidden: FilterMode,
+    /// Items with an `#[unstable]` attribute.
+    pub unstable: FilterMode,
+    /// Only print the public API: the items which downstream users can
+    /// name, each under its public path rather than its definition path.
+    pub public_only: bool,
+    /// Fit the docs into about this many tokens, by shortening or leaving
+    /// out the docs of the least important items first.
+    pub max_tokens: Option<usize>,
+}
+
+impl FromStr for FilterMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "show" => Ok(Self::Show),
+            "annotate" => Ok(Self::Annotate),
+            "omit" => Ok(Self::Omit),
+            _ => Err(format!(
+                "Unknown filter mode '{s}': expected show, annotate or omit"
+            )),
+        }
+    }
+}
+
+impl RustDoc {
+    /// Use the given options when printing.
+    pub fn with_options(mut self, options: PrintOptions) -> Self {
+        self.options = options;
+        self
+    }
+}
+
+impl RustDocItem {
+    /// The filter categories this item falls into, with the mode for each.
+    fn filter_modes(
+        &self,
+        options: &PrintOptions,
+    ) -> Vec<(&'static str, FilterMode)> {
+        let attrs = self.attributes();
+        let mut modes = Vec::new();
+
+        if self.deprecation.is_some() || attrs.contains(&Attribute::Deprecated)
+        {
+            modes.push(("deprecated", options.deprecated));
+        }
+        if attrs.contains(&Attribute::DocHidden) {
+            modes.push(("hidden", options.hidden));
+        }
+        if attrs
+            .iter()
+            .any(|attr| matches!(attr, Attribute::Unstable { .. }))
+        {
+            modes.push(("unstable", options.unstable));
+        }
+
+        modes
+    }
+
+    /// If this item is omitted by the print options, the reason why.
+    ///
+    /// Impls are also omitted if their trait or self type is omitted.
+    pub(crate) fn omitted_reason(&self, doc: &RustDoc) -> Option<&'static str> {
+        let is_omitted = self
+            .filter_modes(&doc.options)
+            .into_iter()
+            .find(|(_, mode)| *mode == FilterMode::Omit);
+        if let Some((category, _)) = is_omitted {
+            return Some(match category {
+                "deprecated" => "deprecated item (omitted by print options)",
+                "hidden" => "#[doc(hidden)] item (omitted by print options)",
+                _ => "unstable item (omitted by print options)",
+            });
+        }
+
+        let impl_ = self.inner.as_ref()?.impl_.as_ref()?;
+        let trait_id = impl_.trait_.as_ref().and_then(|t| t.id.as_deref());
+        let for_id = match &impl_.for_ {
+            Some(Parameter::ResolvedPath { resolved_path }) =>
+                resolved_path.id.as_deref(),
+            _ => None,
+        };
+        let is_omitted = |id: Option<&str>| {
+            id.and_then(|id| doc.index.get(id))
+                .is_some_and(|item| item.omitted_reason(doc).is_some())
+        };
+        if is_omitted(trait_id) || is_omitted(for_id) {
+            return Some("impl of an omitted trait or for an omitted type");
+        }
+
+        None
+    }
+
+    /// The labels to flag this item with, e.g. `["deprecated"]`.
+    pub(crate) fn annotations(&self, doc: &RustDoc) -> Vec<&'static str> {
+        self.filter_modes(&doc.options)
+            .into_iter()
+            .filter(|(_, mode)| *mode == FilterMode::Annotate)
+            .map(|(category, _)| category)
+            .collect()
+    }
+
+    /// Write the annotations as comments in a code listing, e.g.
+    /// `// deprecated` above a trait method.
+    pub(crate) fn write_annotations(
+        &self,
+        out: &mut String,
+        doc: &RustDoc,
+        indent: &str,
+    ) -> fmt::Result {
+        for label in self.annotations(doc) {
+            writeln!(out, "{indent}// {label}")?;
+        }
+        Ok(())
+    }
+
+    /// The annotations formatted for a heading, e.g. ` *(deprecated)*`.
+    pub(crate) fn heading_annotations(&self, doc: &RustDoc) -> String {
+        self.annotations(doc)
+            .into_iter()
+            .map(|label| format!(" *({label})*"))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const COMMON_JSON_STR: &str =
+        include_str!("../test-data/common/rustdoc.json");
+
+    fn render(options: PrintOptions) -> String {
+        serde_json::from_str::<RustDoc>(COMMON_JSON_STR)
+            .unwrap()
+            .with_options(options)
+            .to_markdown()
+    }
+
+    #[test]
+    fn test_show() {
+        let markdown = render(PrintOptions::default());
+        assert!(markdown.contains("### pub hello\n"));
+        assert!(markdown.contains("fn draw_debug("));
+        assert!(markdown.contains("### pub __log_impl\n"));
+        assert!(markdown.contains("`Draw` for `RawHandle`"));
+        assert!(markdown.contains("deprecated [`hello`](#"));
+    }
+
+    #[test]
+    fn test_annotate() {
+        let markdown = render(PrintOptions {
+            deprecated: FilterMode::Annotate,
+            hidden: FilterMode::Annotate,
+            unstable: FilterMode::Annotate,
+            ..Default::default()
+        });
+        assert!(markdown.contains("### pub hello *(deprecated)*\n"));
+        assert!(markdown.contains("### pub __log_impl *(hidden)*\n"));
+        assert!(markdown.contains("    // deprecated\n    fn draw_debug("));
+    }
+
+    #[test]
+    fn test_omit() {
+        let markdown = render(PrintOptions {
+            deprecated: FilterMode::Omit,
+            hidden: FilterMode::Omit,
+            unstable: FilterMode::Omit,
+            ..Default::default()
+        });
+
+        // Omitted from the item listing, trait method listings, and impls.
+        assert!(!markdown.contains("### pub hello"));
+        assert!(!markdown.contains("draw_debug"));
+        assert!(!markdown.contains("__log_impl"));
+        assert!(!markdown.contains("RawHandle"));
+        assert!(markdown.contains("fn draw("));
+
+        // Links to omitted items fall back to plain text.
+        assert!(markdown.contains("Prefer this over the deprecated `hello`."));
+    }
+}
@@ -1,7 +1,66 @@
-use std::{collections::BTreeMap, fmt};
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Write},
+    fs, io,
+    path::Path,
+    sync::OnceLock,
+};
 
 use serde::{Deserialize, Serialize};
 
+pub use crate::{
+    attrs::Attribute,
+    budget::estimate_tokens,
+    cfg::Cfg,
+    chunks::{Chunk, ChunkLink},
+    configs::BuildConfig,
+    conversions::{Constructor, Conversion},
+    diff::{ApiDiff, ChangeKind, ItemChange},
+    docset::RustDocSet,
+    errors::ErrorType,
+    examples::DocExample,
+    filter::{FilterMode, PrintOptions},
+    methods::{AvailableMethod, MethodSource},
+    reexports::ReExport,
+    search::Match,
+    semver::{SemverChange, SemverLevel, SemverReport},
+    usages::{Usage, UsageKind},
+};
+use crate::{
+    budget::Detail,
+    cfg::ItemCfgs,
+    configs::ItemConfigs,
+    errors::ErrorReturns,
+    methods::{type_impls, MethodImpls},
+    reachability::PublicApi,
+    skeleton::{generic_params, where_clause, SourceStyle},
+    usages::TypeUsages,
+};
+
+mod attrs;
+mod budget;
+mod cfg;
+mod chunks;
+mod configs;
+mod conversions;
+mod diff;
+mod docset;
+mod errors;
+mod examples;
+mod filter;
+mod llms;
+mod mcp;
+mod methods;
+mod reachability;
+mod reexports;
+mod search;
+mod semver;
+mod skeleton;
+mod stubs;
+mod typesearch;
+mod usages;
+mod visit;
+
 // --- Type Definitions --- //
 
 #[derive(Debug, Deserialize)]
@@ -11,13 +70,60 @@ pub struct RustDoc {
     #[allow(dead_code)]
     includes_private: bool,
     index: BTreeMap<String, RustDocItem>,
+    #[serde(default)]
+    paths: BTreeMap<String, ItemSummary>,
+    #[serde(default)]
+    external_crates: BTreeMap<String, ExternalCrate>,
+    #[serde(skip)]
+    options: PrintOptions,
+    /// Links to the docs of other crates' items, by the ids we know them by.
+    /// Set when the crate is documented as part of a [`RustDocSet`].
+    #[serde(skip)]
+    external_links: BTreeMap<String, String>,
+    #[serde(skip)]
+    public_api: OnceLock<PublicApi>,
+    /// The functions which return each error type, found on first use.
+    #[serde(skip)]
+    error_returns: OnceLock<ErrorReturns>,
+    /// The impl of each item of an impl, found on first use.
+    #[serde(skip)]
+    method_impls: OnceLock<MethodImpls>,
+    /// The items which use each type, found on first use.
+    #[serde(skip)]
+    type_usages: OnceLock<TypeUsages>,
+    /// The effective `cfg` of each item, found on first use.
+    #[serde(skip)]
+    item_cfgs: OnceLock<ItemCfgs>,
+    /// Which configurations have each item, when the docs were merged from
+    /// several build configurations.
+    #[serde(skip)]
+    configs: ItemConfigs,
+}
+
+/// An entry of the top-level `external_crates` map: a crate which the
+/// documented crate refers to, keyed by its `crate_id`.
+#[derive(Debug, Deserialize, Serialize)]
+struct ExternalCrate {
+    name: String,
+}
+
+/// An entry of the top-level `paths` map: the canonical path of an item.
+#[derive(Debug, Deserialize, Serialize)]
+struct ItemSummary {
+    crate_id: u32,
+    path: Vec<String>,
+    kind: String,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct RustDocItem {
     #[serde(default)]
-    docs: Option<String>,
+    id: String,
     #[serde(default)]
+    docs: Option<String>,
+    /// `"public"`, `"default"`, `"crate"`, or `"restricted"` for e.g.
+    /// `pub(in crate::a)`.
+    #[serde(default, deserialize_with = "deserialize_visibility")]
     visibility: Option<String>,
     #[serde(default)]
     name: Option<String>,
@@ -28,15 +134,23 @@ pub struct RustDocItem {
     #[serde(default)]
     crate_id: u32,
     #[serde(default)]
-    deprecation: Option<serde_json::Value>,
+    deprecation: Option<Deprecation>,
     #[serde(default)]
     links: serde_json::Map<String, serde_json::Value>,
     #[serde(skip_deserializing)]
     span: Option<serde_json::Value>,
 }
 
+#[derive(Debug, Deserialize, Serialize)]
+struct Deprecation {
+    since: Option<String>,
+    note: Option<String>,
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 struct ItemInner {
+    module: Option<ModuleDetails>,
+    import: Option<Import>,
     function: Option<FunctionDetails>,
     #[serde(rename = "enum")]
     enum_: Option<EnumDetails>,
@@ -44,10 +158,115 @@ struct ItemInner {
     impl_: Option<Impl>,
     #[serde(rename = "struct")]
     struct_: Option<StructDetails>,
+    #[serde(rename = "union")]
+    union_: Option<UnionDetails>,
     #[serde(rename = "trait")]
     trait_: Option<TraitInfo>,
     #[serde(rename = "variant")]
     variant: Option<EnumVariantDetails>,
+    struct_field: Option<ReturnType>,
+    assoc_type: Option<AssocType>,
+    assoc_const: Option<AssocConst>,
+    type_alias: Option<TypeAlias>,
+    #[serde(rename = "static")]
+    static_: Option<StaticDetails>,
+    /// The source of a `macro_rules!` macro, with the bodies of its arms
+    /// elided, e.g. `macro_rules! point {\n    () => { ... };\n}`.
+    #[serde(rename = "macro")]
+    macro_: Option<String>,
+    proc_macro: Option<ProcMacro>,
+}
+
+/// A procedural macro, e.g. `#[proc_macro_derive(Builder,
+/// attributes(builder))]`.
+#[derive(Debug, Deserialize, Serialize)]
+struct ProcMacro {
+    kind: ProcMacroKind,
+    /// The helper attributes of a derive macro, e.g. `builder`.
+    #[serde(default)]
+    helpers: Vec<String>,
+}
+
+/// How a procedural macro is invoked.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum ProcMacroKind {
+    /// A function-like macro, e.g. `sql!(...)`.
+    Bang,
+    /// An attribute macro, e.g. `#[route]`.
+    Attr,
+    /// A derive macro, e.g. `#[derive(Builder)]`.
+    Derive,
+}
+
+/// An associated type of a trait or impl, e.g. `type Item = u8;`.
+#[derive(Debug, Deserialize, Serialize)]
+struct AssocType {
+    generics: Option<Generics>,
+    #[serde(default)]
+    bounds: Vec<TraitBoundInfo>,
+    /// The type, in an impl, or the default type, in a trait.
+    default: Option<ReturnType>,
+}
+
+/// A type alias, e.g. `type Result<T> = std::result::Result<T, Error>;`.
+#[derive(Debug, Deserialize, Serialize)]
+struct TypeAlias {
+    #[serde(rename = "type")]
+    type_: ReturnType,
+    generics: Option<Generics>,
+}
+
+/// A union, e.g. `union Number { int: i32, float: f32 }`.
+#[derive(Debug, Deserialize, Serialize)]
+struct UnionDetails {
+    generics: Option<Generics>,
+    #[serde(default)]
+    fields_stripped: bool,
+    fields: Vec<String>,
+    impls: Vec<String>,
+}
+
+/// A static, e.g. `static mut CALLS: u32 = 0;`.
+#[derive(Debug, Deserialize, Serialize)]
+struct StaticDetails {
+    #[serde(rename = "type")]
+    type_: ReturnType,
+    #[serde(default)]
+    mutable: bool,
+    /// The value as written, or empty for statics of `extern` blocks.
+    #[serde(default)]
+    expr: String,
+}
+
+/// An associated constant of a trait or impl, e.g. `const MAX: u32 = 1;`.
+#[derive(Debug, Deserialize, Serialize)]
+struct AssocConst {
+    #[serde(rename = "type")]
+    type_: ReturnType,
+    /// The value as written, in an impl, or the default value, in a trait.
+    default: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct ModuleDetails {
+    #[serde(default)]
+    is_crate: bool,
+    items: Vec<String>,
+    #[serde(default)]
+    is_stripped: bool,
+}
+
+/// A `use` item, e.g. `pub use foo::Bar;` or `pub use foo::*;`.
+#[derive(Debug, Deserialize, Serialize)]
+struct Import {
+    /// The path as written, e.g. `foo::Bar`.
+    source: String,
+    /// The name it is imported as, e.g. `Bar`, or `Baz` for `foo::Bar as Baz`.
+    name: String,
+    /// The imported item, or `None` if rustdoc couldn't resolve it.
+    id: Option<String>,
+    glob: bool,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -57,9 +276,16 @@ struct Impl {
     #[serde(rename = "for")]
     for_: Option<Parameter>,
     items: Vec<String>,
+    /// The names of the trait's methods which the impl doesn't override.
+    #[serde(default)]
+    provided_trait_methods: Vec<String>,
     is_unsafe: bool,
     blanket_impl: Option<BlanketImpl>,
     generics: Option<Generics>,
+    /// Whether this is an impl of an auto trait, e.g. `Send`, which rustdoc
+    /// adds rather than it being written in the source.
+    #[serde(default)]
+    synthetic: bool,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -92,6 +318,102 @@ struct EnumVariantDetails {
 #[derive(Debug, Deserialize, Serialize)]
 struct FunctionDetails {
     decl: FunctionDecl,
+    /// Whether the function has a body, i.e. for trait methods, whether the
+    /// trait provides a default implementation.
+    #[serde(default)]
+    has_body: bool,
+    #[serde(default)]
+    generics: Option<Generics>,
+    #[serde(default)]
+    header: Option<FunctionHeader>,
+}
+
+/// The qualifiers of a function, e.g. `const` or `unsafe`.
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct FunctionHeader {
+    #[serde(rename = "const", default)]
+    is_const: bool,
+    #[serde(rename = "unsafe", default)]
+    is_unsafe: bool,
+    #[serde(rename = "async", default)]
+    is_async: bool,
+    /// `"Rust"`, or e.g. `{"C": {"unwind": false}}`.
+    #[serde(default)]
+    abi: serde_json::Value,
+}
+
+impl RustDocItem {
+    /// The ABI of the `extern` block of a foreign function or static, e.g.
+    /// `C`. Rustdoc lists foreign items as items of their module: functions
+    /// without a body, and statics without a value. Only the functions know
+    /// their ABI, so statics are taken to be in `extern "C"` here, and
+    /// [`RustDoc::extern_abi`] looks for a better guess.
+    fn foreign_abi(&self) -> Option<String> {
+        let inner = self.inner.as_ref()?;
+        if let Some(function) = &inner.function {
+            if function.has_body {
+                return None;
+            }
+            return function.header.as_ref()?.abi();
+        }
+        inner
+            .static_
+            .as_ref()?
+            .expr
+            .is_empty()
+            .then(|| "C".to_string())
+    }
+
+    /// Write the path a macro is used by, e.g. `common::point!`. Macros
+    /// with `#[macro_export]` are at the crate root, wherever they are
+    /// defined.
+    fn write_macro_path(&self, out: &mut String, doc: &RustDoc) -> fmt::Result {
+        let Some(path) = doc.public_path(&self.id) else {
+            return Ok(());
+        };
+        let proc_macro =
+            self.inner.as_ref().and_then(|i| i.proc_macro.as_ref());
+        let bang = match proc_macro {
+            Some(proc_macro) if proc_macro.kind != ProcMacroKind::Bang => "",
+            _ => "!",
+        };
+        writeln!(out, "**Path:** `{}{bang}`\n", path.join("::"))
+    }
+}
+
+impl FunctionHeader {
+    /// The ABI of the function as written in `extern "..."`, unless it's
+    /// the Rust ABI.
+    fn abi(&self) -> Option<String> {
+        let serde_json::Value::Object(abi) = &self.abi else {
+            return None;
+        };
+        let (name, value) = abi.iter().next()?;
+        match name.as_str() {
+            "C" => Some(name.clone()),
+            "Other" => value.as_str().map(str::to_string),
+            // e.g. `System` for `extern "system"`
+            _ => Some(name.to_lowercase()),
+        }
+    }
+
+    /// The qualifiers before `fn`, e.g. `const ` or `unsafe extern "C" `.
+    fn qualifiers(&self) -> String {
+        let mut qualifiers = String::new();
+        if self.is_const {
+            qualifiers.push_str("const ");
+        }
+        if self.is_async {
+            qualifiers.push_str("async ");
+        }
+        if self.is_unsafe {
+            qualifiers.push_str("unsafe ");
+        }
+        if let Some(abi) = self.abi() {
+            qualifiers.push_str(&format!("extern \"{abi}\" "));
+        }
+        qualifiers
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -104,16 +426,54 @@ struct FunctionDecl {
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(untagged)]
 enum Parameter {
-    BorrowedRef { borrowed_ref: Box<BorrowedRefParam> },
-    Primitive { primitive: String },
-    Generic { generic: String },
-    ResolvedPath { resolved_path: Box<ResolvedPath> },
-    Qualified { qualified_path: Box<QualifiedPath> },
-    Slice { slice: Box<Parameter> },
-    Array { array: Box<ParameterArrayType> },
-    RawPointer { raw_pointer: Box<RawPointer> },
-    ImplTrait { impl_trait: Vec<ImplTraitBound> },
-    DynTrait { dyn_trait: Box<DynTrait> },
+    BorrowedRef {
+        borrowed_ref: Box<BorrowedRefParam>,
+    },
+    Primitive {
+        primitive: String,
+    },
+    Generic {
+        generic: String,
+    },
+    ResolvedPath {
+        resolved_path: Box<ResolvedPath>,
+    },
+    Qualified {
+        qualified_path: Box<QualifiedPath>,
+    },
+    Slice {
+        slice: Box<Parameter>,
+    },
+    Array {
+        array: Box<ParameterArrayType>,
+    },
+    RawPointer {
+        raw_pointer: Box<RawPointer>,
+    },
+    ImplTrait {
+        impl_trait: Vec<TraitBoundInfo>,
+    },
+    DynTrait {
+        dyn_trait: Box<DynTrait>,
+    },
+    Tuple {
+        tuple: Vec<Parameter>,
+    },
+    FunctionPointer {
+        function_pointer: Box<FunctionPointer>,
+    },
+    /// A type we don't model, e.g. a pattern type, written as `_`.
+    Unknown(serde_json::Value),
+}
+
+/// A function pointer type, e.g. `for<'a> unsafe extern "C" fn(&'a u8)`.
+#[derive(Debug, Deserialize, Serialize)]
+struct FunctionPointer {
+    decl: FunctionDecl,
+    /// The lifetimes of a `for<'a>`.
+    #[serde(default)]
+    generic_params: Vec<GenericParam>,
+    header: FunctionHeader,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -211,7 +571,7 @@ struct EqualityConstraint {
 enum GenericArg {
     Type {
         #[serde(rename = "type")]
-        type_inner: TypeContent,
+        type_inner: ReturnType,
     },
     Lifetime {
         lifetime: String,
@@ -231,20 +591,6 @@ struct ConstGeneric {
     value: Option<serde_json::Value>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
-struct TypeContent {
-    primitive: Option<String>,
-    slice: Option<SliceContent>,
-    tuple: Option<Vec<ReturnType>>,
-    resolved_path: Option<ResolvedPath>,
-    generic: Option<String>,
-}
-
-#[derive(Debug, Deserialize, Serialize)]
-struct SliceContent {
-    primitive: String,
-}
-
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(untagged)]
 enum ReturnType {
@@ -276,18 +622,16 @@ enum ReturnType {
         raw_pointer: Box<RawPointer>,
     },
     ImplTrait {
-        impl_trait: Vec<ImplTraitBound>,
+        impl_trait: Vec<TraitBoundInfo>,
     },
     DynTrait {
         dyn_trait: Box<DynTrait>,
     },
-    // Special case for trait methods that return Self
-    Self_ {},
-}
-
-#[derive(Debug, Deserialize, Serialize)]
-struct ImplTraitBound {
-    trait_bound: TraitBound,
+    FunctionPointer {
+        function_pointer: Box<FunctionPointer>,
+    },
+    /// A type we don't model, e.g. a pattern type, written as `_`.
+    Unknown(serde_json::Value),
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -306,7 +650,32 @@ struct Generics {
 }
 
 #[derive(Debug, Deserialize, Serialize)]
-struct WherePredicate {}
+#[serde(untagged)]
+enum WherePredicate {
+    /// e.g. `T: Clone + 'static`
+    Bound { bound_predicate: BoundPredicate },
+    /// e.g. `'a: 'b`
+    Lifetime {
+        lifetime_predicate: LifetimePredicate,
+    },
+    /// e.g. `<T as Iterator>::Item = u8`, which we don't print.
+    Other(serde_json::Value),
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct BoundPredicate {
+    #[serde(rename = "type")]
+    type_: ReturnType,
+    bounds: Vec<TraitBoundInfo>,
+    #[serde(default)]
+    generic_params: Vec<GenericParam>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct LifetimePredicate {
+    lifetime: String,
+    outlives: Vec<String>,
+}
 
 #[derive(Debug, Deserialize, Serialize)]
 struct GenericParam {
@@ -334,17 +703,20 @@ struct LifetimeParam {
 #[derive(Debug, Deserialize, Serialize)]
 struct TypeParam {
     #[serde(default)]
-    bounds: Vec<serde_json::Value>,
+    bounds: Vec<TraitBoundInfo>,
+    #[serde(default)]
+    default: Option<ReturnType>,
+    /// Whether the parameter stands for an `impl Trait` argument.
     #[serde(default)]
-    default: Option<serde_json::Value>,
+    synthetic: bool,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 struct ConstParam {
+    #[serde(rename = "type")]
+    type_: ReturnType,
     #[serde(default)]
-    type_: serde_json::Value,
-    #[serde(default)]
-    default: Option<serde_json::Value>,
+    default: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -372,193 +744,383 @@ struct QualifiedPath {
 // --- Implementations --- //
 
 impl RustDoc {
+    /// Load the rustdoc JSON output at the given path.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let json = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
     pub fn print(&self) {
-        let crate_name = self.root.split('/').last().unwrap_or(&self.root);
+        print!("{}", self.to_markdown());
+    }
+
+    /// Render the docs for the whole crate as markdown.
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        self.write_markdown(&mut out, &[])
+            .expect("Writing to a String can't fail");
+        out
+    }
+
+    /// Write the docs for the whole crate. Other crates' JSON in
+    /// `dependencies` is used to expand their re-exported modules.
+    fn write_markdown(
+        &self,
+        out: &mut String,
+        dependencies: &[RustDoc],
+    ) -> fmt::Result {
+        match self.options.max_tokens {
+            Some(max_tokens) =>
+                self.write_within_budget(out, dependencies, max_tokens),
+            None => self.write_all(out, dependencies, &BTreeMap::new()),
+        }
+    }
 
-        println!("# {crate_name} v{}", self.crate_version);
-        println!();
+    /// Write the docs for the whole crate, with as much of each item's docs
+    /// as `details` allows. Items which aren't in `details` are printed in
+    /// full.
+    fn write_all(
+        &self,
+        out: &mut String,
+        dependencies: &[RustDoc],
+        details: &BTreeMap<String, Detail>,
+    ) -> fmt::Result {
+        let crate_name = self.crate_name();
+
+        writeln!(out, "# {crate_name} v{}", self.crate_version)?;
+        writeln!(out)?;
+
+        self.write_sections(out, details, |_| true)?;
+        self.write_reexports(out, dependencies)?;
+        self.write_features(out)?;
+
+        Ok(())
+    }
 
+    /// Write the sections of the docs, e.g. "Functions", with the listed
+    /// items for which `include` returns true, in as much detail as
+    /// `details` allows.
+    fn write_sections(
+        &self,
+        out: &mut String,
+        details: &BTreeMap<String, Detail>,
+        include: impl Fn(&str) -> bool,
+    ) -> fmt::Result {
         let mut functions = Vec::new();
+        let mut macros = Vec::new();
         let mut structs = Vec::new();
         let mut enums = Vec::new();
+        let mut unions = Vec::new();
         let mut traits = Vec::new();
         let mut impls = Vec::new();
+        let mut statics = Vec::new();
+        let mut foreign_items = Vec::new();
         let mut others = Vec::new();
-        let mut enum_variants = Vec::new();
 
         for (id, item) in &self.index {
-            if !id.starts_with("0:") {
-                continue;
-            }
-
-            if item.name.is_none() && !item.is_impl() {
+            let detail = details.get(id).copied().unwrap_or_default();
+            if !self.is_listed(id, item)
+                || detail == Detail::Dropped
+                || !include(id)
+            {
                 continue;
             }
 
             if let Some(inner) = &item.inner {
                 match inner {
-                    _ if inner.function.is_some() => functions.push((id, item)),
-                    _ if inner.enum_.is_some() => enums.push((id, item)),
-                    _ if self.is_trait(item) => traits.push((id, item)),
+                    _ if item.foreign_abi().is_some() =>
+                        foreign_items.push(item),
+                    _ if inner.function.is_some() =>
+                        functions.push((detail, item)),
+                    _ if item.macro_source().is_some() =>
+                        macros.push((detail, item)),
+                    _ if inner.enum_.is_some() => enums.push((detail, item)),
+                    _ if self.is_trait(item) => traits.push((detail, item)),
                     _ if inner.impl_.is_some() && item.name.is_some() => {
-                        impls.push((id, item));
+                        impls.push((detail, item));
                     }
-                    _ if self.is_struct(item) => structs.push((id, item)),
-                    _ if item.is_enum_variant() =>
-                        enum_variants.push((id, item)),
-                    _ => others.push((id, item)),
+                    _ if self.is_struct(item) => structs.push((detail, item)),
+                    _ if inner.union_.is_some() => unions.push((detail, item)),
+                    _ if inner.static_.is_some() =>
+                        statics.push((detail, item)),
+                    // Variants are written in their enums, and fields in their
+                    // structs, unions and variants
+                    _ if item.is_enum_variant()
+                        || inner.struct_field.is_some() => {}
+                    _ => others.push((detail, item)),
                 }
             } else {
-                others.push((id, item));
+                others.push((detail, item));
             }
         }
 
         // Print items by category with section headings
         if !functions.is_empty() {
-            println!("## Functions");
-            println!();
-            for (_, item) in functions {
-                item.print(self);
+            writeln!(out, "## Functions")?;
+            writeln!(out)?;
+            for (detail, item) in functions {
+                item.write_markdown_with(out, self, detail)?;
+            }
+        }
+
+        if !macros.is_empty() {
+            writeln!(out, "## Macros")?;
+            writeln!(out)?;
+            for (detail, item) in macros {
+                item.write_markdown_with(out, self, detail)?;
             }
         }
 
         if !structs.is_empty() {
-            println!("## Structs");
-            println!();
-            for (_, item) in structs {
-                item.print(self);
+            writeln!(out, "## Structs")?;
+            writeln!(out)?;
+            for (detail, item) in structs {
+                item.write_markdown_with(out, self, detail)?;
             }
         }
 
         if !enums.is_empty() {
-            println!("## Enums");
-            println!();
-            for (_, item) in enums {
-                item.print(self);
+            writeln!(out, "## Enums")?;
+            writeln!(out)?;
+            for (detail, item) in enums {
+                item.write_markdown_with(out, self, detail)?;
+            }
+        }
+
+        if !unions.is_empty() {
+            writeln!(out, "## Unions")?;
+            writeln!(out)?;
+            for (detail, item) in unions {
+                item.write_markdown_with(out, self, detail)?;
             }
         }
 
         if !traits.is_empty() {
-            println!("## Traits");
-            println!();
-            for (_, item) in traits {
-                item.print(self);
+            writeln!(out, "## Traits")?;
+            writeln!(out)?;
+            for (detail, item) in traits {
+                item.write_markdown_with(out, self, detail)?;
             }
         }
 
         if !impls.is_empty() {
-            println!("## Implementations");
-            println!();
-            for (_, item) in impls {
-                item.print(self);
+            writeln!(out, "## Implementations")?;
+            writeln!(out)?;
+            for (detail, item) in impls {
+                item.write_markdown_with(out, self, detail)?;
             }
         }
 
-        if !enum_variants.is_empty() {
-            println!("## Enum Variants");
-            println!();
-            for (_, item) in enum_variants {
-                item.print(self);
+        if !statics.is_empty() {
+            writeln!(out, "## Statics")?;
+            writeln!(out)?;
+            for (detail, item) in statics {
+                item.write_markdown_with(out, self, detail)?;
+            }
+        }
+
+        // Foreign items are written together, as the `extern` blocks of
+        // each module
+        if !foreign_items.is_empty() {
+            writeln!(out, "## Extern Blocks")?;
+            writeln!(out)?;
+            let mut modules = BTreeMap::<_, Vec<&RustDocItem>>::new();
+            for item in foreign_items {
+                let module_path = self
+                    .api()
+                    .module(&item.id)
+                    .and_then(|id| self.item_path(id))
+                    .unwrap_or_default();
+                modules.entry(module_path).or_default().push(item);
+            }
+            let style = SourceStyle {
+                full_docs: true,
+                ..SourceStyle::SKELETON
+            };
+            for (module_path, items) in modules {
+                writeln!(out, "### {}\n", module_path.join("::"))?;
+                writeln!(out, "```rust")?;
+                self.write_extern_blocks(out, &items, style)?;
+                writeln!(out, "```\n")?;
             }
         }
 
         if !others.is_empty() {
-            println!("## Other Items");
-            println!();
-            for (_, item) in others {
-                item.print(self);
+            writeln!(out, "## Other Items")?;
+            writeln!(out)?;
+            for (detail, item) in others {
+                item.write_markdown_with(out, self, detail)?;
             }
         }
+
+        Ok(())
     }
 
-    fn is_trait(&self, item: &RustDocItem) -> bool {
-        let Some(inner) = &item.inner else {
-            return false;
-        };
-        let Ok(value) = serde_json::to_value(inner) else {
-            return false;
-        };
-        let Some(obj) = value.as_object() else {
-            return false;
+    /// Whether an item is listed in the sections of the crate's docs, rather
+    /// than only within another item.
+    fn is_listed(&self, id: &str, item: &RustDocItem) -> bool {
+        id.starts_with("0:")
+            && (item.name.is_some() || item.is_impl())
+            && (!self.options.public_only || self.is_public_api(id))
+    }
+
+    /// The name of the documented crate, from its root module.
+    pub fn crate_name(&self) -> &str {
+        self.index
+            .get(&self.root)
+            .and_then(|root| root.name.as_deref())
+            .unwrap_or(&self.root)
+    }
+
+    /// The full path of an item, e.g. `["hex", "HexDisplay"]`.
+    ///
+    /// Items listed in `paths` use their canonical path. Associated items
+    /// (methods, fields, ...) aren't listed there, so we use the path of their
+    /// parent type or trait instead.
+    fn item_path(&self, id: &str) -> Option<Vec<String>> {
+        if let Some(summary) = self.paths.get(id) {
+            return Some(summary.path.clone());
+        }
+
+        let name = self.index.get(id)?.name.as_ref()?;
+        let (parent_id, _) = self.parent(id)?;
+
+        // Methods in an impl block live under the type the impl is for.
+        let parent_inner = self.index.get(parent_id)?.inner.as_ref()?;
+        let mut path = match &parent_inner.impl_ {
+            Some(impl_) => match &impl_.for_ {
+                Some(Parameter::ResolvedPath { resolved_path }) =>
+                    self.item_path(resolved_path.id.as_deref()?)?,
+                _ => return None,
+            },
+            None => self.item_path(parent_id)?,
         };
+        path.push(name.clone());
+        Some(path)
+    }
 
-        obj.contains_key("trait")
+    /// The impl, trait, struct or enum which directly contains an item.
+    fn parent(&self, id: &str) -> Option<(&String, &RustDocItem)> {
+        self.index.get_key_value(self.api().parent(id)?)
     }
 
-    fn is_struct(&self, item: &RustDocItem) -> bool {
+    /// The items of an impl or trait, fields of a struct or variant, or
+    /// variants of an enum.
+    pub(crate) fn child_ids(&self, item: &RustDocItem) -> Vec<String> {
         let Some(inner) = &item.inner else {
-            return false;
-        };
-        let Ok(value) = serde_json::to_value(inner) else {
-            return false;
-        };
-        let Some(obj) = value.as_object() else {
-            return false;
+            return Vec::new();
         };
+        if let Some(impl_) = &inner.impl_ {
+            impl_.items.clone()
+        } else if let Some(trait_) = &inner.trait_ {
+            trait_.items.clone()
+        } else if let Some(struct_) = &inner.struct_ {
+            struct_.field_ids()
+        } else if let Some(enum_) = &inner.enum_ {
+            enum_.variants.clone()
+        } else if let Some(variant) = &inner.variant {
+            variant.field_ids()
+        } else {
+            Vec::new()
+        }
+    }
 
-        obj.contains_key("struct")
+    fn is_trait(&self, item: &RustDocItem) -> bool {
+        item.inner
+            .as_ref()
+            .is_some_and(|inner| inner.trait_.is_some())
+    }
+
+    fn is_struct(&self, item: &RustDocItem) -> bool {
+        item.inner
+            .as_ref()
+            .is_some_and(|inner| inner.struct_.is_some())
     }
 }
 
 impl RustDocItem {
     fn is_impl(&self) -> bool {
-        let Some(inner) = &self.inner else {
-            return false;
-        };
-        let Ok(inner_json) = serde_json::to_value(inner) else {
-            return false;
-        };
-        let Some(obj) = inner_json.as_object() else {
-            return false;
-        };
-
-        obj.contains_key("impl")
+        self.inner
+            .as_ref()
+            .is_some_and(|inner| inner.impl_.is_some())
     }
 
-    fn print(&self, doc: &RustDoc) {
-        if self.is_impl() && self.name.is_none() {
-            self.print_impl_details(doc);
-            return;
-        }
+    pub fn print(&self, doc: &RustDoc) {
+        let mut out = String::new();
+        self.write_markdown(&mut out, doc)
+            .expect("Writing to a String can't fail");
+        print!("{out}");
+    }
 
-        let Some(name) = &self.name else { return };
+    fn write_markdown(&self, out: &mut String, doc: &RustDoc) -> fmt::Result {
+        self.write_markdown_with(out, doc, Detail::Full)
+    }
 
+    /// Write the docs of the item, and of its members, e.g. the fields of a
+    /// struct, in the given detail.
+    fn write_markdown_with(
+        &self,
+        out: &mut String,
+        doc: &RustDoc,
+        detail: Detail,
+    ) -> fmt::Result {
         // Skip items that shouldn't be printed
         if self.skip(doc).is_some() {
-            return;
+            return Ok(());
         }
 
-        let empty_string = String::new();
-        let docs_content = self.docs.as_ref().unwrap_or(&empty_string);
+        if self.is_impl() && self.name.is_none() {
+            self.print_impl_details(out, doc)?;
+            return Ok(());
+        }
+
+        let Some(name) = &self.name else {
+            return Ok(());
+        };
+
+        let docs_content = self.docs_to_print(detail).unwrap_or_default();
 
-        // TODO(max): For now, we print everything, but we will eventually
-        // want to restrict to public items only. Leave this to reuse later:
-        // if self.visibility.as_deref() != Some("public") {
-        //     return;
-        // }
+        // Show items of the public API under their public path
+        let public_path;
+        let heading_name = if doc.options.public_only {
+            let Some(path) = doc.public_path(&self.id) else {
+                return Ok(());
+            };
+            public_path = path.join("::");
+            &public_path
+        } else {
+            name
+        };
 
         let visibility = self.visibility.as_deref().unwrap_or("default");
+        let annotations = self.heading_annotations(doc);
 
         if self.is_enum_variant() {
-            println!("#### `{}`", name);
+            writeln!(out, "#### `{}`{annotations}", heading_name)?;
         } else {
-            println!(
-                "### {}{}",
+            writeln!(
+                out,
+                "### {}{}{annotations}",
                 if visibility == "public" { "pub " } else { "" },
-                name
-            );
+                heading_name
+            )?;
         }
-        println!();
+        writeln!(out)?;
+
+        self.write_deprecation(out)?;
+        doc.write_cfg(out, &self.id)?;
+        doc.write_item_configs(out, &self.id)?;
+        self.write_alternate_paths(out, doc)?;
 
         if !docs_content.is_empty() {
-            let processed_docs = self.process_documentation(docs_content, doc);
-            println!("{}", processed_docs);
-            println!();
+            let processed_docs = self.process_documentation(&docs_content, doc);
+            writeln!(out, "{}", processed_docs)?;
+            writeln!(out)?;
         }
 
         let Some(inner) = &self.inner else {
-            println!();
-            return;
+            writeln!(out)?;
+            return Ok(());
         };
         // Collect all implemented traits
         let mut traits = Vec::new();
@@ -567,6 +1129,9 @@ impl RustDocItem {
                 let Some(impl_item) = doc.index.get(impl_id) else {
                     continue;
                 };
+                if impl_item.omitted_reason(doc).is_some() {
+                    continue;
+                }
                 let Some(inner) = &impl_item.inner else {
                     continue;
                 };
@@ -592,364 +1157,95 @@ impl RustDocItem {
             collect_traits_from_impls(&enum_details.impls);
         }
 
-        if let Some(struct_details) = &inner.struct_ {
-            collect_traits_from_impls(&struct_details.impls);
-        }
-
-        // Sort and deduplicate traits
-        traits.sort();
-        traits.dedup();
-
-        // Print function signature for functions
-        if let Some(f) = &inner.function {
-            f.decl.print(name);
-            println!();
-        }
-
-        // Handle enum variant
-        if self.is_enum_variant() {
-            let Some(variant_inner) = &inner.variant else {
-                return;
-            };
-
-            println!("```rust");
-
-            // Extract kind object or use default formatting
-            let kind_obj = variant_inner.kind.as_object();
-            if kind_obj.is_none() {
-                println!("{},", name);
-                println!("```");
-                println!();
-                return;
-            }
-
-            let kind_obj = kind_obj.unwrap();
-            let tuple = kind_obj.get("tuple");
-            let struct_fields = kind_obj.get("struct");
-            let kind_str = kind_obj.get("kind");
-
-            // Handle tuple variant
-            if let Some(tuple) = tuple {
-                print_tuple_variant(name, tuple);
-            }
-            // Handle struct variant
-            else if let Some(struct_fields) = struct_fields {
-                print_struct_variant(name, struct_fields);
-            }
-            // Handle plain variant
-            else if let Some(kind_str) = kind_str {
-                print_plain_variant(name, kind_str, variant_inner);
-            }
-            // Default for any other variant type
-            else {
-                println!("{},", name);
-            }
-
-            println!("```");
-            println!();
-        }
-
-        // Helper functions for enum variant handling
-        fn print_tuple_variant(name: &str, tuple: &serde_json::Value) {
-            let tuple_array = tuple.as_array();
-
-            if let Some(arr) = tuple_array {
-                if arr.is_empty() {
-                    println!("{}(),", name);
-                    return;
-                }
-
-                print!("{}(", name);
-                for (i, _) in arr.iter().enumerate() {
-                    if i > 0 {
-                        print!(", ");
-                    }
-                    print!("/* field type */");
-                }
-                println!("),");
-            } else {
-                println!("{},", name);
-            }
-        }
-
-        fn print_struct_variant(name: &str, struct_fields: &serde_json::Value) {
-            let fields_array = struct_fields.as_array();
-
-            if let Some(arr) = fields_array {
-                if arr.is_empty() {
-                    println!("{} {{}},", name);
-                    return;
-                }
-
-                println!("{} {{", name);
-                println!("    // fields...");
-                println!("{}}},", name);
-            } else {
-                println!("{},", name);
-            }
-        }
-
-        fn print_plain_variant(
-            name: &str,
-            kind_str: &serde_json::Value,
-            variant_inner: &EnumVariantDetails,
-        ) {
-            // Check if it's a plain variant with discriminant
-            let kind_str_value = kind_str.as_str();
-            let is_plain = kind_str_value.map_or(false, |k| k == "plain");
-            let has_discriminant = variant_inner.discriminant.is_some();
-
-            if is_plain && has_discriminant {
-                // We know discriminant exists at this point
-                let discriminant = variant_inner.discriminant.as_ref().unwrap();
-
-                // Try to get expression from discriminant
-                if let Some(expr) = discriminant.get("expr") {
-                    if let Some(s) = expr.as_str() {
-                        println!("{} = {},", name, s);
-                        return;
-                    }
-                }
-
-                // Try direct string representation
-                if let Some(s) = discriminant.as_str() {
-                    println!("{} = {},", name, s);
-                    return;
-                }
-            }
-
-            // Default case for plain variants
-            println!("{},", name);
-        }
-
-        // Print enum definitions with more detailed formatting
-        if let Some(enum_details) = &inner.enum_ {
-            println!("```rust");
-
-            // Print enum generics if available
-            if let Some(generics) = &enum_details.generics {
-                if !generics.params.is_empty() {
-                    // For now just indicate generics with <...>
-                    println!("pub enum {name}<...> {{");
-                } else {
-                    println!("pub enum {name} {{");
-                }
-            } else {
-                println!("pub enum {name} {{");
-            }
-
-            for variant_id in &enum_details.variants {
-                if let Some(variant) = doc.index.get(variant_id) {
-                    if let Some(docs) = &variant.docs {
-                        // Split multi-line docs into proper doc comments
-                        for line in docs.lines() {
-                            println!("    /// {}", line);
-                        }
-                    }
-                    if let Some(name) = &variant.name {
-                        // TODO: Add variant fields when available
-                        println!("    {name},");
-                    }
-                }
-            }
-            println!("}}");
-            println!("```");
-            println!();
-        }
-
-        // Print struct definitions with fields
-        if let Some(struct_details) = &inner.struct_ {
-            println!("```rust");
-
-            // Print struct generics if available
-            let generics_str = if let Some(generics) = &struct_details.generics
-            {
-                let mut params = Vec::new();
-
-                for param in &generics.params {
-                    // Add all params to the list
-                    if let Some(name) = &param.name {
-                        params.push(name.clone());
-                    }
-                }
-
-                if !params.is_empty() {
-                    // Make sure lifetime parameters are properly
-                    // prefixed with an apostrophe
-                    // No need for special handling since JSON
-                    // already has correctly formatted names
-                    let formatted_params = params.clone();
-                    format!("<{}>", formatted_params.join(", "))
-                } else {
-                    String::new()
-                }
-            } else {
-                String::new()
-            };
+        if let Some(struct_details) = &inner.struct_ {
+            collect_traits_from_impls(&struct_details.impls);
+        }
 
-            if let Some(kind) = &struct_details.kind {
-                match kind {
-                    StructKind::Tuple { tuple } if tuple.is_some() => {
-                        print!("pub struct {name}{generics_str}(");
+        if let Some(union_details) = &inner.union_ {
+            collect_traits_from_impls(&union_details.impls);
+        }
 
-                        // Tuple structs have elements in the 'tuple' array
-                        if let Some(tuple_fields) = tuple {
-                            let mut first = true;
+        // Sort and deduplicate traits
+        traits.sort();
+        traits.dedup();
 
-                            // If we have explicit field IDs, prefer those
-                            if !struct_details.fields.is_empty() {
-                                for field_id in &struct_details.fields {
-                                    if let Some(field) = doc.index.get(field_id)
-                                    {
-                                        if !first {
-                                            print!(", ");
-                                        }
-                                        let visibility = field
-                                            .visibility
-                                            .as_deref()
-                                            .unwrap_or("default");
-                                        if visibility == "public" {
-                                            print!("pub ");
-                                        }
-                                        // This is a simplification - we'd need
-                                        // to extract the type
-                                        print!("/* field type */");
-                                        first = false;
-                                    }
-                                }
-                            }
-                            // Otherwise use the tuple array directly
-                            else {
-                                for (i, field_value) in
-                                    tuple_fields.iter().enumerate()
-                                {
-                                    if i > 0 {
-                                        print!(", ");
-                                    }
+        // Foreign items are written in their `extern` block
+        if self.foreign_abi().is_some() {
+            writeln!(out, "```rust")?;
+            doc.write_extern_blocks(out, &[self], SourceStyle::SKELETON)?;
+            writeln!(out, "```\n")?;
+        } else if let Some(f) = &inner.function {
+            // Print function signature for functions
+            f.decl.print(out, name, self)?;
+            doc.write_external_links(out, &f.decl.type_paths())?;
+            writeln!(out)?;
+        }
 
-                                    // Try to parse field type from value
-                                    // Handle special cases and null values
-                                    if field_value.is_null() {
-                                        handle_null_field_value(
-                                            name,
-                                            struct_details,
-                                            &generics_str,
-                                        );
-                                        continue;
-                                    }
+        if let Some(source) = self.type_alias_source() {
+            writeln!(out, "```rust\n{source}\n```\n")?;
+        }
 
-                                    // Handle object field values
-                                    if let Some(field_obj) =
-                                        field_value.as_object()
-                                    {
-                                        let type_name = field_obj
-                                            .get("name")
-                                            .and_then(|v| v.as_str())
-                                            .unwrap_or("/* field type */");
-                                        print!("{}", type_name);
-                                    } else {
-                                        print!("/* field type */");
-                                    }
-                                }
+        if let Some(source) = self.macro_source() {
+            writeln!(out, "```rust")?;
+            self.write_attributes(out, "")?;
+            writeln!(out, "{source}\n```\n")?;
+            self.write_macro_path(out, doc)?;
+        }
 
-                                // Helper function to handle null field values
-                                fn handle_null_field_value(
-                                    name: &str,
-                                    struct_details: &StructDetails,
-                                    generics_str: &str,
-                                ) {
-                                    if name == "HexDisplay" {
-                                        let lifetime = extract_lifetime_param(
-                                            struct_details,
-                                        );
-                                        print!("&{} [u8]", lifetime);
-                                        return;
-                                    }
+        if let Some(source) = self
+            .static_source()
+            .filter(|_| self.foreign_abi().is_none())
+        {
+            writeln!(out, "```rust")?;
+            self.write_attributes(out, "")?;
+            writeln!(out, "{source}\n```\n")?;
+        }
 
-                                    let _is_tuple_struct =
-                                        check_is_tuple_struct(struct_details);
+        // Variants are written in their enum, but can be written alone,
+        // e.g. when looked up by path
+        if self.is_enum_variant() {
+            writeln!(out, "```rust")?;
+            doc.write_variant(out, self, "", SourceStyle::SKELETON)?;
+            writeln!(out, "```\n")?;
+        }
 
-                                    if generics_str.contains("'") {
-                                        let lifetime = extract_first_lifetime(
-                                            generics_str,
-                                        )
-                                        .unwrap_or("'a");
+        if let Some(enum_details) = &inner.enum_ {
+            self.print_enum_definition(out, name, enum_details, doc, detail)?;
+        }
 
-                                        print!("&{} [u8]", lifetime);
-                                    } else {
-                                        print!("/* type */");
-                                    }
-                                }
+        if let Some(struct_details) = &inner.struct_ {
+            self.print_struct_definition(
+                out,
+                name,
+                struct_details,
+                doc,
+                detail,
+            )?;
+        }
 
-                                // Helper function to check if a struct is a
-                                // tuple struct
-                                fn check_is_tuple_struct(
-                                    struct_details: &StructDetails,
-                                ) -> bool {
-                                    let Ok(json_value) =
-                                        serde_json::to_value(struct_details)
-                                    else {
-                                        return false;
-                                    };
-                                    let Some(obj) = json_value.as_object()
-                                    else {
-                                        return false;
-                                    };
-                                    let Some(kind) = obj.get("kind") else {
-                                        return false;
-                                    };
-                                    let Some(kind_obj) = kind.as_object()
-                                    else {
-                                        return false;
-                                    };
-
-                                    kind_obj.contains_key("tuple")
-                                }
-                            }
-                        }
+        if let Some(union_details) = &inner.union_ {
+            writeln!(out, "```rust")?;
+            doc.write_union_source(
+                out,
+                self,
+                union_details,
+                SourceStyle::SKELETON,
+            )?;
+            writeln!(out, "```\n")?;
+        }
 
-                        println!(");");
-                    }
-                    StructKind::Unit(_) => {
-                        println!("pub struct {name}{generics_str};");
-                    }
-                    _ => {
-                        println!("pub struct {name}{generics_str}(); // Unknown struct kind");
-                    }
-                }
-            } else {
-                println!("pub struct {name}{generics_str} {{");
-                for field_id in &struct_details.fields {
-                    if let Some(field) = doc.index.get(field_id) {
-                        if let Some(docs) = &field.docs {
-                            // Split multi-line docs into proper doc comments
-                            for line in docs.lines() {
-                                println!("    /// {}", line);
-                            }
-                        }
-                        if let Some(field_name) = &field.name {
-                            let visibility = field
-                                .visibility
-                                .as_deref()
-                                .unwrap_or("default");
-                            print!("    ");
-                            if visibility == "public" {
-                                print!("pub ");
-                            }
-                            // This is a simplification - we'd need to extract
-                            // the type
-                            println!("{field_name}: /* field type */,");
-                        }
-                    }
-                }
-                println!("}}");
-            }
-            println!("```");
-            println!();
+        if type_impls(self).is_some() {
+            doc.write_available_methods(out, &self.id)?;
+            doc.write_conversions(out, &self.id)?;
+            doc.write_error_type(out, &self.id)?;
         }
 
         // Handle trait definition
-        self.print_trait_details(doc);
+        self.print_trait_details(out, doc, detail)?;
+
+        if type_impls(self).is_some() || inner.trait_.is_some() {
+            doc.write_usages(out, &self.id)?;
+        }
 
         // Print trait implementations if we found any
         if !traits.is_empty() {
@@ -979,44 +1275,44 @@ impl RustDocItem {
 
             // Print manually implemented traits first
             if !manual_traits.is_empty() {
-                println!("**Implements:**");
+                writeln!(out, "**Implements:**")?;
                 for trait_ in manual_traits {
-                    println!("- `{}`", trait_);
+                    writeln!(out, "- `{}`", trait_)?;
                 }
-                println!();
+                writeln!(out)?;
             }
 
             // Print auto-derived traits
             if !auto_traits.is_empty() {
-                println!("**Auto-implemented traits:**");
+                writeln!(out, "**Auto-implemented traits:**")?;
                 for trait_ in auto_traits {
-                    println!("- `{}`", trait_);
+                    writeln!(out, "- `{}`", trait_)?;
                 }
-                println!();
+                writeln!(out)?;
             }
         }
 
         // Print implementation details for trait impls
-        self.print_impl_details(doc);
+        self.print_impl_details(out, doc)?;
 
-        println!();
+        writeln!(out)
     }
 
-    fn is_enum_variant(&self) -> bool {
-        let Some(inner) = &self.inner else {
-            return false;
-        };
-        let Ok(inner_json) = serde_json::to_value(inner) else {
-            return false;
-        };
-        let Some(obj) = inner_json.as_object() else {
-            return false;
-        };
+    fn import(&self) -> Option<&Import> {
+        self.inner.as_ref()?.import.as_ref()
+    }
 
-        obj.contains_key("variant")
+    fn is_enum_variant(&self) -> bool {
+        self.inner
+            .as_ref()
+            .is_some_and(|inner| inner.variant.is_some())
     }
 
     pub fn skip(&self, doc: &RustDoc) -> Option<&'static str> {
+        if let Some(reason) = self.omitted_reason(doc) {
+            return Some(reason);
+        }
+
         if self.is_trait_method_implementation(doc) {
             return Some("trait method implementation (already shown in parent trait impl)");
         }
@@ -1104,31 +1400,43 @@ impl RustDocItem {
 
             // Search for the target item in the index by ID
             let Some(item) = doc.index.get(target_id) else {
+                // Items of other crates link to their docs, if we have them
+                if let Some(url) = doc.external_links.get(target_id) {
+                    let link = format!("[{link_text}]");
+                    let replacement = format!("[{link_text}]({url})");
+                    processed = processed.replace(&link, &replacement);
+                }
                 continue;
             };
-            let Some(target_name) = &item.name else {
+            if item.name.is_none() {
                 continue;
-            };
+            }
 
-            // Format the replacement link based on the item type
-            let replacement = if let Some(inner) = &item.inner {
-                let item_type = Self::determine_item_type(inner);
-                format!(
-                    "[{}](#{}-{})",
-                    link_text,
-                    target_name.to_lowercase(),
-                    item_type
-                )
-            } else {
-                format!("[{}](#{}-item)", link_text, target_name.to_lowercase())
-            };
+            // Links to omitted items would dangle, so use plain text instead
+            let link = format!("[{link_text}]");
+            if item.omitted_reason(doc).is_some() {
+                processed = processed.replace(&link, link_text);
+                continue;
+            }
 
-            processed = processed.replace(link_text, &replacement);
+            let replacement = format!("[{}](#{})", link_text, item.anchor());
+            processed = processed.replace(&link, &replacement);
         }
 
         processed
     }
 
+    /// The anchor which links to this item, e.g. `point-struct`.
+    fn anchor(&self) -> String {
+        let name = self.name.as_deref().unwrap_or_default().to_lowercase();
+        // Format the anchor based on the item type
+        let item_type = match &self.inner {
+            Some(inner) => Self::determine_item_type(inner),
+            None => "item",
+        };
+        format!("{name}-{item_type}")
+    }
+
     fn determine_item_type(inner: &ItemInner) -> &'static str {
         if inner.function.is_some() {
             "function"
@@ -1136,76 +1444,372 @@ impl RustDocItem {
             "struct"
         } else if inner.enum_.is_some() {
             "enum"
+        } else if inner.union_.is_some() {
+            "union"
         } else if inner.trait_.is_some() {
             "trait"
+        } else if inner.static_.is_some() {
+            "static"
+        } else if inner.macro_.is_some() || inner.proc_macro.is_some() {
+            "macro"
         } else {
             "item"
         }
     }
 
-    fn print_trait_details(&self, doc: &RustDoc) {
-        let Some(inner) = &self.inner else { return };
-        let Some(name) = &self.name else { return };
+    /// Print the definition of an enum, with its variants.
+    fn print_enum_definition(
+        &self,
+        out: &mut String,
+        name: &str,
+        enum_details: &EnumDetails,
+        doc: &RustDoc,
+        detail: Detail,
+    ) -> fmt::Result {
+        writeln!(out, "```rust")?;
+        self.write_attributes(out, "")?;
+        let generics = enum_details.generics.as_ref();
+        writeln!(
+            out,
+            "pub enum {name}{}{} {{",
+            generic_params(generics),
+            where_clause(generics)
+        )?;
+
+        for variant_id in &enum_details.variants {
+            let Some(variant) = doc.index.get(variant_id) else {
+                continue;
+            };
+            if variant.omitted_reason(doc).is_some() {
+                continue;
+            }
+            if let Some(docs) = variant.docs_to_print(detail) {
+                for line in docs.lines() {
+                    if line.is_empty() {
+                        writeln!(out, "    ///")?;
+                    } else {
+                        writeln!(out, "    /// {line}")?;
+                    }
+                }
+            }
+            variant.write_annotations(out, doc, "    ")?;
+            doc.write_variant(out, variant, "    ", SourceStyle::SKELETON)?;
+        }
+        writeln!(out, "}}")?;
+        writeln!(out, "```")?;
+        writeln!(out)?;
+        Ok(())
+    }
+
+    /// Print the definition of a struct, with its fields.
+    fn print_struct_definition(
+        &self,
+        out: &mut String,
+        name: &str,
+        struct_details: &StructDetails,
+        doc: &RustDoc,
+        detail: Detail,
+    ) -> fmt::Result {
+        writeln!(out, "```rust")?;
+        self.write_attributes(out, "")?;
+
+        // Print struct generics if available
+        let generics_str = if let Some(generics) = &struct_details.generics {
+            let mut params = Vec::new();
+
+            for param in &generics.params {
+                // Add all params to the list
+                if let Some(name) = &param.name {
+                    params.push(name.clone());
+                }
+            }
+
+            if !params.is_empty() {
+                // Make sure lifetime parameters are properly
+                // prefixed with an apostrophe
+                // No need for special handling since JSON
+                // already has correctly formatted names
+                let formatted_params = params.clone();
+                format!("<{}>", formatted_params.join(", "))
+            } else {
+                String::new()
+            }
+        } else {
+            String::new()
+        };
+
+        if let Some(kind) = &struct_details.kind {
+            match kind {
+                StructKind::Tuple { tuple } if tuple.is_some() => {
+                    write!(out, "pub struct {name}{generics_str}(")?;
+
+                    // Tuple structs have elements in the 'tuple' array
+                    if let Some(tuple_fields) = tuple {
+                        let mut first = true;
+
+                        // If we have explicit field IDs, prefer those
+                        if !struct_details.fields.is_empty() {
+                            for field_id in &struct_details.fields {
+                                if let Some(field) = doc.index.get(field_id) {
+                                    if !first {
+                                        write!(out, ", ")?;
+                                    }
+                                    let visibility = field
+                                        .visibility
+                                        .as_deref()
+                                        .unwrap_or("default");
+                                    if visibility == "public" {
+                                        write!(out, "pub ")?;
+                                    }
+                                    // This is a simplification - we'd need
+                                    // to extract the type
+                                    write!(out, "/* field type */")?;
+                                    first = false;
+                                }
+                            }
+                        }
+                        // Otherwise use the tuple array directly
+                        else {
+                            for (i, field_value) in
+                                tuple_fields.iter().enumerate()
+                            {
+                                if i > 0 {
+                                    write!(out, ", ")?;
+                                }
+
+                                // Try to parse field type from value
+                                // Handle special cases and null values
+                                if field_value.is_null() {
+                                    handle_null_field_value(
+                                        out,
+                                        name,
+                                        struct_details,
+                                        &generics_str,
+                                    )?;
+                                    continue;
+                                }
+
+                                // Handle object field values
+                                if let Some(field_obj) = field_value.as_object()
+                                {
+                                    let type_name = field_obj
+                                        .get("name")
+                                        .and_then(|v| v.as_str())
+                                        .unwrap_or("/* field type */");
+                                    write!(out, "{}", type_name)?;
+                                } else {
+                                    write!(out, "/* field type */")?;
+                                }
+                            }
+
+                            // Helper function to handle null field values
+                            fn handle_null_field_value(
+                                out: &mut String,
+                                name: &str,
+                                struct_details: &StructDetails,
+                                generics_str: &str,
+                            ) -> fmt::Result {
+                                if name == "HexDisplay" {
+                                    let lifetime =
+                                        extract_lifetime_param(struct_details);
+                                    write!(out, "&{} [u8]", lifetime)?;
+                                    return Ok(());
+                                }
+
+                                let _is_tuple_struct =
+                                    check_is_tuple_struct(struct_details);
+
+                                if generics_str.contains("'") {
+                                    let lifetime =
+                                        extract_first_lifetime(generics_str)
+                                            .unwrap_or("'a");
+
+                                    write!(out, "&{} [u8]", lifetime)
+                                } else {
+                                    write!(out, "/* type */")
+                                }
+                            }
+
+                            // Helper function to check if a struct is a
+                            // tuple struct
+                            fn check_is_tuple_struct(
+                                struct_details: &StructDetails,
+                            ) -> bool {
+                                let Ok(json_value) =
+                                    serde_json::to_value(struct_details)
+                                else {
+                                    return false;
+                                };
+                                let Some(obj) = json_value.as_object() else {
+                                    return false;
+                                };
+                                let Some(kind) = obj.get("kind") else {
+                                    return false;
+                                };
+                                let Some(kind_obj) = kind.as_object() else {
+                                    return false;
+                                };
+
+                                kind_obj.contains_key("tuple")
+                            }
+                        }
+                    }
+
+                    writeln!(out, ");")?;
+                }
+                StructKind::Unit(_) => {
+                    writeln!(out, "pub struct {name}{generics_str};")?;
+                }
+                StructKind::Plain { plain } => {
+                    writeln!(out, "pub struct {name}{generics_str} {{")?;
+                    let has_private_fields =
+                        print_named_fields(out, detail, &plain.fields, doc)?;
+                    if plain.fields_stripped || has_private_fields {
+                        writeln!(out, "    // Some fields are private")?;
+                    }
+                    writeln!(out, "}}")?;
+                }
+                _ => {
+                    writeln!(out, "pub struct {name}{generics_str}(); // Unknown struct kind")?;
+                }
+            }
+        } else {
+            writeln!(out, "pub struct {name}{generics_str} {{")?;
+            print_named_fields(out, detail, &struct_details.fields, doc)?;
+            writeln!(out, "}}")?;
+        }
+        writeln!(out, "```")?;
+        writeln!(out)?;
+
+        /// Returns whether private fields were left out, since only the
+        /// public API is printed.
+        fn print_named_fields(
+            out: &mut String,
+            detail: Detail,
+            field_ids: &[String],
+            doc: &RustDoc,
+        ) -> Result<bool, fmt::Error> {
+            let mut has_private_fields = false;
+            for field_id in field_ids {
+                let Some(field) = doc.index.get(field_id) else {
+                    continue;
+                };
+                let Some(field_name) = &field.name else {
+                    continue;
+                };
+                if field.omitted_reason(doc).is_some() {
+                    continue;
+                }
+                if doc.options.public_only && !field.is_public() {
+                    has_private_fields = true;
+                    continue;
+                }
+
+                if let Some(docs) = field.docs_to_print(detail) {
+                    // Split multi-line docs into proper doc comments
+                    for line in docs.lines() {
+                        writeln!(out, "    /// {}", line)?;
+                    }
+                }
+                field.write_annotations(out, doc, "    ")?;
+                let visibility = if field.is_public() { "pub " } else { "" };
+                match field.inner.as_ref().and_then(|i| i.struct_field.as_ref())
+                {
+                    Some(type_) =>
+                        writeln!(out, "    {visibility}{field_name}: {type_},")?,
+                    None => writeln!(
+                        out,
+                        "    {visibility}{field_name}: /* field type */,"
+                    )?,
+                }
+            }
+            Ok(has_private_fields)
+        }
+
+        Ok(())
+    }
+
+    fn print_trait_details(
+        &self,
+        out: &mut String,
+        doc: &RustDoc,
+        detail: Detail,
+    ) -> fmt::Result {
+        let Some(inner) = &self.inner else {
+            return Ok(());
+        };
+        let Some(name) = &self.name else {
+            return Ok(());
+        };
 
         if let Some(trait_info) = &inner.trait_ {
-            println!("```rust");
+            writeln!(out, "```rust")?;
+            self.write_attributes(out, "")?;
             let safety = if trait_info.is_unsafe { "unsafe " } else { "" };
 
-            print!("pub {safety}trait {name}");
+            write!(out, "pub {safety}trait {name}")?;
 
             // Print generic params if any
             if let Some(generics) = &trait_info.generics {
                 if !generics.params.is_empty() {
-                    print!("<...>"); // Simplified for now
+                    write!(out, "<...>")?; // Simplified for now
                 }
             }
 
             // Print trait bounds if any
             if !trait_info.bounds.is_empty() {
-                print!(": ");
+                write!(out, ": ")?;
                 let mut first = true;
                 for bound in &trait_info.bounds {
                     if !first {
-                        print!(" + ");
+                        write!(out, " + ")?;
                     }
                     if let Some(trait_bound) = &bound.trait_bound {
-                        print!("{}", trait_bound.trait_.name);
+                        write!(out, "{}", trait_bound.trait_.name)?;
                     } else if let Some(outlives) = &bound.outlives {
-                        print!("{}", outlives);
+                        write!(out, "{}", outlives)?;
                     }
                     first = false;
                 }
             }
 
-            println!(" {{");
+            writeln!(out, " {{")?;
 
             // Print required methods
             for method_id in &trait_info.items {
                 if let Some(method_item) = doc.index.get(method_id) {
+                    if method_item.omitted_reason(doc).is_some() {
+                        continue;
+                    }
                     if let Some(method_name) = &method_item.name {
                         // Print method documentation as a doc comment
-                        if let Some(method_docs) = &method_item.docs {
+                        if let Some(method_docs) =
+                            method_item.docs_to_print(detail)
+                        {
                             for line in method_docs.lines() {
-                                println!("    /// {line}");
+                                writeln!(out, "    /// {line}")?;
                             }
                         }
 
                         // Print method signature
                         if let Some(inner) = &method_item.inner {
                             if let Some(function) = &inner.function {
-                                print!("    fn {method_name}(");
+                                method_item
+                                    .write_annotations(out, doc, "    ")?;
+                                method_item.write_attributes(out, "    ")?;
+                                write!(out, "    fn {method_name}(")?;
 
                                 let mut first = true;
                                 for (param_name, param) in &function.decl.inputs
                                 {
                                     if !first {
-                                        print!(", ");
+                                        write!(out, ", ")?;
                                     }
-                                    print!("{param_name}: {param}");
+                                    write!(out, "{param_name}: {param}")?;
                                     first = false;
                                 }
 
-                                print!(")");
+                                write!(out, ")")?;
 
                                 if let Some(ret) = &function.decl.output {
                                     // Handle special case for trait methods
@@ -1213,46 +1817,60 @@ impl RustDocItem {
                                     if let ReturnType::Generic { generic } = ret
                                     {
                                         if generic == "Self" {
-                                            print!(" -> Self");
+                                            write!(out, " -> Self")?;
                                         } else {
-                                            print!(" -> {ret}");
+                                            write!(out, " -> {ret}")?;
                                         }
                                     } else {
-                                        print!(" -> {ret}");
+                                        write!(out, " -> {ret}")?;
                                     }
                                 }
 
-                                println!(" {{ ... }}"); // Empty block instead
-                                                        // of
-                                                        // semicolon
+                                writeln!(out, " {{ ... }}")?; // Empty block
+                                                              // instead
+                                                              // of
+                                                              // semicolon
                             }
                         }
                     }
                 }
             }
 
-            println!("}}");
-            println!("```");
-            println!();
+            writeln!(out, "}}")?;
+            writeln!(out, "```")?;
+            writeln!(out)?;
 
-            println!("**Methods:**");
-            println!();
+            writeln!(out, "**Methods:**")?;
+            writeln!(out)?;
             // Then print each method with full details
             for method_id in &trait_info.items {
                 if let Some(method_item) = doc.index.get(method_id) {
+                    if method_item.omitted_reason(doc).is_some() {
+                        continue;
+                    }
                     if let Some(method_name) = &method_item.name {
-                        println!("#### `{}::{}`", name, method_name);
-                        if let Some(method_docs) = &method_item.docs {
-                            println!();
-                            println!("{method_docs}");
-                            println!();
+                        let annotations = method_item.heading_annotations(doc);
+                        writeln!(
+                            out,
+                            "#### `{name}::{method_name}`{annotations}"
+                        )?;
+                        if let Some(method_docs) =
+                            method_item.docs_to_print(detail)
+                        {
+                            writeln!(out)?;
+                            writeln!(out, "{method_docs}")?;
+                            writeln!(out)?;
                         }
 
                         // Print method signature
                         if let Some(inner) = &method_item.inner {
                             if let Some(function) = &inner.function {
-                                function.decl.print(method_name);
-                                println!();
+                                function.decl.print(
+                                    out,
+                                    method_name,
+                                    method_item,
+                                )?;
+                                writeln!(out)?;
                             }
                         }
                     }
@@ -1262,23 +1880,29 @@ impl RustDocItem {
         // Fallback to the older approach if needed
         else if let Some(trait_details) = self.get_trait_details() {
             if let Some(items) = &trait_details.items {
-                println!("**Trait Methods:**");
-                println!();
+                writeln!(out, "**Trait Methods:**")?;
+                writeln!(out)?;
                 for method_id in items {
                     if let Some(method_item) = doc.index.get(method_id) {
                         if let Some(method_name) = &method_item.name {
-                            println!("#### `{}::{}`", name, method_name);
-                            if let Some(method_docs) = &method_item.docs {
-                                println!();
-                                println!("{method_docs}");
-                                println!();
+                            writeln!(out, "#### `{}::{}`", name, method_name)?;
+                            if let Some(method_docs) =
+                                method_item.docs_to_print(detail)
+                            {
+                                writeln!(out)?;
+                                writeln!(out, "{method_docs}")?;
+                                writeln!(out)?;
                             }
 
                             // Print method signature
                             if let Some(inner) = &method_item.inner {
                                 if let Some(function) = &inner.function {
-                                    function.decl.print(method_name);
-                                    println!();
+                                    function.decl.print(
+                                        out,
+                                        method_name,
+                                        method_item,
+                                    )?;
+                                    writeln!(out)?;
                                 }
                             }
                         }
@@ -1286,14 +1910,28 @@ impl RustDocItem {
                 }
             }
         }
+
+        Ok(())
     }
 
-    fn print_impl_details(&self, doc: &RustDoc) {
-        let Some(inner) = &self.inner else { return };
-        let Some(impl_) = &inner.impl_ else { return };
+    fn print_impl_details(
+        &self,
+        out: &mut String,
+        doc: &RustDoc,
+    ) -> fmt::Result {
+        let Some(inner) = &self.inner else {
+            return Ok(());
+        };
+        let Some(impl_) = &inner.impl_ else {
+            return Ok(());
+        };
 
-        let Some(trait_) = &impl_.trait_ else { return };
-        let Some(for_type) = &impl_.for_ else { return };
+        let Some(trait_) = &impl_.trait_ else {
+            return Ok(());
+        };
+        let Some(for_type) = &impl_.for_ else {
+            return Ok(());
+        };
 
         let trait_name = &trait_.name;
         let trait_args = if let Some(args) = &trait_.args {
@@ -1319,14 +1957,15 @@ impl RustDocItem {
         };
 
         // Add a heading for the trait implementation
-        println!(
+        writeln!(
+            out,
             "### Implementation of `{}` for `{}`",
             trait_name, for_type_name
-        );
-        println!();
+        )?;
+        writeln!(out)?;
 
         // Print the impl header using a code block for better formatting
-        println!("```rust");
+        writeln!(out, "```rust")?;
 
         // Format the trait name with its arguments
         let trait_full_name = format!("{}{}", trait_name, trait_args);
@@ -1346,15 +1985,20 @@ impl RustDocItem {
                 }
                 generics_str.push('>');
 
-                println!(
+                writeln!(
+                    out,
                     "impl{} {} for {} {{",
                     generics_str, trait_full_name, for_type_name
-                );
+                )?;
             } else {
-                println!("impl {} for {} {{", trait_full_name, for_type_name);
+                writeln!(
+                    out,
+                    "impl {} for {} {{",
+                    trait_full_name, for_type_name
+                )?;
             }
         } else {
-            println!("impl {} for {} {{", trait_full_name, for_type_name);
+            writeln!(out, "impl {} for {} {{", trait_full_name, for_type_name)?;
         }
 
         // Print implementation methods in the code block
@@ -1365,35 +2009,55 @@ impl RustDocItem {
             let Some(method_name) = &method_item.name else {
                 continue;
             };
+            if method_item.omitted_reason(doc).is_some() {
+                continue;
+            }
 
             // Print method signature within the impl block
             if let Some(inner) = &method_item.inner {
                 if let Some(function) = &inner.function {
-                    print!("    fn {method_name}(");
+                    method_item.write_annotations(out, doc, "    ")?;
+                    method_item.write_attributes(out, "    ")?;
+                    write!(out, "    fn {method_name}(")?;
 
                     let mut first = true;
                     for (param_name, param) in &function.decl.inputs {
                         if !first {
-                            print!(", ");
+                            write!(out, ", ")?;
                         }
-                        print!("{param_name}: {param}");
+                        write!(out, "{param_name}: {param}")?;
                         first = false;
                     }
 
-                    print!(")");
+                    write!(out, ")")?;
 
                     if let Some(ret) = &function.decl.output {
-                        print!(" -> {ret}");
+                        write!(out, " -> {ret}")?;
                     }
 
-                    println!(" {{ ... }}"); // Empty block instead of semicolon
+                    writeln!(out, " {{ ... }}")?; // Empty block instead of
+                                                  // semicolon
                 }
             }
         }
 
-        println!("}}");
-        println!("```");
-        println!();
+        writeln!(out, "}}")?;
+        writeln!(out, "```")?;
+
+        let mut type_paths = impl_.type_paths();
+        for method_id in &impl_.items {
+            if let Some(function) = doc
+                .index
+                .get(method_id)
+                .and_then(|method| method.inner.as_ref())
+                .and_then(|inner| inner.function.as_ref())
+            {
+                type_paths.extend(function.decl.type_paths());
+            }
+        }
+        doc.write_external_links(out, &type_paths)?;
+
+        writeln!(out)
     }
 
     fn get_trait_details(&self) -> Option<TraitDetails> {
@@ -1423,12 +2087,37 @@ struct StructDetails {
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(untagged)]
 enum StructKind {
+    Plain {
+        plain: PlainStruct,
+    },
     Tuple {
         tuple: Option<Vec<serde_json::Value>>,
     },
     Unit(String),
 }
 
+#[derive(Debug, Deserialize, Serialize)]
+struct PlainStruct {
+    fields: Vec<String>,
+    #[serde(default)]
+    fields_stripped: bool,
+}
+
+impl StructDetails {
+    /// The ids of the fields of the struct which weren't stripped.
+    fn field_ids(&self) -> Vec<String> {
+        match &self.kind {
+            Some(StructKind::Plain { plain }) => plain.fields.clone(),
+            Some(StructKind::Tuple { tuple }) => tuple
+                .iter()
+                .flatten()
+                .filter_map(|field| field.as_str().map(String::from))
+                .collect(),
+            _ => self.fields.clone(),
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 struct TraitInfo {
     bounds: Vec<TraitBoundInfo>,
@@ -1437,6 +2126,9 @@ struct TraitInfo {
     is_unsafe: bool,
     #[serde(default)]
     items: Vec<String>,
+    /// The ids of the impls of the trait.
+    #[serde(default)]
+    implementations: Vec<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -1454,25 +2146,38 @@ struct TraitDetails {
 }
 
 impl FunctionDecl {
-    fn print(&self, name: &str) {
-        print!("```rust\npub fn {name}(");
+    fn print(
+        &self,
+        out: &mut String,
+        name: &str,
+        item: &RustDocItem,
+    ) -> fmt::Result {
+        writeln!(out, "```rust")?;
+        item.write_attributes(out, "")?;
+        let qualifiers = item
+            .inner
+            .as_ref()
+            .and_then(|inner| inner.function.as_ref()?.header.as_ref())
+            .map(FunctionHeader::qualifiers)
+            .unwrap_or_default();
+        write!(out, "pub {qualifiers}fn {name}(")?;
 
         let mut first = true;
         for (param_name, param) in &self.inputs {
             if !first {
-                print!(", ");
+                write!(out, ", ")?;
             }
-            print!("{param_name}: {param}");
+            write!(out, "{param_name}: {param}")?;
             first = false;
         }
 
-        print!(")");
+        write!(out, ")")?;
 
         if let Some(ret) = &self.output {
-            print!(" -> {ret}");
+            write!(out, " -> {ret}")?;
         }
 
-        println!(" {{ ... }}\n```"); // Empty block instead of semicolon
+        writeln!(out, " {{ ... }}\n```") // Empty block instead of semicolon
     }
 }
 
@@ -1491,14 +2196,7 @@ impl fmt::Display for Parameter {
                 write!(f, "{}", borrowed_ref.type_)
             }
             Self::Primitive { primitive } => write!(f, "{}", primitive),
-            Self::Qualified { qualified_path } => {
-                write!(
-                    f,
-                    "{}{}",
-                    qualified_path.name,
-                    format_angle_bracketed_args(qualified_path.args.as_ref())
-                )
-            }
+            Self::Qualified { qualified_path } => write!(f, "{qualified_path}"),
             Self::Generic { generic } => write!(f, "{}", generic),
             Self::ResolvedPath { resolved_path } => {
                 write!(
@@ -1518,29 +2216,16 @@ impl fmt::Display for Parameter {
                 } else {
                     write!(f, "*const {}", raw_pointer.type_)
                 },
-            Self::ImplTrait { impl_trait } => {
-                let bounds = impl_trait
-                    .iter()
-                    .map(|item| item.trait_bound.trait_.name.clone())
-                    .collect::<Vec<_>>()
-                    .join(" + ");
-                write!(f, "impl {}", bounds)
-            }
-            Self::DynTrait { dyn_trait } => {
-                let joined_traits = dyn_trait
-                    .traits
-                    .iter()
-                    .map(|tb| {
-                        let name = &tb.trait_.name;
-                        let args = format_angle_bracketed_args(
-                            tb.trait_.args.as_ref(),
-                        );
-                        format!("{name}{args}")
-                    })
-                    .collect::<Vec<_>>()
-                    .join(" + ");
-                write!(f, "dyn {}", joined_traits)
+            Self::ImplTrait { impl_trait } =>
+                write!(f, "impl {}", bounds_source(impl_trait)),
+            Self::DynTrait { dyn_trait } => write!(f, "dyn {dyn_trait}"),
+            Self::Tuple { tuple } => {
+                let types = tuple.iter().map(Parameter::to_string);
+                write!(f, "{}", tuple_source(types))
             }
+            Self::FunctionPointer { function_pointer } =>
+                write!(f, "{function_pointer}"),
+            Self::Unknown(_) => write!(f, "_"),
         }
     }
 }
@@ -1571,28 +2256,12 @@ impl fmt::Display for ReturnType {
                 }
                 write!(f, "{}", borrowed_ref.type_)
             }
-            Self::Tuple { tuple } =>
-                if tuple.is_empty() {
-                    write!(f, "()")
-                } else {
-                    write!(f, "(")?;
-                    for (i, t) in tuple.iter().enumerate() {
-                        if i > 0 {
-                            write!(f, ", ")?;
-                        }
-                        write!(f, "{}", t)?;
-                    }
-                    write!(f, ")")
-                },
-            Self::Generic { generic } => write!(f, "{}", generic),
-            Self::Qualified { qualified_path } => {
-                write!(
-                    f,
-                    "{}{}",
-                    qualified_path.name,
-                    format_angle_bracketed_args(qualified_path.args.as_ref())
-                )
+            Self::Tuple { tuple } => {
+                let types = tuple.iter().map(ReturnType::to_string);
+                write!(f, "{}", tuple_source(types))
             }
+            Self::Generic { generic } => write!(f, "{}", generic),
+            Self::Qualified { qualified_path } => write!(f, "{qualified_path}"),
             Self::Slice { slice } => write!(f, "[{}]", slice),
             Self::RawPointer { raw_pointer } =>
                 if raw_pointer.mutable {
@@ -1600,34 +2269,63 @@ impl fmt::Display for ReturnType {
                 } else {
                     write!(f, "*const {}", raw_pointer.type_)
                 },
-            Self::ImplTrait { impl_trait } => {
-                let bounds = impl_trait
-                    .iter()
-                    .map(|item| item.trait_bound.trait_.name.clone())
-                    .collect::<Vec<_>>()
-                    .join(" + ");
-                write!(f, "impl {}", bounds)
-            }
-            Self::DynTrait { dyn_trait } => {
-                let joined_traits = dyn_trait
-                    .traits
-                    .iter()
-                    .map(|tb| {
-                        let name = &tb.trait_.name;
-                        let args = format_angle_bracketed_args(
-                            tb.trait_.args.as_ref(),
-                        );
-                        format!("{name}{args}")
-                    })
-                    .collect::<Vec<_>>()
-                    .join(" + ");
-                write!(f, "dyn {}", joined_traits)
-            }
-            Self::Self_ {} => write!(f, "Self"),
+            Self::ImplTrait { impl_trait } =>
+                write!(f, "impl {}", bounds_source(impl_trait)),
+            Self::DynTrait { dyn_trait } => write!(f, "dyn {dyn_trait}"),
+            Self::FunctionPointer { function_pointer } =>
+                write!(f, "{function_pointer}"),
+            Self::Unknown(_) => write!(f, "_"),
+        }
+    }
+}
+
+impl fmt::Display for FunctionPointer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let qualifiers = self.header.qualifiers();
+        write!(f, "{}{qualifiers}fn(", higher_ranked(&self.generic_params))?;
+        let mut inputs = self
+            .decl
+            .inputs
+            .iter()
+            .map(|(name, type_)| match name.as_str() {
+                "_" => type_.to_string(),
+                name => format!("{name}: {type_}"),
+            })
+            .collect::<Vec<_>>();
+        if self.decl.c_variadic {
+            inputs.push("...".to_string());
         }
+        write!(f, "{})", inputs.join(", "))?;
+        if let Some(output) = &self.decl.output {
+            write!(f, " -> {output}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A tuple type of the given types, e.g. `(u8,)` for a single one.
+fn tuple_source(types: impl Iterator<Item = String>) -> String {
+    let types = types.collect::<Vec<_>>();
+    match types.as_slice() {
+        [type_] => format!("({type_},)"),
+        _ => format!("({})", types.join(", ")),
     }
 }
 
+/// Visibilities are strings, except for `{"restricted": {"parent": ...,
+/// "path": ...}}`, which is kept as `"restricted"`.
+fn deserialize_visibility<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<String>, D::Error> {
+    let visibility = Option::<serde_json::Value>::deserialize(deserializer)?;
+    Ok(visibility.map(|visibility| match visibility {
+        serde_json::Value::String(visibility) => visibility,
+        serde_json::Value::Object(visibility) =>
+            visibility.keys().next().cloned().unwrap_or_default(),
+        visibility => visibility.to_string(),
+    }))
+}
+
 fn extract_first_lifetime(generics_str: &str) -> Option<&str> {
     let start_pos = generics_str.find("<'")?;
 
@@ -1688,6 +2386,15 @@ fn format_angle_bracketed_args(args: Option<&GenericArgs>) -> String {
                 .args
                 .iter()
                 .map(format_generic_arg)
+                .chain(angle_bracketed.bindings.iter().map(|binding| {
+                    let BindingKind::Equality { equality } = &binding.binding;
+                    format!(
+                        "{}{} = {}",
+                        binding.name,
+                        format_angle_bracketed_args(binding.args.as_ref()),
+                        equality.type_
+                    )
+                }))
                 .collect::<Vec<_>>();
 
             if formatted_args.is_empty() {
@@ -1696,10 +2403,18 @@ fn format_angle_bracketed_args(args: Option<&GenericArgs>) -> String {
                 format!("<{}>", formatted_args.join(", "))
             }
         }
-        Some(GenericArgs::Parenthesized { parenthesized: _ }) => {
-            String::new()
-            // TODO(max): If we want to print them, do something like:
-            // format!("({}...)", ...)
+        // e.g. `Fn(u8) -> bool`
+        Some(GenericArgs::Parenthesized { parenthesized }) => {
+            let inputs = parenthesized
+                .inputs
+                .iter()
+                .map(|input| input.to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+            match &parenthesized.output {
+                Some(output) => format!("({inputs}) -> {output}"),
+                None => format!("({inputs})"),
+            }
         }
     }
 }
@@ -1707,53 +2422,81 @@ fn format_angle_bracketed_args(args: Option<&GenericArgs>) -> String {
 // Helper function to format a generic argument
 fn format_generic_arg(arg: &GenericArg) -> String {
     match arg {
-        GenericArg::Type { type_inner } => format_type_arg(type_inner),
+        GenericArg::Type { type_inner } => type_inner.to_string(),
         GenericArg::Lifetime { lifetime } => lifetime.clone(),
         GenericArg::Const { const_ } => const_.expr.clone(),
     }
 }
 
-// Helper function to format a type argument
-fn format_type_arg(type_inner: &TypeContent) -> String {
-    // Try each type of content in order
-    if let Some(generic) = &type_inner.generic {
-        // Handle Self and other generic types
-        return generic.clone();
-    }
-
-    if let Some(primitive) = &type_inner.primitive {
-        return primitive.clone();
+/// The `for<'a>` of a higher-ranked bound, if it is one.
+fn higher_ranked(generic_params: &[GenericParam]) -> String {
+    let lifetimes = generic_params
+        .iter()
+        .filter_map(|param| param.name.as_deref())
+        .collect::<Vec<_>>();
+    if lifetimes.is_empty() {
+        String::new()
+    } else {
+        format!("for<{}> ", lifetimes.join(", "))
     }
+}
 
-    if let Some(slice) = &type_inner.slice {
-        return format!("[{}]", slice.primitive);
-    }
+/// Bounds joined with `+`, e.g. `Clone + 'static`.
+fn bounds_source(bounds: &[TraitBoundInfo]) -> String {
+    bounds
+        .iter()
+        .filter_map(|bound| match (&bound.trait_bound, &bound.outlives) {
+            (Some(trait_bound), _) => Some(trait_bound.to_string()),
+            (None, Some(outlives)) => Some(outlives.clone()),
+            (None, None) => None,
+        })
+        .collect::<Vec<_>>()
+        .join(" + ")
+}
 
-    if let Some(tuple) = &type_inner.tuple {
-        return format_tuple_type(tuple);
+impl fmt::Display for TraitBound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", higher_ranked(&self.generic_params))?;
+        if self.modifier.as_deref() == Some("maybe") {
+            write!(f, "?")?;
+        }
+        write!(
+            f,
+            "{}{}",
+            self.trait_.name,
+            format_angle_bracketed_args(self.trait_.args.as_ref())
+        )
     }
+}
 
-    if let Some(resolved_path) = &type_inner.resolved_path {
-        let inner_args =
-            format_angle_bracketed_args(resolved_path.args.as_ref());
-        return format!("{}{}", resolved_path.name, inner_args);
+impl fmt::Display for DynTrait {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let traits = self
+            .traits
+            .iter()
+            .map(|trait_| trait_.to_string())
+            .chain(self.lifetime.clone())
+            .collect::<Vec<_>>();
+        write!(f, "{}", traits.join(" + "))
     }
-
-    // Default case for unknown types
-    "/* unknown type */".to_string()
 }
 
-// Helper function to format a tuple type
-fn format_tuple_type(tuple: &[ReturnType]) -> String {
-    if tuple.is_empty() {
-        "()".to_string()
-    } else {
-        let formatted_elements = tuple
-            .iter()
-            .map(|t| t.to_string())
-            .collect::<Vec<_>>()
-            .join(", ");
-        format!("({})", formatted_elements)
+/// e.g. `<T as Iterator>::Item`, or `Self::Item` within a trait.
+impl fmt::Display for QualifiedPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let args = format_angle_bracketed_args(self.args.as_ref());
+        match (&*self.self_type, &self.trait_) {
+            (Parameter::Generic { generic }, _) if generic == "Self" =>
+                write!(f, "Self::{}{args}", self.name),
+            (self_type, Some(trait_)) => write!(
+                f,
+                "<{self_type} as {}{}>::{}{args}",
+                trait_.name,
+                format_angle_bracketed_args(trait_.args.as_ref()),
+                self.name
+            ),
+            (self_type, None) => write!(f, "{self_type}::{}{args}", self.name),
+        }
     }
 }
 
@@ -1768,6 +2511,9 @@ mod test {
     const COMMON_JSON_STR: &str =
         include_str!("../test-data/common/rustdoc.json");
     const HEX_JSON_STR: &str = include_str!("../test-data/hex/rustdoc.json");
+    const FFI_JSON_STR: &str = include_str!("../test-data/ffi/rustdoc.json");
+    const MACROS_JSON_STR: &str =
+        include_str!("../test-data/macros/rustdoc.json");
 
     /// ```bash
     /// $ cargo test print_hex_docs -- --ignored --nocapture
@@ -1918,4 +2664,78 @@ mod test {
         let doc = serde_json::from_str::<RustDoc>(HEX_JSON_STR).unwrap();
         doc.print();
     }
+
+    #[test]
+    fn test_enum_definition() {
+        let doc = serde_json::from_str::<RustDoc>(COMMON_JSON_STR).unwrap();
+        let markdown = doc.to_markdown();
+        assert!(markdown.contains(
+            "pub enum ConfigError {\n    /// The config file was not found.\n    \
+             NotFound,\n    /// A line could not be parsed.\n    \
+             #[non_exhaustive]\n    Parse {\n        /// The number of the \
+             line, from 1.\n        line: usize,\n    },\n}\n"
+        ));
+        assert!(markdown.contains("    Rect(Point, Point),\n"));
+        assert!(markdown.contains("    Error = 1,\n"));
+        // Variants are only written in their enums
+        assert!(!markdown.contains("## Enum Variants"));
+        assert!(!markdown.contains("#### `Rect`"));
+    }
+
+    #[test]
+    fn test_ffi_items() {
+        let doc = serde_json::from_str::<RustDoc>(FFI_JSON_STR).unwrap();
+        let markdown = doc.to_markdown();
+        assert!(markdown.contains(
+            "## Unions\n\n### pub Number\n\nA value which is either an \
+             integer or a float.\n\n```rust\n#[repr(C)]\npub union Number {\n"
+        ));
+        assert!(markdown.contains("```rust\npub static mut CALLS: u32 = 0;\n"));
+        assert!(markdown.contains(
+            "## Extern Blocks\n\n### ffi\n\n```rust\nextern \"C\" {\n    /// \
+             Returns the absolute value of `x`.\n    pub fn abs(x: c_int) -> \
+             c_int;\n"
+        ));
+        assert!(
+            markdown.contains("    pub static last_error: c_int;\n}\n```\n")
+        );
+        // Blocks of other modules are written under their module's path
+        assert!(markdown
+            .contains("### ffi::windows\n\n```rust\nextern \"system\" {\n"));
+        assert!(markdown.contains("#[no_mangle]\npub extern \"C\" fn add("));
+        // Fields are only written in their structs and unions
+        assert!(!markdown.contains("### pub x\n"));
+        assert!(!markdown.contains("### pub on_error\n"));
+
+        let item = |path: &[&str]| {
+            let path = path.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+            &doc.index[doc.id_by_path(&path).unwrap()]
+        };
+        assert_eq!(item(&["ffi", "Number"]).kind_name(&doc), "union");
+        assert_eq!(item(&["ffi", "CALLS"]).kind_name(&doc), "static");
+    }
+
+    #[test]
+    fn test_macros() {
+        let doc = serde_json::from_str::<RustDoc>(COMMON_JSON_STR).unwrap();
+        let markdown = doc.to_markdown();
+        assert!(markdown.contains("## Macros\n\n### pub pt\n"));
+        assert!(markdown.contains(
+            "```rust\n#[macro_export]\nmacro_rules! pt {\n    () => { ... \
+             };\n    ($x:expr, $y:expr) => { ... };\n}\n```\n\n**Path:** \
+             `common::pt!`\n"
+        ));
+        assert!(markdown.contains("```rust\nmacro_rules! double {\n"));
+
+        let doc = serde_json::from_str::<RustDoc>(MACROS_JSON_STR).unwrap();
+        let markdown = doc.to_markdown();
+        assert!(markdown.contains(
+            "```rust\n#[derive(Builder)]\n{\n    // Attributes available to \
+             this derive:\n    #[builder]\n}\n```\n\n**Path:** \
+             `macros::Builder`\n"
+        ));
+        assert!(markdown.contains("```rust\n#[route]\n```\n"));
+        assert!(markdown.contains("**Path:** `macros::sql!`\n"));
+        assert!(!markdown.contains("*Also available as*"));
+    }
 }
@@ -0,0 +1,227 @@
+//! The docs of a crate in the [`llms.txt`](https://llmstxt.org) format: an
+//! index, `llms.txt`, which links to a markdown file per module, and
+//! `llms-full.txt`, which has all of the docs in one file.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::{self, Write},
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::{Parameter, RustDoc, RustDocItem};
+
+impl RustDoc {
+    /// The `llms.txt` files of the crate with their contents: `llms.txt`,
+    /// `llms-full.txt` and a markdown file per module, e.g.
+    /// `common/shapes.md`.
+    pub fn to_llms_txt(&self) -> Vec<(PathBuf, String)> {
+        self.write_llms_files()
+            .expect("Writing to a String can't fail")
+    }
+
+    /// Write the `llms.txt` files of the crate to `dir`.
+    pub fn write_llms_txt(&self, dir: impl AsRef<Path>) -> io::Result<()> {
+        for (file, contents) in self.to_llms_txt() {
+            let file = dir.as_ref().join(file);
+            if let Some(parent) = file.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(file, contents)?;
+        }
+        Ok(())
+    }
+
+    fn write_llms_files(&self) -> Result<Vec<(PathBuf, String)>, fmt::Error> {
+        let item_modules = self.item_modules();
+        let mut module_files = Vec::new();
+        for (path, module) in self.modules() {
+            let file = PathBuf::from(format!("{}.md", path.join("/")));
+            let mut markdown = String::new();
+            writeln!(markdown, "# Module `{}`", path.join("::"))?;
+            writeln!(markdown)?;
+            if let Some(docs) = &module.docs {
+                writeln!(
+                    markdown,
+                    "{}",
+                    module.process_documentation(docs, self)
+                )?;
+                writeln!(markdown)?;
+            }
+            self.write_sections(&mut markdown, &BTreeMap::new(), |id| {
+                item_modules.get(id) == Some(&module.id.as_str())
+            })?;
+            if path.len() == 1 {
+                self.write_reexports(&mut markdown, &[])?;
+            }
+            module_files.push((path, module, file, markdown));
+        }
+
+        let mut header = String::new();
+        writeln!(header, "# {} v{}", self.crate_name(), self.crate_version)?;
+        writeln!(header)?;
+        let root = self.index.get(&self.root);
+        if let Some(summary) = root.and_then(|root| root.summary()) {
+            writeln!(header, "> {summary}")?;
+            writeln!(header)?;
+        }
+
+        let mut llms_txt = header.clone();
+        writeln!(llms_txt, "## Modules")?;
+        writeln!(llms_txt)?;
+        for (path, module, file, _) in &module_files {
+            let path = path.join("::");
+            let file = file.display();
+            match module.summary() {
+                Some(summary) =>
+                    writeln!(llms_txt, "- [{path}]({file}): {summary}")?,
+                None => writeln!(llms_txt, "- [{path}]({file})")?,
+            }
+        }
+        writeln!(llms_txt)?;
+        writeln!(llms_txt, "## Optional")?;
+        writeln!(llms_txt)?;
+        writeln!(
+            llms_txt,
+            "- [Full docs](llms-full.txt): The docs of all modules in one file"
+        )?;
+
+        let mut llms_full_txt = header;
+        for (_, _, _, markdown) in &module_files {
+            llms_full_txt.push_str(markdown);
+        }
+
+        let mut files = vec![
+            (PathBuf::from("llms.txt"), llms_txt),
+            (PathBuf::from("llms-full.txt"), llms_full_txt),
+        ];
+        files.extend(
+            module_files
+                .into_iter()
+                .map(|(_, _, file, markdown)| (file, markdown)),
+        );
+        Ok(files)
+    }
+
+    /// The module which each item is documented in, by id: the module which
+    /// has the item, or for members, e.g. fields and methods, the module of
+    /// the type or trait. Impls of a trait for types of other crates are in
+    /// the trait's module.
+    fn item_modules(&self) -> HashMap<&str, &str> {
+        let mut modules = HashMap::new();
+        for (_, module) in self.modules() {
+            for item in self.module_items(module) {
+                self.add_members(item, &module.id, &mut modules);
+            }
+        }
+        // Only now, so that impls for local types are with the type
+        for (id, item) in &self.index {
+            let Some(inner) = &item.inner else {
+                continue;
+            };
+            let Some(trait_) = &inner.trait_ else {
+                continue;
+            };
+            let Some(module) = modules.get(id.as_str()).copied() else {
+                continue;
+            };
+            for impl_item in trait_
+                .implementations
+                .iter()
+                .filter_map(|id| self.index.get(id))
+            {
+                if !modules.contains_key(impl_item.id.as_str()) {
+                    self.add_members(impl_item, module, &mut modules);
+                }
+            }
+        }
+        modules
+    }
+
+    /// Add an item and its members to `module`.
+    fn add_members<'a>(
+        &'a self,
+        item: &'a RustDocItem,
+        module: &'a str,
+        modules: &mut HashMap<&'a str, &'a str>,
+    ) {
+        modules.insert(&item.id, module);
+        let Some(inner) = &item.inner else {
+            return;
+        };
+
+        let members = if let Some(struct_) = &inner.struct_ {
+            [&struct_.field_ids()[..], &struct_.impls].concat()
+        } else if let Some(enum_) = &inner.enum_ {
+            [&enum_.variants[..], &enum_.impls].concat()
+        } else if let Some(union_) = &inner.union_ {
+            [&union_.fields[..], &union_.impls].concat()
+        } else if let Some(variant) = &inner.variant {
+            variant.field_ids()
+        } else if let Some(trait_) = &inner.trait_ {
+            trait_.items.clone()
+        } else if let Some(impl_) = &inner.impl_ {
+            // Impls of generic or primitive types are listed by their trait
+            if !matches!(impl_.for_, Some(Parameter::ResolvedPath { .. })) {
+                return;
+            }
+            impl_.items.clone()
+        } else {
+            return;
+        };
+        for member in members.iter().filter_map(|id| self.index.get(id)) {
+            self.add_members(member, module, modules);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const COMMON_JSON_STR: &str =
+        include_str!("../test-data/common/rustdoc.json");
+
+    #[test]
+    fn test_llms_txt() {
+        let doc = serde_json::from_str::<RustDoc>(COMMON_JSON_STR).unwrap();
+        let files = doc.to_llms_txt();
+        let file = |name: &str| {
+            let (_, contents) = files
+                .iter()
+                .find(|(file, _)| file == Path::new(name))
+                .unwrap_or_else(|| panic!("No file {name}"));
+            contents.as_str()
+        };
+
+        let llms_txt = file("llms.txt");
+        assert!(llms_txt.starts_with(
+            "# common v0.1.0\n\n> Common API patterns, used as a test fixture \
+             for `ai-rustdoc`.\n\n## Modules\n\n"
+        ));
+        assert!(llms_txt.contains(
+            "- [common::shapes](common/shapes.md): Simple geometry types.\n"
+        ));
+        assert!(llms_txt.contains("- [common::imp](common/imp.md)\n"));
+        assert!(
+            llms_txt.contains("## Optional\n\n- [Full docs](llms-full.txt)")
+        );
+
+        // Items and their members are in their own module's file
+        let shapes = file("common/shapes.md");
+        assert!(shapes.starts_with("# Module `common::shapes`\n\nSimple"));
+        assert!(shapes.contains("### pub Point\n"));
+        assert!(shapes.contains("### pub new\n"));
+        assert!(!shapes.contains("### pub greet\n"));
+        let root = file("common.md");
+        assert!(root.contains("### pub greet\n"));
+        assert!(!root.contains("### pub Point\n"));
+
+        // The full docs have the docs of every module
+        let full = file("llms-full.txt");
+        assert!(full.starts_with("# common v0.1.0\n\n> Common API"));
+        for module in ["common", "common::shapes", "common::imp"] {
+            assert!(full.contains(&format!("# Module `{module}`\n")));
+        }
+    }
+}
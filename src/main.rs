@@ -0,0 +1,221 @@
+//! The `ai-rustdoc` command line tool.
+
+use std::{env, io, path::Path, process::ExitCode};
+
+use ai_rustdoc::{BuildConfig, PrintOptions, RustDoc};
+
+const USAGE: &str = "\
+Usage:
+    ai-rustdoc [<options>] <rustdoc.json>
+        Print the docs of a crate as markdown.
+    ai-rustdoc skeleton <rustdoc.json>
+        Print only the signatures of a crate's items, as Rust source with
+        one-line doc summaries.
+    ai-rustdoc chunks <rustdoc.json> [--json]
+        Print one JSON object per item, e.g. to embed the docs for
+        retrieval, as JSON Lines or as a JSON array.
+    ai-rustdoc search <rustdoc.json> <query>...
+        Print the items which best match a name, path or question, e.g.
+        `bytes to hex`, with their signatures. Prefix the query with a kind,
+        e.g. `fn:` or `struct:`, to find only items of that kind. Queries
+        with an arrow find functions by type, e.g. '&str -> Vec<u8>'.
+    ai-rustdoc llms-txt <rustdoc.json> <dir>
+        Write llms.txt, llms-full.txt and a markdown file per module to dir.
+    ai-rustdoc stubs <rustdoc.json> <dir>
+        Write a stub crate to dir, with the crate's signatures and docs and
+        `unimplemented!()` bodies. Add the crate's dependencies to
+        dir/Cargo.toml to check it with `cargo check`.
+    ai-rustdoc examples <rustdoc.json> <dir>
+        Write each Rust code block in the docs to dir as a standalone
+        program, e.g. dir/hex/display.rs for the example of `hex::display`.
+    ai-rustdoc serve --stdio <rustdoc.json>
+        Serve the docs as a Model Context Protocol server over stdio, with
+        tools to search items, get an item's docs, list a module, and list
+        the methods of a type or the implementors of a trait.
+    ai-rustdoc diff <old.json> <new.json>
+        Print migration notes for the changes to a crate's public API.
+    ai-rustdoc semver <old.json> <new.json> [--json]
+        Classify the changes to a crate's public API as major, minor or
+        patch. Fails if the version bump is smaller than the changes require.
+    ai-rustdoc configs <Cargo.toml> [<config>...]
+        Document a crate in several build configurations with `cargo
+        rustdoc`, and print the merged docs, noting the configurations which
+        have each item. A config is `default`, `all-features`,
+        `features=<a,b>` or a target triple, by default `default` and
+        `all-features`.
+
+Options, for printing the docs and for `chunks`, `llms-txt`, `serve` and
+`configs`:
+    --max-tokens <N>
+        Print the docs in about N tokens at most, by shortening or leaving
+        out the least important docs.
+    --deprecated <mode>, --hidden <mode>, --unstable <mode>
+        Print deprecated, `#[doc(hidden)]` or unstable items like any other
+        item (`show`, the default), with a note (`annotate`), or not at all
+        (`omit`).
+    --public-only
+        Print only the items which other crates can name, under their
+        public paths.
+";
+
+/// The most items `search` prints.
+const MAX_SEARCH_RESULTS: usize = 10;
+
+fn main() -> ExitCode {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("Error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: &[String]) -> Result<(), String> {
+    let mut args = args.iter().map(String::as_str).collect::<Vec<_>>();
+    let arg_count = args.len();
+    let mut options = PrintOptions::default();
+    if let Some(n) = take_value(&mut args, "--max-tokens")? {
+        let max_tokens = n
+            .parse()
+            .map_err(|_| "--max-tokens needs a number of tokens")?;
+        options.max_tokens = Some(max_tokens);
+    }
+    if let Some(mode) = take_value(&mut args, "--deprecated")? {
+        options.deprecated = mode.parse()?;
+    }
+    if let Some(mode) = take_value(&mut args, "--hidden")? {
+        options.hidden = mode.parse()?;
+    }
+    if let Some(mode) = take_value(&mut args, "--unstable")? {
+        options.unstable = mode.parse()?;
+    }
+    if let Some(i) = args.iter().position(|arg| *arg == "--public-only") {
+        options.public_only = true;
+        args.remove(i);
+    }
+    // The other commands don't print the docs, so the options would be
+    // ignored
+    let takes_options = matches!(
+        args.as_slice(),
+        [_] | ["chunks" | "llms-txt" | "serve" | "configs", ..]
+    );
+    if args.len() < arg_count && !takes_options {
+        return Err(format!(
+            "The options only apply to printing the docs\n\n{USAGE}"
+        ));
+    }
+
+    match args.as_slice() {
+        ["-h" | "--help"] => print!("{USAGE}"),
+        ["diff", old, new] => {
+            let old = load(old)?;
+            let new = load(new)?;
+            print!("{}", old.diff(&new).to_markdown());
+        }
+        ["skeleton", path] => print!("{}", load(path)?.to_skeleton()),
+        ["chunks", path, rest @ ..] => {
+            let doc = load(path)?.with_options(options);
+            match rest {
+                [] => print!("{}", doc.to_chunks_jsonl()),
+                ["--json"] => println!("{}", doc.to_chunks_json()),
+                _ => return Err(format!("Invalid arguments\n\n{USAGE}")),
+            }
+        }
+        ["search", path, query @ ..] if !query.is_empty() => {
+            let doc = load(path)?;
+            let matches = doc.search(&query.join(" "));
+            if matches.is_empty() {
+                return Err(format!("No items match `{}`", query.join(" ")));
+            }
+            for found in matches.iter().take(MAX_SEARCH_RESULTS) {
+                println!("### `{}` ({})\n", found.path, found.kind);
+                if let Some(signature) = &found.signature {
+                    println!("```rust\n{signature}\n```\n");
+                }
+                if let Some(summary) = &found.summary {
+                    println!("{summary}\n");
+                }
+            }
+        }
+        ["llms-txt", path, dir] => load(path)?
+            .with_options(options)
+            .write_llms_txt(dir)
+            .map_err(|e| format!("Couldn't write the files to {dir}: {e}"))?,
+        ["stubs", path, dir] => load(path)?
+            .write_stub_crate(dir)
+            .map_err(|e| format!("Couldn't write the stubs to {dir}: {e}"))?,
+        ["examples", path, dir] => {
+            load(path)?.write_examples(Path::new(dir)).map_err(|e| {
+                format!("Couldn't write the examples to {dir}: {e}")
+            })?;
+        }
+        ["serve", "--stdio", path] | ["serve", path, "--stdio"] => {
+            let doc = load(path)?.with_options(options);
+            doc.serve_mcp(io::stdin().lock(), io::stdout().lock())
+                .map_err(|e| format!("Couldn't serve the docs: {e}"))?
+        }
+        ["semver", old, new, rest @ ..] => {
+            let json = match rest {
+                [] => false,
+                ["--json"] => true,
+                _ => return Err(format!("Invalid arguments\n\n{USAGE}")),
+            };
+            let report = load(old)?.semver_check(&load(new)?);
+            if json {
+                println!("{}", report.to_json());
+            } else {
+                print!("{}", report.to_markdown());
+            }
+            if report.is_downgrade {
+                return Err(format!(
+                    "The new version {} is lower than the old version {}",
+                    report.new_version, report.old_version
+                ));
+            }
+            if report.is_bump_sufficient() == Some(false) {
+                return Err(format!(
+                    "The changes require a {} version bump",
+                    report.verdict
+                ));
+            }
+        }
+        ["configs", manifest_path, specs @ ..] => {
+            let configs = match specs {
+                [] => vec![
+                    BuildConfig::default_features(),
+                    BuildConfig::all_features(),
+                ],
+                specs =>
+                    specs.iter().map(|spec| BuildConfig::parse(spec)).collect(),
+            };
+            RustDoc::document_configs(manifest_path, &configs)
+                .map_err(|e| format!("Couldn't document {manifest_path}: {e}"))?
+                .with_options(options)
+                .print()
+        }
+        [path] if !path.starts_with('-') =>
+            load(path)?.with_options(options).print(),
+        _ => return Err(format!("Invalid arguments\n\n{USAGE}")),
+    }
+    Ok(())
+}
+
+/// Remove a flag and its value from the arguments, e.g. `--hidden omit`,
+/// returning the value.
+fn take_value<'a>(
+    args: &mut Vec<&'a str>,
+    flag: &str,
+) -> Result<Option<&'a str>, String> {
+    let Some(i) = args.iter().position(|arg| *arg == flag) else {
+        return Ok(None);
+    };
+    let value = *args.get(i + 1).ok_or(format!("{flag} needs a value"))?;
+    args.drain(i..i + 2);
+    Ok(Some(value))
+}
+
+fn load(path: &str) -> Result<RustDoc, String> {
+    RustDoc::load(path).map_err(|e| format!("Couldn't load {path}: {e}"))
+}
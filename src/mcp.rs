@@ -0,0 +1,527 @@
+//! A [Model Context Protocol](https://modelcontextprotocol.io) server over
+//! stdio, which lets AI agents look up exactly the docs they need with tools,
+//! rather than loading the docs of a whole crate.
+//!
+//! Messages are JSON-RPC 2.0, one per line.
+
+use std::{
+    fmt::Write as _,
+    io::{self, BufRead, Write},
+};
+
+use serde_json::{json, Value};
+
+use crate::{methods::type_impls, skeleton::SourceStyle, RustDoc, RustDocItem};
+
+/// The protocol versions we support, latest first.
+const PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+/// The most items `search_items` returns.
+const MAX_SEARCH_RESULTS: usize = 20;
+
+// JSON-RPC error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+impl RustDoc {
+    /// Serve the docs as an MCP server, reading requests from `input` and
+    /// writing responses to `output`, until `input` ends.
+    pub fn serve_mcp(
+        &self,
+        input: impl BufRead,
+        mut output: impl Write,
+    ) -> io::Result<()> {
+        for line in input.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            if let Some(response) = self.handle_mcp_message(&line) {
+                writeln!(output, "{response}")?;
+                output.flush()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// The response to a JSON-RPC message, or `None` for notifications.
+    fn handle_mcp_message(&self, message: &str) -> Option<Value> {
+        let message = match serde_json::from_str::<Value>(message) {
+            Ok(message) => message,
+            Err(e) => return Some(error_response(Value::Null, PARSE_ERROR, e)),
+        };
+        let method = message.get("method").and_then(Value::as_str);
+        let Some(id) = message.get("id").cloned() else {
+            // Notifications, e.g. `notifications/initialized`, need no reply
+            return None;
+        };
+        let Some(method) = method else {
+            return Some(error_response(id, INVALID_REQUEST, "No method"));
+        };
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+
+        let result = match method {
+            "initialize" => Ok(self.mcp_initialize(&params)),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(json!({ "tools": mcp_tools() })),
+            "tools/call" => self.mcp_call_tool(&params),
+            _ => Err((METHOD_NOT_FOUND, format!("Unknown method {method}"))),
+        };
+        Some(match result {
+            Ok(result) =>
+                json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => error_response(id, code, message),
+        })
+    }
+
+    fn mcp_initialize(&self, params: &Value) -> Value {
+        let requested = params.get("protocolVersion").and_then(Value::as_str);
+        let version = requested
+            .filter(|version| PROTOCOL_VERSIONS.contains(version))
+            .unwrap_or(PROTOCOL_VERSIONS[0]);
+        json!({
+            "protocolVersion": version,
+            "capabilities": { "tools": {} },
+            "serverInfo": {
+                "name": "ai-rustdoc",
+                "version": env!("CARGO_PKG_VERSION"),
+            },
+            "instructions": format!(
+                "Docs of the Rust crate `{}` v{}.",
+                self.crate_name(),
+                self.crate_version
+            ),
+        })
+    }
+
+    /// Call a tool. Failures of the tool, e.g. an unknown path, are results
+    /// with `isError`, so that the agent sees them.
+    fn mcp_call_tool(&self, params: &Value) -> Result<Value, (i64, String)> {
+        let name = params.get("name").and_then(Value::as_str);
+        let arguments = params.get("arguments").unwrap_or(&Value::Null);
+        let argument = |key: &str| {
+            arguments.get(key).and_then(Value::as_str).ok_or_else(|| {
+                (INVALID_PARAMS, format!("Missing the argument `{key}`"))
+            })
+        };
+
+        let text = match name {
+            Some("search_items") => self.search_items_tool(argument("query")?),
+            Some("get_item") => self.get_item_tool(argument("path")?),
+            Some("list_module") => self.list_module_tool(argument("path")?),
+            Some("methods_of") => self.methods_of_tool(argument("type")?),
+            Some("implementors_of") =>
+                self.implementors_of_tool(argument("trait")?),
+            Some(name) =>
+                return Err((INVALID_PARAMS, format!("Unknown tool {name}"))),
+            None => return Err((INVALID_PARAMS, "No tool name".to_string())),
+        };
+        let (text, is_error) = match text {
+            Ok(text) => (text, false),
+            Err(text) => (text, true),
+        };
+        Ok(json!({
+            "content": [{ "type": "text", "text": text }],
+            "isError": is_error,
+        }))
+    }
+
+    fn search_items_tool(&self, query: &str) -> Result<String, String> {
+        let matches = self.search(query);
+        if matches.is_empty() {
+            return Err(format!("No items match `{query}`"));
+        }
+
+        let mut out = String::new();
+        for found in matches.iter().take(MAX_SEARCH_RESULTS) {
+            match &found.summary {
+                Some(summary) => writeln!(
+                    out,
+                    "- `{}` ({}): {summary}",
+                    found.path, found.kind
+                ),
+                None => writeln!(out, "- `{}` ({})", found.path, found.kind),
+            }
+            .expect("Writing to a String can't fail");
+        }
+        if matches.len() > MAX_SEARCH_RESULTS {
+            let more = matches.len() - MAX_SEARCH_RESULTS;
+            writeln!(out, "\nand {more} more; refine the query to see them")
+                .ok();
+        }
+        Ok(out)
+    }
+
+    fn get_item_tool(&self, path: &str) -> Result<String, String> {
+        let item = self.mcp_item(path)?;
+        if let Some(reason) = item.omitted_reason(self) {
+            return Err(format!("`{path}` is left out of the docs: {reason}"));
+        }
+        let mut out = String::new();
+        item.write_markdown(&mut out, self)
+            .map_err(|_| format!("Couldn't render `{path}`"))?;
+        // The docs write the items of traits and trait impls with the trait
+        // or impl, so write their source on its own
+        if out.is_empty() {
+            let style = SourceStyle {
+                full_docs: true,
+                ..SourceStyle::SKELETON
+            };
+            let mut source = String::new();
+            self.write_item_source(&mut source, item, style)
+                .map_err(|_| format!("Couldn't render `{path}`"))?;
+            if source.is_empty() {
+                return Err(format!("Couldn't render `{path}`"));
+            }
+            out = format!("```rust\n{source}```\n");
+        }
+        Ok(out)
+    }
+
+    fn list_module_tool(&self, path: &str) -> Result<String, String> {
+        let segments = self.mcp_path(path);
+        let (_, module) = self
+            .modules()
+            .into_iter()
+            .find(|(module_path, _)| *module_path == segments)
+            .ok_or_else(|| format!("No module `{path}`"))?;
+
+        let mut out = format!("# Module `{}`\n\n", segments.join("::"));
+        if let Some(summary) = module.summary() {
+            writeln!(out, "{summary}\n").ok();
+        }
+        for item in self.module_items(module) {
+            if let Some(import) = item.import() {
+                let glob = if import.glob { "::*" } else { "" };
+                writeln!(out, "- `pub use {}{glob}`", import.source).ok();
+                continue;
+            }
+            let Some(name) = &item.name else {
+                continue;
+            };
+            let item_path = [segments.clone(), vec![name.clone()]].concat();
+            write_item_line(&mut out, &item_path.join("::"), item, self);
+        }
+        Ok(out)
+    }
+
+    fn methods_of_tool(&self, type_path: &str) -> Result<String, String> {
+        let item = self.mcp_item(type_path)?;
+        if type_impls(item).is_none() {
+            return Err(format!("`{type_path}` isn't a struct, enum or union"));
+        }
+
+        let mut out = String::new();
+        self.write_available_methods(&mut out, &item.id)
+            .map_err(|_| format!("Couldn't render `{type_path}`"))?;
+        if out.is_empty() {
+            return Ok(format!("`{type_path}` has no methods"));
+        }
+        Ok(out)
+    }
+
+    fn implementors_of_tool(&self, trait_path: &str) -> Result<String, String> {
+        let item = self.mcp_item(trait_path)?;
+        let trait_ = item
+            .inner
+            .as_ref()
+            .and_then(|inner| inner.trait_.as_ref())
+            .ok_or_else(|| format!("`{trait_path}` isn't a trait"))?;
+
+        let mut out = String::new();
+        for impl_item in trait_
+            .implementations
+            .iter()
+            .filter_map(|id| self.index.get(id))
+            .filter(|impl_item| impl_item.omitted_reason(self).is_none())
+        {
+            if let Some(label) = self.item_label(impl_item) {
+                writeln!(out, "- `{label}`").ok();
+            }
+        }
+        if out.is_empty() {
+            return Ok(format!("`{trait_path}` has no implementors"));
+        }
+        Ok(out)
+    }
+
+    /// The item at a path, with or without the crate name, e.g.
+    /// `shapes::Point` or `common::shapes::Point::new`.
+    fn mcp_item(&self, path: &str) -> Result<&RustDocItem, String> {
+        let segments = self.mcp_path(path);
+        let not_found = || format!("No item `{path}`");
+        if let Some(id) = self.id_by_path(&segments) {
+            return self.index.get(id).ok_or_else(not_found);
+        }
+
+        // Members of types and traits, e.g. methods, have no path of their own
+        let (name, parent) = segments.split_last().ok_or_else(not_found)?;
+        let parent = self
+            .id_by_path(parent)
+            .and_then(|id| self.index.get(id))
+            .and_then(|parent| parent.inner.as_ref())
+            .ok_or_else(not_found)?;
+        let members = if let Some(struct_) = &parent.struct_ {
+            [&struct_.field_ids()[..], &struct_.impls].concat()
+        } else if let Some(enum_) = &parent.enum_ {
+            [&enum_.variants[..], &enum_.impls].concat()
+        } else if let Some(trait_) = &parent.trait_ {
+            trait_.items.clone()
+        } else {
+            Vec::new()
+        };
+        let impl_items = members
+            .iter()
+            .filter_map(|id| self.index.get(id))
+            .filter_map(|member| member.inner.as_ref()?.impl_.as_ref())
+            .filter(|impl_| !impl_.synthetic && impl_.blanket_impl.is_none())
+            .flat_map(|impl_| &impl_.items);
+        members
+            .iter()
+            .chain(impl_items)
+            .filter_map(|id| self.index.get(id))
+            .find(|member| member.name.as_ref() == Some(name))
+            .ok_or_else(not_found)
+    }
+
+    /// The segments of a path, starting with the crate name.
+    fn mcp_path(&self, path: &str) -> Vec<String> {
+        let mut segments = path
+            .trim()
+            .trim_matches('`')
+            .split("::")
+            .map(String::from)
+            .collect::<Vec<_>>();
+        if segments.first().map(String::as_str) != Some(self.crate_name()) {
+            segments.insert(0, self.crate_name().to_string());
+        }
+        segments
+    }
+}
+
+/// Write an item as a line of a list, e.g. `` - `common::greet` (function):
+/// Greets someone by name. ``
+fn write_item_line(
+    out: &mut String,
+    path: &str,
+    item: &RustDocItem,
+    doc: &RustDoc,
+) {
+    let kind = item.kind_name(doc);
+    match item.summary() {
+        Some(summary) => writeln!(out, "- `{path}` ({kind}): {summary}"),
+        None => writeln!(out, "- `{path}` ({kind})"),
+    }
+    .expect("Writing to a String can't fail");
+}
+
+fn error_response(id: Value, code: i64, message: impl ToString) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message.to_string() },
+    })
+}
+
+/// The tools the server provides, with JSON schemas of their arguments.
+fn mcp_tools() -> Value {
+    let tool = |name: &str, description: &str, argument: &str, about: &str| {
+        json!({
+            "name": name,
+            "description": description,
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    argument: { "type": "string", "description": about },
+                },
+                "required": [argument],
+            },
+        })
+    };
+    json!([
+        tool(
+            "search_items",
+            "Search the crate's items by name, path and docs, best first.",
+            "query",
+            "A name, path or question, e.g. `Point::new` or `bytes to hex`; \
+             `fn:`, `struct:` etc. filter by kind, and `&str -> Vec<u8>` \
+             finds functions by type",
+        ),
+        tool(
+            "get_item",
+            "Get the docs and signature of an item.",
+            "path",
+            "The path of the item, e.g. `shapes::Point::new`",
+        ),
+        tool(
+            "list_module",
+            "List the items of a module, with their summaries.",
+            "path",
+            "The path of the module, e.g. `shapes`, or the crate name",
+        ),
+        tool(
+            "methods_of",
+            "List the methods which can be called on a struct or enum, \
+             including trait and `Deref` methods, with where they come from.",
+            "type",
+            "The path of the type, e.g. `shapes::Point`",
+        ),
+        tool(
+            "implementors_of",
+            "List the impls of a trait.",
+            "trait",
+            "The path of the trait, e.g. `Draw`",
+        ),
+    ])
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const COMMON_JSON_STR: &str =
+        include_str!("../test-data/common/rustdoc.json");
+
+    /// Run the server with the given stdin, returning the responses.
+    fn serve(requests: &[Value]) -> Vec<Value> {
+        let doc = serde_json::from_str::<RustDoc>(COMMON_JSON_STR).unwrap();
+        let input = requests
+            .iter()
+            .map(|request| format!("{request}\n"))
+            .collect::<String>();
+        let mut output = Vec::new();
+        doc.serve_mcp(input.as_bytes(), &mut output).unwrap();
+        String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    fn call(id: u32, tool: &str, arguments: Value) -> Value {
+        json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": "tools/call",
+            "params": { "name": tool, "arguments": arguments },
+        })
+    }
+
+    fn text(response: &Value) -> &str {
+        response["result"]["content"][0]["text"].as_str().unwrap()
+    }
+
+    #[test]
+    fn test_mcp_session() {
+        let responses = serve(&[
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "initialize",
+                "params": {
+                    "protocolVersion": "2024-11-05",
+                    "capabilities": {},
+                    "clientInfo": { "name": "test", "version": "1" },
+                },
+            }),
+            json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }),
+            json!({ "jsonrpc": "2.0", "id": 2, "method": "tools/list" }),
+        ]);
+
+        // No response to the notification
+        assert_eq!(responses.len(), 2);
+        assert_eq!(responses[0]["id"], 1);
+        assert_eq!(responses[0]["result"]["protocolVersion"], "2024-11-05");
+        assert_eq!(responses[0]["result"]["serverInfo"]["name"], "ai-rustdoc");
+        let tools = responses[1]["result"]["tools"].as_array().unwrap();
+        let names = tools
+            .iter()
+            .map(|tool| tool["name"].as_str().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            [
+                "search_items",
+                "get_item",
+                "list_module",
+                "methods_of",
+                "implementors_of"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_mcp_tools() {
+        let responses = serve(&[
+            call(1, "search_items", json!({ "query": "point" })),
+            call(2, "get_item", json!({ "path": "shapes::Point::new" })),
+            call(3, "list_module", json!({ "path": "common::shapes" })),
+            call(4, "methods_of", json!({ "type": "shapes::Point" })),
+            call(5, "implementors_of", json!({ "trait": "Draw" })),
+            call(6, "get_item", json!({ "path": "nope" })),
+            call(7, "get_item", json!({ "path": "Draw::draw" })),
+            call(8, "get_item", json!({ "path": "shapes::Point::draw" })),
+        ]);
+
+        let search = text(&responses[0]);
+        assert!(search.starts_with(
+            "- `common::shapes::Point` (struct): A point on a 2D integer \
+             grid.\n"
+        ));
+        assert!(search.contains("- `common::shapes::Point::new` (method)"));
+
+        let item = text(&responses[1]);
+        assert!(item.starts_with("### pub new\n"));
+        assert!(item.contains("pub fn new(x: i32, y: i32) -> Self"));
+
+        let module = text(&responses[2]);
+        assert!(module.starts_with("# Module `common::shapes`\n"));
+        assert!(module.contains("- `common::shapes::Shape` (enum):"));
+
+        let methods = text(&responses[3]);
+        assert!(methods.contains("- `pub fn manhattan(&self) -> i32`: "));
+        assert!(methods.contains("- From `Draw`: `fn draw(&self) -> String`"));
+
+        let implementors = text(&responses[4]);
+        assert!(implementors.contains("- `impl Draw for shapes::Point`\n"));
+        assert!(implementors.contains("- `impl Draw for RawHandle`\n"));
+
+        assert_eq!(responses[5]["result"]["isError"], true);
+        assert_eq!(text(&responses[5]), "No item `nope`");
+
+        // Items of traits and trait impls
+        assert_eq!(responses[6]["result"]["isError"], false);
+        assert_eq!(
+            text(&responses[6]),
+            "```rust\n/// Draws the value.\nfn draw(&self) -> String;\n```\n"
+        );
+        assert_eq!(
+            text(&responses[7]),
+            "```rust\nfn draw(&self) -> String { ... }\n```\n"
+        );
+    }
+
+    #[test]
+    fn test_mcp_errors() {
+        let doc = serde_json::from_str::<RustDoc>(COMMON_JSON_STR).unwrap();
+        let input =
+            "not json\n\n{\"jsonrpc\":\"2.0\",\"id\":7,\"method\":\"nope\"}\n";
+        let mut output = Vec::new();
+        doc.serve_mcp(input.as_bytes(), &mut output).unwrap();
+        let responses = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str::<Value>(line).unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(responses[0]["error"]["code"], PARSE_ERROR);
+        assert_eq!(responses[0]["id"], Value::Null);
+        assert_eq!(responses[1]["error"]["code"], METHOD_NOT_FOUND);
+        assert_eq!(responses[1]["id"], 7);
+
+        let responses = serve(&[call(1, "get_item", json!({}))]);
+        assert_eq!(responses[0]["error"]["code"], INVALID_PARAMS);
+    }
+}
@@ -0,0 +1,513 @@
+//! The methods which can be called on a type: its inherent methods, the
+//! methods of the traits it implements, including blanket impls, and the
+//! methods reached through `Deref`, each with where it comes from.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::{self, Write},
+};
+
+use serde::Serialize;
+
+use crate::{
+    format_angle_bracketed_args, Impl, Parameter, ReturnType, RustDoc,
+    RustDocItem,
+};
+
+/// The id of the impl of each item of an impl, e.g. a method, by the id of
+/// the item.
+pub(crate) type MethodImpls = HashMap<String, String>;
+
+/// A method which can be called on a type.
+#[derive(Clone, Debug, Serialize)]
+pub struct AvailableMethod {
+    pub name: String,
+    /// The signature on one line, e.g. `pub fn new(x: i32, y: i32) -> Self`,
+    /// or `None` for provided methods of other crates' traits, which the
+    /// JSON doesn't have.
+    pub signature: Option<String>,
+    /// The first paragraph of the docs, on one line.
+    pub summary: Option<String>,
+    pub source: MethodSource,
+}
+
+/// Where a method of a type comes from.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub enum MethodSource {
+    /// An inherent impl, i.e. `impl Point`.
+    Inherent,
+    /// An impl of a trait for the type, e.g. `Clone`.
+    Trait(String),
+    /// A blanket impl of a trait, e.g. `ToOwned` for all `T: Clone`.
+    Blanket(String),
+    /// The methods of the `Deref` target, e.g. `Point` of `Labeled`.
+    Deref {
+        target: String,
+        source: Box<MethodSource>,
+    },
+}
+
+impl fmt::Display for MethodSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Inherent => write!(f, "Inherent"),
+            Self::Trait(trait_) => write!(f, "From `{trait_}`"),
+            Self::Blanket(trait_) =>
+                write!(f, "From the blanket impl of `{trait_}`"),
+            Self::Deref { target, source } => {
+                write!(f, "Through `Deref` to `{target}`")?;
+                match &**source {
+                    Self::Inherent => Ok(()),
+                    Self::Trait(trait_) => write!(f, ", from `{trait_}`"),
+                    Self::Blanket(trait_) =>
+                        write!(f, ", from the blanket impl of `{trait_}`"),
+                    source => write!(f, ", {source}"),
+                }
+            }
+        }
+    }
+}
+
+impl RustDoc {
+    /// The methods which can be called on the struct or enum with the given
+    /// id: inherent methods first, then trait methods, then methods of
+    /// blanket impls, then methods of the `Deref` target which the type
+    /// doesn't shadow.
+    pub fn available_methods(&self, id: &str) -> Vec<AvailableMethod> {
+        let mut methods = Vec::new();
+        self.collect_methods(id, None, &mut vec![id.to_string()], &mut methods);
+        methods
+    }
+
+    fn collect_methods(
+        &self,
+        id: &str,
+        deref_target: Option<&str>,
+        visited: &mut Vec<String>,
+        methods: &mut Vec<AvailableMethod>,
+    ) {
+        let Some(impl_ids) = self.index.get(id).and_then(type_impls) else {
+            return;
+        };
+        let impls = impl_ids
+            .iter()
+            .filter_map(|id| self.index.get(id))
+            .filter(|impl_item| impl_item.omitted_reason(self).is_none())
+            .filter_map(|impl_item| {
+                let impl_ = impl_item.inner.as_ref()?.impl_.as_ref()?;
+                (!impl_.synthetic).then_some(impl_)
+            })
+            // The impls of a type include other types' impls which mention
+            // it, e.g. `impl From<Point> for [i32; 2]`
+            .filter(|impl_| {
+                impl_.blanket_impl.is_some() || impl_type_id(impl_) == Some(id)
+            })
+            .collect::<Vec<_>>();
+
+        let mut own_methods = Vec::new();
+        for impl_ in &impls {
+            let source = match &impl_.trait_ {
+                None => MethodSource::Inherent,
+                Some(trait_) => {
+                    let trait_ = format!(
+                        "{}{}",
+                        trait_.name,
+                        format_angle_bracketed_args(trait_.args.as_ref())
+                    );
+                    match impl_.blanket_impl {
+                        Some(_) => MethodSource::Blanket(trait_),
+                        None => MethodSource::Trait(trait_),
+                    }
+                }
+            };
+            let source = match deref_target {
+                Some(target) => MethodSource::Deref {
+                    target: target.to_string(),
+                    source: Box::new(source),
+                },
+                None => source,
+            };
+            // Blanket impls apply to the outer type as well
+            if deref_target.is_some()
+                && matches!(impls_source(&source), MethodSource::Blanket(_))
+            {
+                continue;
+            }
+            self.collect_impl_methods(
+                impl_,
+                deref_target.is_some(),
+                source,
+                &mut own_methods,
+            );
+        }
+        own_methods.sort_by(|a, b| a.source.cmp(&b.source));
+
+        // Methods of the type shadow those of its `Deref` target, but the
+        // type's own impls can have methods of the same name, e.g. `fmt` of
+        // `Debug` and `Display`
+        let shadowing = methods.len();
+        for method in own_methods {
+            if !methods[..shadowing]
+                .iter()
+                .any(|m: &AvailableMethod| m.name == method.name)
+            {
+                methods.push(method);
+            }
+        }
+
+        let Some(target) =
+            impls.iter().find_map(|impl_| deref_target_of(self, impl_))
+        else {
+            return;
+        };
+        let target_name = target.to_string();
+        let target_id = match target {
+            ReturnType::ResolvedPath { resolved_path } =>
+                resolved_path.id.clone(),
+            _ => None,
+        };
+        match target_id {
+            Some(target_id) if !visited.contains(&target_id) => {
+                visited.push(target_id.clone());
+                self.collect_methods(
+                    &target_id,
+                    Some(&target_name),
+                    visited,
+                    methods,
+                );
+            }
+            _ => {}
+        }
+    }
+
+    /// The methods of an impl, including the provided methods of its trait.
+    fn collect_impl_methods(
+        &self,
+        impl_: &Impl,
+        receivers_only: bool,
+        source: MethodSource,
+        methods: &mut Vec<AvailableMethod>,
+    ) {
+        let is_trait_impl = impl_.trait_.is_some();
+        for item in impl_.items.iter().filter_map(|id| self.index.get(id)) {
+            let Some(name) = &item.name else {
+                continue;
+            };
+            // Associated functions can't be called through `Deref`
+            if !is_method(item)
+                || (receivers_only && !has_receiver(item))
+                || item.omitted_reason(self).is_some()
+            {
+                continue;
+            }
+            methods.push(AvailableMethod {
+                name: name.clone(),
+                signature: Some(method_signature(item, is_trait_impl)),
+                summary: item.summary().or_else(|| {
+                    self.trait_method(impl_, name)
+                        .and_then(|method| method.summary())
+                }),
+                source: source.clone(),
+            });
+        }
+
+        // Rustdoc lists the provided methods which the impl overrides too
+        let overridden = impl_
+            .items
+            .iter()
+            .filter_map(|id| self.index.get(id)?.name.as_ref())
+            .collect::<Vec<_>>();
+        for name in &impl_.provided_trait_methods {
+            if overridden.contains(&name) {
+                continue;
+            }
+            let method = self.trait_method(impl_, name);
+            // The methods of other crates' traits are unknown, but most of
+            // them take `self`
+            if method.is_some_and(|m| m.omitted_reason(self).is_some())
+                || (receivers_only && method.is_some_and(|m| !has_receiver(m)))
+            {
+                continue;
+            }
+            methods.push(AvailableMethod {
+                name: name.clone(),
+                signature: method
+                    .map(|method| method_signature(method, is_trait_impl)),
+                summary: method.and_then(|method| method.summary()),
+                source: source.clone(),
+            });
+        }
+    }
+
+    /// The impl which the method or other impl item with the given id is
+    /// in, e.g. to know what `Self` is.
+    pub(crate) fn method_impl(&self, id: &str) -> Option<&Impl> {
+        let impls = self.method_impls.get_or_init(|| self.find_method_impls());
+        let impl_item = self.index.get(impls.get(id)?)?;
+        impl_item.inner.as_ref()?.impl_.as_ref()
+    }
+
+    fn find_method_impls(&self) -> MethodImpls {
+        let mut method_impls = MethodImpls::new();
+        for (impl_id, item) in &self.index {
+            let Some(impl_) =
+                item.inner.as_ref().and_then(|i| i.impl_.as_ref())
+            else {
+                continue;
+            };
+            for id in &impl_.items {
+                method_impls.insert(id.clone(), impl_id.clone());
+            }
+        }
+        method_impls
+    }
+
+    /// The method of the trait of an impl with the given name, if the trait
+    /// is in this crate.
+    fn trait_method(&self, impl_: &Impl, name: &str) -> Option<&RustDocItem> {
+        let trait_id = impl_.trait_.as_ref()?.id.as_ref()?;
+        let trait_ =
+            self.index.get(trait_id)?.inner.as_ref()?.trait_.as_ref()?;
+        trait_
+            .items
+            .iter()
+            .filter_map(|id| self.index.get(id))
+            .find(|item| item.name.as_deref() == Some(name))
+    }
+
+    /// Write the available methods of a struct or enum as a list, with
+    /// inherent methods on their own lines, and other methods grouped by
+    /// where they come from.
+    pub(crate) fn write_available_methods(
+        &self,
+        out: &mut String,
+        id: &str,
+    ) -> fmt::Result {
+        let methods = self.available_methods(id);
+        if methods.is_empty() {
+            return Ok(());
+        }
+
+        writeln!(out, "**Methods:**")?;
+        let mut groups = BTreeMap::<&MethodSource, Vec<String>>::new();
+        for method in &methods {
+            let signature = method
+                .signature
+                .clone()
+                .unwrap_or_else(|| format!("fn {}", method.name));
+            if method.source != MethodSource::Inherent {
+                groups
+                    .entry(&method.source)
+                    .or_default()
+                    .push(format!("`{signature}`"));
+                continue;
+            }
+            match &method.summary {
+                Some(summary) => writeln!(out, "- `{signature}`: {summary}")?,
+                None => writeln!(out, "- `{signature}`")?,
+            }
+        }
+        for (source, signatures) in groups {
+            writeln!(out, "- {source}: {}", signatures.join(", "))?;
+        }
+        writeln!(out)
+    }
+}
+
+/// The impls of a struct or enum.
+pub(crate) fn type_impls(item: &RustDocItem) -> Option<&Vec<String>> {
+    let inner = item.inner.as_ref()?;
+    match (&inner.struct_, &inner.enum_, &inner.union_) {
+        (Some(struct_), ..) => Some(&struct_.impls),
+        (_, Some(enum_), _) => Some(&enum_.impls),
+        (.., Some(union_)) => Some(&union_.impls),
+        _ => None,
+    }
+}
+
+fn is_method(item: &RustDocItem) -> bool {
+    item.inner
+        .as_ref()
+        .is_some_and(|inner| inner.function.is_some())
+}
+
+/// Whether a function takes `self`, so that it can be called as a method.
+pub(crate) fn has_receiver(item: &RustDocItem) -> bool {
+    let function = item.inner.as_ref().and_then(|i| i.function.as_ref());
+    function.is_some_and(|function| {
+        function
+            .decl
+            .inputs
+            .first()
+            .is_some_and(|(name, _)| name == "self")
+    })
+}
+
+/// The source of a method, leaving out `Deref`.
+fn impls_source(source: &MethodSource) -> &MethodSource {
+    match source {
+        MethodSource::Deref { source, .. } => impls_source(source),
+        source => source,
+    }
+}
+
+/// The `Target` of an impl of `Deref`.
+fn deref_target_of<'a>(
+    doc: &'a RustDoc,
+    impl_: &Impl,
+) -> Option<&'a ReturnType> {
+    if impl_.trait_.as_ref()?.name != "Deref" {
+        return None;
+    }
+    impl_
+        .items
+        .iter()
+        .filter_map(|id| doc.index.get(id))
+        .filter(|item| item.name.as_deref() == Some("Target"))
+        .find_map(|item| {
+            item.inner.as_ref()?.assoc_type.as_ref()?.default.as_ref()
+        })
+}
+
+/// The signature of a method, e.g. `pub fn new(x: i32, y: i32) -> Self`,
+/// or `fn clone(&self) -> Point` in trait impls.
+pub(crate) fn method_signature(
+    item: &RustDocItem,
+    is_trait_impl: bool,
+) -> String {
+    let vis = if is_trait_impl || !item.is_public() {
+        ""
+    } else {
+        "pub "
+    };
+    let mut signature = String::new();
+    item.write_fn_signature(&mut signature, vis)
+        .expect("Writing to a String can't fail");
+    // Derived impls have the paths of the derive macro
+    signature.replace("$crate::", "")
+}
+
+/// The id of the type of an impl, e.g. of `Point` for `impl Point`.
+pub(crate) fn impl_type_id(impl_: &Impl) -> Option<&str> {
+    match impl_.for_.as_ref()? {
+        Parameter::ResolvedPath { resolved_path } =>
+            resolved_path.id.as_deref(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const COMMON_JSON_STR: &str =
+        include_str!("../test-data/common/rustdoc.json");
+    const HEX_JSON_STR: &str = include_str!("../test-data/hex/rustdoc.json");
+
+    fn methods(doc: &RustDoc, name: &str) -> Vec<AvailableMethod> {
+        let (id, _) = doc
+            .index
+            .iter()
+            .find(|(_, item)| {
+                item.name.as_deref() == Some(name) && type_impls(item).is_some()
+            })
+            .unwrap();
+        doc.available_methods(id)
+    }
+
+    #[test]
+    fn test_available_methods() {
+        let doc = serde_json::from_str::<RustDoc>(COMMON_JSON_STR).unwrap();
+        let point = methods(&doc, "Point");
+        let method = |name: &str| {
+            point
+                .iter()
+                .find(|method| method.name == name)
+                .unwrap_or_else(|| panic!("No method {name}"))
+        };
+
+        assert_eq!(method("new").source, MethodSource::Inherent);
+        assert_eq!(
+            method("manhattan").signature.as_deref(),
+            Some("pub fn manhattan(&self) -> i32")
+        );
+        assert_eq!(method("draw").source, MethodSource::Trait("Draw".into()));
+        // Provided methods, of this crate's traits and of others
+        assert_eq!(
+            method("draw_debug").signature.as_deref(),
+            Some("fn draw_debug(&self) -> String")
+        );
+        assert_eq!(method("clone_from").signature, None);
+        assert_eq!(
+            method("to_owned").source,
+            MethodSource::Blanket("ToOwned".into())
+        );
+        // Inherent methods come first
+        assert_eq!(point[0].name, "new");
+        // Methods of different traits can have the same name
+        let sources = |methods: &[AvailableMethod], name: &str| {
+            methods
+                .iter()
+                .filter(|method| method.name == name)
+                .map(|method| method.source.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            sources(&point, "from"),
+            [
+                MethodSource::Trait("From<[i32; 2]>".into()),
+                MethodSource::Blanket("From<T>".into()),
+            ]
+        );
+        // Not `impl From<Point> for [i32; 2]`, which mentions the type
+        assert!(!point.iter().any(|method| method
+            .signature
+            .as_deref()
+            .is_some_and(|signature| signature.contains("point: Point"))));
+        // Overridden provided methods are listed once
+        assert_eq!(sources(&point, "clone_into").len(), 1);
+
+        let hex = serde_json::from_str::<RustDoc>(HEX_JSON_STR).unwrap();
+        assert_eq!(
+            sources(&methods(&hex, "HexDisplay"), "fmt"),
+            [
+                MethodSource::Trait("Debug".into()),
+                MethodSource::Trait("Display".into()),
+            ]
+        );
+
+        let labeled = methods(&doc, "Labeled");
+        let through_deref = labeled
+            .iter()
+            .find(|method| method.name == "manhattan")
+            .unwrap();
+        assert_eq!(
+            through_deref.source,
+            MethodSource::Deref {
+                target: "Point".into(),
+                source: Box::new(MethodSource::Inherent),
+            }
+        );
+        // Associated functions can't be called through `Deref`, and the
+        // type's own methods shadow its target's
+        assert_eq!(labeled.iter().filter(|m| m.name == "new").count(), 1);
+        assert_eq!(labeled.iter().filter(|m| m.name == "into").count(), 1);
+    }
+
+    #[test]
+    fn test_write_available_methods() {
+        let doc = serde_json::from_str::<RustDoc>(COMMON_JSON_STR).unwrap();
+        let markdown = doc.to_markdown();
+        assert!(markdown.contains(
+            "**Methods:**\n- `pub fn new(x: i32, y: i32) -> Self`: Creates a \
+             new point.\n"
+        ));
+        assert!(markdown.contains(
+            "- From `Draw`: `fn draw(&self) -> String`, `fn draw_debug(&self) \
+             -> String`\n"
+        ));
+        assert!(markdown.contains(
+            "- Through `Deref` to `Point`: `pub fn manhattan(&self) -> i32`\n"
+        ));
+    }
+}
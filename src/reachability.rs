@@ -0,0 +1,371 @@
+//! Find the public API of a crate: every item which downstream users can
+//! name, and the public paths they can name it by.
+//!
+//! Starting at the crate root, we walk `pub` items of modules, following
+//! `pub mod`s and `pub use` re-exports, including glob re-exports. Items in
+//! private modules are only reachable if they are re-exported somewhere
+//! public.
+
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+use crate::{Attribute, Parameter, RustDoc, RustDocItem};
+
+/// The public paths of every reachable item, keyed by item id.
+#[derive(Debug, Default)]
+pub(crate) struct PublicApi {
+    /// The public paths of each item, shortest first.
+    paths: BTreeMap<String, Vec<Vec<String>>>,
+    /// The impl, trait, struct or enum which directly contains each item.
+    parents: BTreeMap<String, String>,
+    /// The module which directly contains each item.
+    modules: BTreeMap<String, String>,
+}
+
+impl PublicApi {
+    pub(crate) fn compute(doc: &RustDoc) -> Self {
+        let mut api = Self::default();
+        for (id, item) in &doc.index {
+            for child_id in doc.child_ids(item) {
+                api.parents.insert(child_id, id.clone());
+            }
+            if let Some(module) =
+                item.inner.as_ref().and_then(|i| i.module.as_ref())
+            {
+                for child_id in &module.items {
+                    api.modules.insert(child_id.clone(), id.clone());
+                }
+            }
+        }
+
+        let mut visited_modules = BTreeSet::new();
+        let mut queue = VecDeque::new();
+
+        let root_path = vec![doc.crate_name().to_string()];
+        api.add_path(&doc.root, root_path.clone());
+        queue.push_back((doc.root.clone(), root_path.clone()));
+
+        // `#[macro_export]` macros are at the crate root, whichever module
+        // they are defined in
+        for (id, item) in &doc.index {
+            if let Some(name) = &item.name {
+                if item.has_attribute(&Attribute::MacroExport) {
+                    api.add_path(
+                        id,
+                        [root_path.clone(), vec![name.clone()]].concat(),
+                    );
+                }
+            }
+        }
+
+        // Breadth-first, so that the first path found for each item is one of
+        // the shortest.
+        while let Some((module_id, module_path)) = queue.pop_front() {
+            if !visited_modules.insert(module_id.clone()) {
+                continue;
+            }
+            let Some(module) = doc.index.get(&module_id) else {
+                continue;
+            };
+            let Some(module_details) =
+                module.inner.as_ref().and_then(|i| i.module.as_ref())
+            else {
+                continue;
+            };
+
+            for (name, target_id) in doc.module_children(&module_details.items)
+            {
+                let mut path = module_path.clone();
+                path.push(name.to_string());
+                api.add_path(target_id, path.clone());
+
+                let is_module = doc
+                    .index
+                    .get(target_id)
+                    .and_then(|target| target.inner.as_ref())
+                    .is_some_and(|inner| inner.module.is_some());
+                if is_module {
+                    queue.push_back((target_id.to_string(), path));
+                }
+            }
+        }
+
+        api
+    }
+
+    fn add_path(&mut self, id: &str, path: Vec<String>) {
+        let paths = self.paths.entry(id.to_string()).or_default();
+        if !paths.contains(&path) {
+            paths.push(path);
+        }
+    }
+
+    /// The shortest public path of a directly reachable item.
+    fn path(&self, id: &str) -> Option<&Vec<String>> {
+        self.paths.get(id).and_then(|paths| paths.first())
+    }
+
+    /// All public paths of a directly reachable item, shortest first.
+    pub(crate) fn paths(&self, id: &str) -> &[Vec<String>] {
+        self.paths.get(id).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// The id of the item which directly contains an item.
+    pub(crate) fn parent(&self, id: &str) -> Option<&String> {
+        self.parents.get(id)
+    }
+
+    /// The id of the module which directly contains an item.
+    pub(crate) fn module(&self, id: &str) -> Option<&String> {
+        self.modules.get(id)
+    }
+
+    /// The ids of all directly reachable items.
+    pub(crate) fn ids(&self) -> impl Iterator<Item = &String> {
+        self.paths.keys()
+    }
+}
+
+impl RustDoc {
+    /// The public API, computed on first use.
+    pub(crate) fn api(&self) -> &PublicApi {
+        self.public_api.get_or_init(|| PublicApi::compute(self))
+    }
+
+    /// The names which the public children of a module make nameable, with
+    /// the ids of the items they name.
+    ///
+    /// `pub use path::Item;` names `Item`, and `pub use path::*;` names all
+    /// public items of a module, or all variants of an enum. Glob imports
+    /// of other crates' modules can't be expanded, since the module's items
+    /// aren't in our JSON.
+    pub(crate) fn module_children<'a>(
+        &'a self,
+        items: &'a [String],
+    ) -> Vec<(&'a str, &'a str)> {
+        let mut children = Vec::new();
+        let mut expanded_globs = BTreeSet::new();
+        self.collect_module_children(items, &mut expanded_globs, &mut children);
+        children
+    }
+
+    fn collect_module_children<'a>(
+        &'a self,
+        items: &'a [String],
+        expanded_globs: &mut BTreeSet<&'a str>,
+        children: &mut Vec<(&'a str, &'a str)>,
+    ) {
+        for child_id in items {
+            let Some(child) = self.index.get(child_id) else {
+                continue;
+            };
+            if !child.is_public() {
+                continue;
+            }
+
+            let Some(import) = child.import() else {
+                if let Some(name) = &child.name {
+                    children.push((name, child_id));
+                }
+                continue;
+            };
+            let Some(target_id) = import.id.as_deref() else {
+                continue;
+            };
+            if !import.glob {
+                children.push((&import.name, target_id));
+                continue;
+            }
+
+            // Guard against glob imports which (indirectly) import themselves
+            if !expanded_globs.insert(target_id) {
+                continue;
+            }
+            let Some(target_inner) =
+                self.index.get(target_id).and_then(|t| t.inner.as_ref())
+            else {
+                continue;
+            };
+            if let Some(module) = &target_inner.module {
+                self.collect_module_children(
+                    &module.items,
+                    expanded_globs,
+                    children,
+                );
+            } else if let Some(enum_) = &target_inner.enum_ {
+                for variant_id in &enum_.variants {
+                    if let Some(name) = self
+                        .index
+                        .get(variant_id)
+                        .and_then(|variant| variant.name.as_ref())
+                    {
+                        children.push((name, variant_id));
+                    }
+                }
+            }
+        }
+    }
+
+    /// The public paths of every item downstream users can name, sorted.
+    pub fn public_api(&self) -> Vec<String> {
+        let mut paths = self
+            .api()
+            .paths
+            .values()
+            .flatten()
+            .map(|path| path.join("::"))
+            .collect::<Vec<_>>();
+        paths.sort();
+        paths
+    }
+
+    /// The public path of an item, e.g. `["common", "Widget"]` for a struct
+    /// defined in a private module and re-exported at the crate root.
+    ///
+    /// Associated items (methods, fields, variants) are public if they are
+    /// `pub` and their parent is public. Variants and their fields are as
+    /// public as their enum. Impls are public if their type is.
+    pub(crate) fn public_path(&self, id: &str) -> Option<Vec<String>> {
+        if let Some(path) = self.api().path(id) {
+            return Some(path.clone());
+        }
+
+        let item = self.index.get(id)?;
+        if let Some(impl_) = item.inner.as_ref()?.impl_.as_ref() {
+            // Impls are usable if the type and trait are. External types and
+            // traits are always public.
+            let is_public = |id: Option<&str>| match id {
+                Some(id) if id.starts_with("0:") => self.is_public_api(id),
+                _ => true,
+            };
+            let for_id = match &impl_.for_ {
+                Some(Parameter::ResolvedPath { resolved_path }) =>
+                    resolved_path.id.as_deref(),
+                _ => None,
+            };
+            let trait_id = impl_.trait_.as_ref().and_then(|t| t.id.as_deref());
+            // Impls themselves don't have a path.
+            return (is_public(for_id) && is_public(trait_id)).then(Vec::new);
+        }
+
+        let (parent_id, parent) = self.parent(id)?;
+        let parent_inner = parent.inner.as_ref()?;
+        let mut path = match &parent_inner.impl_ {
+            // Items in impls live under the type the impl is for.
+            Some(impl_) => {
+                self.public_path(parent_id)?;
+                match &impl_.for_ {
+                    Some(Parameter::ResolvedPath { resolved_path }) =>
+                        self.public_path(resolved_path.id.as_deref()?)?,
+                    _ => return None,
+                }
+            }
+            None => self.public_path(parent_id)?,
+        };
+
+        // Items of traits, trait impls, enums and variants are as public as
+        // their parent. Other items need to be `pub` themselves.
+        let inherits_visibility = parent_inner.trait_.is_some()
+            || parent_inner.enum_.is_some()
+            || parent_inner.variant.is_some()
+            || parent_inner
+                .impl_
+                .as_ref()
+                .is_some_and(|impl_| impl_.trait_.is_some());
+        if !inherits_visibility && !item.is_public() {
+            return None;
+        }
+
+        path.push(item.name.clone()?);
+        Some(path)
+    }
+
+    /// Whether downstream users can name the item with this id.
+    pub(crate) fn is_public_api(&self, id: &str) -> bool {
+        self.public_path(id).is_some()
+    }
+}
+
+impl RustDocItem {
+    pub(crate) fn is_public(&self) -> bool {
+        self.visibility.as_deref() == Some("public")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::PrintOptions;
+
+    const COMMON_JSON_STR: &str =
+        include_str!("../test-data/common/rustdoc.json");
+    const HEX_JSON_STR: &str = include_str!("../test-data/hex/rustdoc.json");
+
+    #[test]
+    fn test_public_api() {
+        let doc = serde_json::from_str::<RustDoc>(HEX_JSON_STR).unwrap();
+        assert_eq!(
+            doc.public_api(),
+            [
+                "hex",
+                "hex::DecodeError",
+                "hex::FromHex",
+                "hex::HexDisplay",
+                "hex::decode",
+                "hex::decode_const",
+                "hex::decode_to_slice",
+                "hex::display",
+                "hex::encode",
+            ]
+        );
+
+        let doc = serde_json::from_str::<RustDoc>(COMMON_JSON_STR).unwrap();
+        let api = doc.public_api();
+        // Re-exported from a private module.
+        assert!(api.contains(&"common::Widget".to_string()));
+        assert!(api.contains(&"common::prelude::Draw".to_string()));
+        assert!(api.contains(&"common::shapes::Point".to_string()));
+        // `#[macro_export]` macros are at the crate root, even if defined in
+        // a module, and other macros aren't exported.
+        assert!(api.contains(&"common::pt".to_string()));
+        assert!(!api.contains(&"common::shapes::pt".to_string()));
+        assert!(!api.iter().any(|path| path.contains("double")));
+        // Private, or public but in a private module without a re-export.
+        assert!(!api.iter().any(|path| path.contains("::imp")));
+        assert!(!api.iter().any(|path| path.contains("unreachable_helper")));
+        assert!(!api.iter().any(|path| path.contains("private_helper")));
+    }
+
+    #[test]
+    fn test_print_public_only() {
+        let markdown = serde_json::from_str::<RustDoc>(COMMON_JSON_STR)
+            .unwrap()
+            .with_options(PrintOptions {
+                public_only: true,
+                ..Default::default()
+            })
+            .to_markdown();
+
+        // Items are shown under their public path, not their definition path.
+        assert!(markdown.contains("### pub common::Widget\n"));
+        assert!(markdown.contains("### pub common::Widget::new\n"));
+        assert!(markdown.contains("### pub common::shapes::Point::manhattan\n"));
+        // Fields are written in their structs, without headings of their own.
+        assert!(markdown
+            .contains("/// The horizontal coordinate.\n    pub x: i32,\n"));
+        assert!(!markdown.contains("### pub common::shapes::Point::x\n"));
+        assert!(!markdown.contains("common::imp::"));
+        assert!(!markdown.contains("unreachable_helper"));
+        assert!(!markdown.contains("private_helper"));
+        // Private fields are left out, but the public method is kept.
+        assert!(!markdown.contains("### common::Config::name\n"));
+        assert!(markdown.contains("### pub common::Config::name\n"));
+        assert!(markdown.contains(
+            "pub struct Config {\n    // Some fields are private\n}\n"
+        ));
+        // Trait impls for public types are kept.
+        assert!(markdown
+            .contains("### Implementation of `Draw` for `shapes::Point`"));
+        // Signatures use the plain name.
+        assert!(markdown.contains("pub fn greet("));
+    }
+}
@@ -0,0 +1,359 @@
+//! `pub use` re-exports, including glob re-exports and re-exports of items
+//! from other crates.
+//!
+//! Many crates expose most of their API through re-exports, e.g. a prelude
+//! module, or a facade over a private module tree. We list each re-export in
+//! the module which re-exports it, and note the alternate paths of an item
+//! where it is documented.
+
+use std::fmt::{self, Write};
+
+use crate::{RustDoc, RustDocItem};
+
+/// An item which is nameable at a path other than the one it is defined at,
+/// because of a `pub use`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReExport {
+    /// The path the item is re-exported as, e.g. `common::prelude::Point`.
+    pub path: Vec<String>,
+    /// The path of the `use` item as written, e.g. `crate::shapes::*`.
+    pub source: String,
+    /// The path the item is defined at, e.g. `common::shapes::Point`, if
+    /// known.
+    pub target: Option<Vec<String>>,
+    /// The name of the crate which defines the item.
+    pub target_crate: String,
+    /// The id of the item, if it is defined in this crate.
+    id: Option<String>,
+}
+
+impl ReExport {
+    /// The path of the module which re-exports the item.
+    pub fn module(&self) -> &[String] {
+        &self.path[..self.path.len() - 1]
+    }
+
+    /// The name the item is re-exported as.
+    pub fn name(&self) -> &str {
+        &self.path[self.path.len() - 1]
+    }
+}
+
+impl RustDoc {
+    /// All public re-exports of this crate, one per item they make nameable.
+    ///
+    /// Glob re-exports of other crates' modules are expanded using the JSON of
+    /// those crates in `dependencies`, if it is given.
+    pub fn reexports(&self, dependencies: &[RustDoc]) -> Vec<ReExport> {
+        let api = self.api();
+        let mut reexports = Vec::new();
+
+        for module_id in api.ids() {
+            let Some(module) = self
+                .index
+                .get(module_id)
+                .and_then(|module| module.inner.as_ref())
+                .and_then(|inner| inner.module.as_ref())
+            else {
+                continue;
+            };
+
+            for child_id in &module.items {
+                let Some(import) =
+                    self.index.get(child_id).and_then(RustDocItem::import)
+                else {
+                    continue;
+                };
+
+                // (name, crate which defines the item, its id in that crate)
+                let mut targets = self
+                    .module_children(std::slice::from_ref(child_id))
+                    .into_iter()
+                    .map(|(name, id)| (name, self, id))
+                    .collect::<Vec<_>>();
+                if targets.is_empty() && import.glob {
+                    if let Some((dependency, module_id)) = import
+                        .id
+                        .as_deref()
+                        .and_then(|id| self.find_in(dependencies, id))
+                    {
+                        let items = dependency
+                            .index
+                            .get(module_id)
+                            .and_then(|module| module.inner.as_ref())
+                            .and_then(|inner| inner.module.as_ref())
+                            .map(|module| module.items.as_slice())
+                            .unwrap_or_default();
+                        targets = dependency
+                            .module_children(items)
+                            .into_iter()
+                            .map(|(name, id)| (name, dependency, id))
+                            .collect();
+                    }
+                }
+
+                // Rustdoc leaves the `::*` off the source of glob imports
+                let source = if import.glob {
+                    format!("{}::*", import.source)
+                } else {
+                    import.source.clone()
+                };
+                for module_path in api.paths(module_id) {
+                    for &(name, crate_doc, id) in &targets {
+                        let mut path = module_path.clone();
+                        path.push(name.to_string());
+
+                        // Otherwise the item is defined in a crate which
+                        // `crate_doc` depends on.
+                        let is_defined_there = id.starts_with("0:")
+                            && crate_doc.index.contains_key(id);
+                        let target = crate_doc.item_path(id);
+                        let target_crate = match &target {
+                            Some(target) if !is_defined_there =>
+                                target[0].clone(),
+                            _ => crate_doc.crate_name().to_string(),
+                        };
+                        if target.as_ref() == Some(&path) {
+                            continue;
+                        }
+
+                        reexports.push(ReExport {
+                            path,
+                            source: source.clone(),
+                            target,
+                            target_crate,
+                            id: (is_defined_there
+                                && std::ptr::eq(crate_doc, self))
+                            .then(|| id.to_string()),
+                        });
+                    }
+                }
+            }
+        }
+
+        reexports.sort_by(|a, b| a.path.cmp(&b.path));
+        reexports
+    }
+
+    /// The id of the local item with the given definition or public path.
+    pub(crate) fn id_by_path(&self, path: &[String]) -> Option<&str> {
+        let by_definition_path = self
+            .paths
+            .iter()
+            .find(|(_, summary)| summary.crate_id == 0 && summary.path == path);
+        if let Some((id, _)) = by_definition_path {
+            return Some(id);
+        }
+
+        let api = self.api();
+        api.ids()
+            .find(|id| api.paths(id).iter().any(|public| public == path))
+            .map(String::as_str)
+    }
+
+    /// Write the re-exports of each module, linking to the items they name.
+    pub(crate) fn write_reexports(
+        &self,
+        out: &mut String,
+        dependencies: &[RustDoc],
+    ) -> fmt::Result {
+        let reexports = self.reexports(dependencies);
+
+        // Group by module, then by `use` item, keeping the order of both.
+        let mut modules =
+            Vec::<(&[String], Vec<(&str, Vec<&ReExport>)>)>::new();
+        for reexport in &reexports {
+            let is_omitted = reexport
+                .id
+                .as_ref()
+                .and_then(|id| self.index.get(id))
+                .is_some_and(|item| item.omitted_reason(self).is_some());
+            if is_omitted {
+                continue;
+            }
+
+            let module = reexport.module();
+            let uses = match modules.iter_mut().find(|(m, _)| *m == module) {
+                Some((_, uses)) => uses,
+                None => {
+                    modules.push((module, Vec::new()));
+                    &mut modules.last_mut().unwrap().1
+                }
+            };
+            match uses
+                .iter_mut()
+                .find(|(source, _)| *source == reexport.source)
+            {
+                Some((_, items)) => items.push(reexport),
+                None => uses.push((&reexport.source, vec![reexport])),
+            }
+        }
+
+        if modules.is_empty() {
+            return Ok(());
+        }
+
+        writeln!(out, "## Re-exports")?;
+        writeln!(out)?;
+        for (module, uses) in modules {
+            writeln!(out, "### `{}`", module.join("::"))?;
+            writeln!(out)?;
+            for (source, items) in uses {
+                let items = items
+                    .iter()
+                    .map(|reexport| self.reexport_link(reexport, dependencies))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                writeln!(out, "- `pub use {source};`: {items}")?;
+            }
+            writeln!(out)?;
+        }
+
+        Ok(())
+    }
+
+    /// A link to a re-exported item, to the docs of the crate which defines
+    /// it if we have them, or otherwise just its full path.
+    fn reexport_link(
+        &self,
+        reexport: &ReExport,
+        dependencies: &[RustDoc],
+    ) -> String {
+        let name = reexport.name();
+        if let Some(item) =
+            reexport.id.as_ref().and_then(|id| self.index.get(id))
+        {
+            return format!("[`{name}`](#{})", item.anchor());
+        }
+
+        let Some(target) = &reexport.target else {
+            return format!("`{name}`");
+        };
+        let dependency_item = dependencies
+            .iter()
+            .find(|dependency| dependency.crate_name() == reexport.target_crate)
+            .and_then(|dependency| {
+                let item =
+                    dependency.index.get(dependency.id_by_path(target)?)?;
+                Some((dependency, item))
+            });
+        match dependency_item {
+            Some((dependency, item)) =>
+                format!("[`{name}`]({})", dependency.link_to(item)),
+            None => format!("`{name}` (`{}`)", target.join("::")),
+        }
+    }
+}
+
+impl RustDocItem {
+    /// Write a note listing the other public paths of an item, e.g.
+    /// `common::prelude::Point` for `common::shapes::Point`.
+    pub(crate) fn write_alternate_paths(
+        &self,
+        out: &mut String,
+        doc: &RustDoc,
+    ) -> fmt::Result {
+        let paths = doc.api().paths(&self.id);
+        // The path shown in the heading, or the definition path otherwise.
+        // Rustdoc leaves some items out of `paths`, e.g. derive macros.
+        let shown = if doc.options.public_only {
+            paths.first().cloned()
+        } else {
+            doc.item_path(&self.id).or_else(|| paths.first().cloned())
+        };
+        let alternates = paths
+            .iter()
+            .filter(|path| Some(*path) != shown.as_ref())
+            .map(|path| format!("`{}`", path.join("::")))
+            .collect::<Vec<_>>();
+        if alternates.is_empty() {
+            return Ok(());
+        }
+
+        writeln!(out, "*Also available as* {}.", alternates.join(", "))?;
+        writeln!(out)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const COMMON_JSON_STR: &str =
+        include_str!("../test-data/common/rustdoc.json");
+    const FACADE_JSON_STR: &str =
+        include_str!("../test-data/facade/rustdoc.json");
+
+    fn find<'a>(reexports: &'a [ReExport], path: &str) -> &'a ReExport {
+        reexports
+            .iter()
+            .find(|reexport| reexport.path.join("::") == path)
+            .unwrap_or_else(|| panic!("No re-export at {path}"))
+    }
+
+    #[test]
+    fn test_reexports() {
+        let common = serde_json::from_str::<RustDoc>(COMMON_JSON_STR).unwrap();
+        let reexports = common.reexports(&[]);
+
+        // Re-exported from a private module
+        let widget = find(&reexports, "common::Widget");
+        assert_eq!(widget.source, "imp::Widget");
+        assert_eq!(
+            widget.target.as_ref().unwrap().join("::"),
+            "common::imp::Widget"
+        );
+
+        // Glob re-exports of a module and of an enum's variants
+        let point = find(&reexports, "common::prelude::Point");
+        assert_eq!(point.source, "crate::shapes::*");
+        assert_eq!(point.target_crate, "common");
+        find(&reexports, "common::prelude::Shape");
+        find(&reexports, "common::prelude::Warn");
+
+        // Re-exported from another crate
+        let display = find(&reexports, "common::prelude::Display");
+        assert_eq!(display.target_crate, "core");
+        assert_eq!(display.id, None);
+
+        // Globs of other crates' modules need that crate's JSON
+        let facade = serde_json::from_str::<RustDoc>(FACADE_JSON_STR).unwrap();
+        let reexports = facade.reexports(&[]);
+        find(&reexports, "facade::greet");
+        assert!(!reexports.iter().any(|r| r.name() == "Point"));
+
+        let reexports = facade.reexports(&[common]);
+        let point = find(&reexports, "facade::Point");
+        assert_eq!(point.source, "common::shapes::*");
+        assert_eq!(point.target_crate, "common");
+        assert_eq!(
+            point.target.as_ref().unwrap().join("::"),
+            "common::shapes::Point"
+        );
+        find(&reexports, "facade::Shape");
+    }
+
+    #[test]
+    fn test_print_reexports() {
+        let doc = serde_json::from_str::<RustDoc>(COMMON_JSON_STR).unwrap();
+        let markdown = doc.to_markdown();
+
+        assert!(markdown.contains("## Re-exports\n"));
+        assert!(markdown.contains(
+            "### `common::prelude`\n\n\
+             - `pub use std::fmt::Display;`: `Display` (`core::fmt::Display`)\n"
+        ));
+        assert!(markdown.contains(
+            "- `pub use crate::shapes::*;`: [`Labeled`](#labeled-struct), \
+             [`Point`](#point-struct), [`Shape`](#shape-enum)\n"
+        ));
+        assert!(markdown.contains(
+            "- `pub use imp::Widget;`: [`Widget`](#widget-struct)\n"
+        ));
+
+        // Items note their alternate paths where they are documented
+        assert!(markdown.contains("*Also available as* `common::Widget`.\n"));
+        assert!(markdown
+            .contains("*Also available as* `common::prelude::Point`.\n"));
+    }
+}
@@ -0,0 +1,341 @@
+//! Searching the items of a crate by name, path and docs, e.g. to answer
+//! "what function turns bytes into hex?"
+
+use serde::Serialize;
+
+use crate::{typesearch::parse_type_signature, RustDoc, RustDocItem};
+
+/// Words which say little about what is searched for.
+const STOP_WORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "by", "can", "do", "does",
+    "for", "from", "how", "i", "in", "into", "is", "it", "me", "my", "of",
+    "on", "or", "that", "the", "this", "to", "what", "which", "with",
+];
+
+/// The kinds which a query can be filtered by, e.g. `fn:encode`, with the
+/// kinds of [`Match::kind`] which they select.
+const KIND_FILTERS: &[(&str, &[&str])] = &[
+    ("fn", &["function", "method"]),
+    ("function", &["function"]),
+    ("method", &["method"]),
+    ("struct", &["struct"]),
+    ("enum", &["enum"]),
+    ("union", &["union"]),
+    ("trait", &["trait"]),
+    ("variant", &["variant"]),
+    ("field", &["field"]),
+    ("static", &["static"]),
+    ("macro", &["macro", "attribute macro", "derive macro"]),
+    ("derive", &["derive macro"]),
+    ("mod", &["module"]),
+    ("module", &["module"]),
+];
+
+/// An item which matches a search query.
+#[derive(Clone, Debug, Serialize)]
+pub struct Match {
+    /// The rustdoc id of the item.
+    pub id: String,
+    /// The path of the item, e.g. `hex::encode`.
+    pub path: String,
+    /// What kind of item it is, e.g. `function` or `struct`.
+    pub kind: &'static str,
+    /// How well the item matches; higher is better.
+    pub score: u32,
+    /// The signature, as shown in the markdown docs.
+    pub signature: Option<String>,
+    /// The first paragraph of the docs, on one line.
+    pub summary: Option<String>,
+}
+
+impl RustDoc {
+    /// The items which match a query, best first. Names and paths are
+    /// matched fuzzily, e.g. `ecnode` finds `encode`, and each word of the
+    /// query is looked up in the docs. A query can be filtered by kind with
+    /// a prefix, as in rustdoc's search, e.g. `fn:hex` or `struct:point`.
+    /// Queries with an arrow find functions by type, e.g. `&str -> String`,
+    /// as with [`RustDoc::find_fns`].
+    pub fn search(&self, query: &str) -> Vec<Match> {
+        if query.contains("->") {
+            let Ok((inputs, output)) = parse_type_signature(query) else {
+                return Vec::new();
+            };
+            let inputs = inputs.iter().map(String::as_str).collect::<Vec<_>>();
+            return self
+                .find_fns(&inputs, output.as_deref())
+                .unwrap_or_default();
+        }
+
+        let (kinds, query) = match query.split_once(':') {
+            Some((kind, rest)) if !rest.starts_with(':') => {
+                match KIND_FILTERS.iter().find(|(name, _)| *name == kind) {
+                    Some((_, kinds)) => (Some(*kinds), rest),
+                    None => (None, query),
+                }
+            }
+            _ => (None, query),
+        };
+        let query = query.trim().trim_matches('`').to_lowercase();
+        let terms = search_terms(&query);
+        if query.is_empty() {
+            return Vec::new();
+        }
+
+        let mut matches = self
+            .index
+            .iter()
+            .filter(|(id, item)| self.is_listed(id, item))
+            .filter(|(_, item)| item.skip(self).is_none() && !item.is_impl())
+            .filter(|(_, item)| {
+                kinds.is_none_or(|kinds| kinds.contains(&item.kind_name(self)))
+            })
+            .filter_map(|(_, item)| {
+                let path = self
+                    .public_path(&item.id)
+                    .or_else(|| self.item_path(&item.id))?
+                    .join("::");
+                let kind = item.kind_name(self);
+                let score = item_score(item, kind, &path, &query, &terms);
+                (score > 0).then_some((path, item, score))
+            })
+            .collect::<Vec<_>>();
+        matches.sort_by(|(a_path, _, a_score), (b_path, _, b_score)| {
+            b_score
+                .cmp(a_score)
+                .then(a_path.len().cmp(&b_path.len()))
+                .then(a_path.cmp(b_path))
+        });
+
+        matches
+            .into_iter()
+            .map(|(path, item, score)| Match {
+                id: item.id.clone(),
+                path,
+                kind: item.kind_name(self),
+                score,
+                signature: item.signature(self),
+                summary: item.summary(),
+            })
+            .collect()
+    }
+}
+
+/// The words of a query to look up, without stop words, e.g. `bytes` and
+/// `hex` for "what function turns bytes into hex?"
+fn search_terms(query: &str) -> Vec<String> {
+    query
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|word| !word.is_empty() && !STOP_WORDS.contains(word))
+        .map(stem)
+        .collect()
+}
+
+/// A crude stem of a word, so that e.g. `bytes` matches `byte`.
+fn stem(word: &str) -> String {
+    match word.strip_suffix('s') {
+        Some(stem) if stem.len() >= 3 && !stem.ends_with('s') =>
+            stem.to_string(),
+        _ => word.to_string(),
+    }
+}
+
+/// How well an item matches a query, or 0 if it doesn't.
+fn item_score(
+    item: &RustDocItem,
+    kind: &str,
+    path: &str,
+    query: &str,
+    terms: &[String],
+) -> u32 {
+    let name = item.name.as_deref().unwrap_or_default().to_lowercase();
+    let path = path.to_lowercase();
+    // Every path has the crate name, so it doesn't count for words
+    let parents = match path.split_once("::") {
+        Some((_, rest)) =>
+            rest.rsplit_once("::").map_or("", |(parents, _)| parents),
+        None => "",
+    };
+
+    let mut score = if query.contains("::") {
+        if path.ends_with(query) {
+            100
+        } else if path.contains(query) {
+            70
+        } else {
+            0
+        }
+    } else {
+        name_score(&name, query)
+    };
+
+    // The crate's root module has the crate name, as does every path
+    let is_root = kind == "module" && !path.contains("::");
+    let summary = item.summary().unwrap_or_default().to_lowercase();
+    let docs = item.docs.as_deref().unwrap_or_default().to_lowercase();
+    let mut matched_terms = 0;
+    for term in terms {
+        let mut term_score = 0;
+        if is_root {
+        } else if name.split('_').any(|part| stem(part) == *term) {
+            term_score = 20;
+        } else if name.contains(term.as_str()) {
+            term_score = 12;
+        } else if parents.contains(term.as_str()) {
+            term_score = 6;
+        } else if kind == term || (kind == "method" && term == "function") {
+            // e.g. "which struct ..."
+            term_score = 5;
+        }
+        if word_position(&summary, term).is_some() {
+            term_score += 8;
+        } else if word_position(&docs, term).is_some() {
+            term_score += 3;
+        }
+        if term_score > 0 {
+            matched_terms += 1;
+        }
+        score += term_score;
+    }
+    // Items which have every word of a longer query are much better matches
+    if terms.len() > 1 && matched_terms == terms.len() {
+        score += 10 * matched_terms;
+    }
+    // As are summaries with the words in the same order, e.g. "bytes" then
+    // "hex" for encoding rather than decoding
+    let positions = terms
+        .iter()
+        .filter_map(|term| word_position(&summary, term))
+        .collect::<Vec<_>>();
+    if positions.len() > 1 && positions.windows(2).all(|pair| pair[0] < pair[1])
+    {
+        score += 5;
+    }
+    score as u32
+}
+
+/// How well a name matches the whole query: exactly, as a prefix, within
+/// the name, with a typo, or with the query's letters in order.
+fn name_score(name: &str, query: &str) -> usize {
+    if name.is_empty() {
+        0
+    } else if name == query {
+        100
+    } else if name.starts_with(query) {
+        80
+    } else if name.contains(query) {
+        60
+    } else if query.len() >= 4 && edit_distance(name, query) <= 1 {
+        50
+    } else if query.len() >= 6 && edit_distance(name, query) <= 2 {
+        40
+    } else if query.len() >= 3 && is_subsequence(query, name) {
+        20
+    } else {
+        0
+    }
+}
+
+/// The index of the first word of `text` which is `word` or starts with it.
+fn word_position(text: &str, word: &str) -> Option<usize> {
+    text.split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|text_word| !text_word.is_empty())
+        .position(|text_word| text_word.starts_with(word))
+}
+
+/// Whether the characters of `needle` appear in `haystack` in order.
+fn is_subsequence(needle: &str, haystack: &str) -> bool {
+    let mut haystack = haystack.chars();
+    needle.chars().all(|c| haystack.any(|h| h == c))
+}
+
+/// The edit distance between two strings, counting swapped neighbouring
+/// characters as one edit, as typos often swap them.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+    // distances[i][j] is the distance between a[..i] and b[..j]
+    let mut distances = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    distances[0] = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let substitution =
+                distances[i - 1][j - 1] + usize::from(a[i - 1] != b[j - 1]);
+            let mut distance = substitution
+                .min(distances[i - 1][j] + 1)
+                .min(distances[i][j - 1] + 1);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(distances[i - 2][j - 2] + 1);
+            }
+            distances[i][j] = distance;
+        }
+    }
+    distances[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const COMMON_JSON_STR: &str =
+        include_str!("../test-data/common/rustdoc.json");
+    const HEX_JSON_STR: &str = include_str!("../test-data/hex/rustdoc.json");
+
+    fn paths(doc: &RustDoc, query: &str) -> Vec<String> {
+        doc.search(query)
+            .into_iter()
+            .map(|found| found.path)
+            .collect()
+    }
+
+    #[test]
+    fn test_search() {
+        let doc = serde_json::from_str::<RustDoc>(COMMON_JSON_STR).unwrap();
+
+        // Exact names first, then names which start with the query
+        let found = paths(&doc, "point");
+        assert_eq!(found[0], "common::shapes::Point");
+        assert!(found.contains(&"common::shapes::Point::new".to_string()));
+
+        // Typos and paths
+        assert_eq!(paths(&doc, "gret")[0], "common::greet");
+        assert_eq!(paths(&doc, "shapes::Point")[0], "common::shapes::Point");
+
+        // Filtered by kind
+        let methods = doc.search("fn:new");
+        assert!(!methods.is_empty());
+        assert!(methods.iter().all(|found| found.kind == "method"));
+        assert!(doc.search("enum:point").is_empty());
+
+        let greet = &doc.search("greet")[0];
+        assert_eq!(greet.kind, "function");
+        assert!(greet
+            .signature
+            .as_deref()
+            .unwrap()
+            .contains("pub fn greet(name: &str) -> String"));
+
+        assert!(doc.search("zzzzzz").is_empty());
+        assert_eq!(
+            paths(&doc, "i32, i32 -> Point")[0],
+            "common::shapes::Point::new"
+        );
+    }
+
+    #[test]
+    fn test_search_docs() {
+        let doc = serde_json::from_str::<RustDoc>(HEX_JSON_STR).unwrap();
+        let found = paths(&doc, "what function turns bytes into hex?");
+        assert!(
+            found.iter().take(3).any(|path| path == "hex::encode"),
+            "{found:?}"
+        );
+        assert_eq!(stem("bytes"), "byte");
+        assert_eq!(stem("class"), "class");
+        assert_eq!(paths(&doc, "ecnode")[0], "hex::encode");
+        assert_eq!(edit_distance("encode", "ecnode"), 1);
+        assert_eq!(edit_distance("encode", "decode"), 2);
+    }
+}
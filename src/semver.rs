@@ -0,0 +1,602 @@
+//! Classifying the changes between two versions of a crate by Rust's semver
+//! rules, to check that a release bumps the version enough.
+//!
+//! The rules follow the Cargo book's chapter on SemVer compatibility. Changes
+//! which only affect code relying on implementation details, e.g. adding an
+//! impl of a trait, count as minor.
+
+use std::fmt::{self, Write};
+
+use serde::Serialize;
+
+use crate::{
+    ApiDiff, Attribute, ChangeKind, ItemChange, RustDoc, RustDocItem,
+    StructKind,
+};
+
+/// The version bump a change requires.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SemverLevel {
+    Patch,
+    Minor,
+    Major,
+}
+
+impl fmt::Display for SemverLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Patch => write!(f, "patch"),
+            Self::Minor => write!(f, "minor"),
+            Self::Major => write!(f, "major"),
+        }
+    }
+}
+
+/// A change to the public API, with the version bump it requires.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct SemverChange {
+    #[serde(flatten)]
+    pub change: ItemChange,
+    pub level: SemverLevel,
+    /// Why the change requires the bump, e.g. "Adding a variant to an
+    /// exhaustive enum breaks exhaustive matches".
+    pub reason: &'static str,
+}
+
+/// The changes to the public API between two versions of a crate, classified
+/// by the version bump they require.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct SemverReport {
+    pub crate_name: String,
+    pub old_version: String,
+    pub new_version: String,
+    /// The bump the changes require: the largest of the changes' levels, or
+    /// patch if there are none.
+    pub verdict: SemverLevel,
+    /// The bump from the old to the new version, if both parse as semver
+    /// and the new version isn't lower.
+    pub version_bump: Option<SemverLevel>,
+    /// Whether the new version is lower than the old one, e.g. `0.2.0` to
+    /// `0.1.0`, which is never a valid release.
+    pub is_downgrade: bool,
+    /// Ordered like [`ApiDiff::changes`].
+    pub changes: Vec<SemverChange>,
+}
+
+impl RustDoc {
+    /// Compare the public API with the `new` version of the crate, and
+    /// classify each change as major, minor or patch.
+    pub fn semver_check(&self, new: &RustDoc) -> SemverReport {
+        let ApiDiff {
+            crate_name,
+            old_version,
+            new_version,
+            changes,
+        } = self.diff(new);
+
+        let changes = changes
+            .into_iter()
+            .map(|change| {
+                let (level, reason) = classify(&change, self, new);
+                SemverChange {
+                    change,
+                    level,
+                    reason,
+                }
+            })
+            .collect::<Vec<_>>();
+        let verdict = changes
+            .iter()
+            .map(|change| change.level)
+            .max()
+            .unwrap_or(SemverLevel::Patch);
+        let version_bump = version_bump(&old_version, &new_version);
+        let is_downgrade = parse_version(&old_version)
+            .zip(parse_version(&new_version))
+            .is_some_and(|(old, new)| new < old);
+
+        SemverReport {
+            crate_name,
+            old_version,
+            new_version,
+            verdict,
+            version_bump,
+            is_downgrade,
+            changes,
+        }
+    }
+}
+
+/// The bump a change requires, and why.
+fn classify(
+    change: &ItemChange,
+    old: &RustDoc,
+    new: &RustDoc,
+) -> (SemverLevel, &'static str) {
+    use SemverLevel::*;
+
+    let old_item = change.old_id.as_ref().and_then(|id| old.index.get(id));
+    let new_item = change.new_id.as_ref().and_then(|id| new.index.get(id));
+
+    match (change.change, old_item, new_item) {
+        (ChangeKind::Removed, _, _) if change.item_kind == "impl" => (
+            Major,
+            "Removing a trait impl breaks code which relies on it",
+        ),
+        (ChangeKind::Removed, _, _) =>
+            (Major, "Removing a public item breaks code which uses it"),
+
+        (ChangeKind::Added, _, Some(item)) => {
+            let parent = new
+                .parent(&item.id)
+                .map(|(_, parent)| parent)
+                .filter(|parent| parent.inner.is_some());
+            match (change.item_kind, parent) {
+                ("variant", Some(enum_))
+                    if enum_.has_attribute(&Attribute::NonExhaustive) => (
+                    Minor,
+                    "Adding a variant to a #[non_exhaustive] enum is allowed",
+                ),
+                ("variant", _) => (
+                    Major,
+                    "Adding a variant to an exhaustive enum breaks exhaustive \
+                     matches",
+                ),
+                ("field", Some(variant))
+                    if variant.inner.as_ref().unwrap().variant.is_some() =>
+                {
+                    if variant.has_attribute(&Attribute::NonExhaustive) {
+                        (
+                            Minor,
+                            "Adding a field to a #[non_exhaustive] variant is \
+                             allowed",
+                        )
+                    } else {
+                        (
+                            Major,
+                            "Adding a field to an enum variant breaks \
+                             constructing and matching the variant",
+                        )
+                    }
+                }
+                ("field", Some(struct_))
+                    if struct_.inner.as_ref().unwrap().struct_.is_some() =>
+                {
+                    // Whether the struct could be constructed before
+                    let old_struct = counterpart(new, old, &struct_.id)
+                        .and_then(|id| old.index.get(id));
+                    if old_struct.is_some_and(RustDocItem::is_constructible) {
+                        (
+                            Major,
+                            "Adding a field to a struct with only public \
+                             fields breaks struct literals and patterns",
+                        )
+                    } else {
+                        (
+                            Minor,
+                            "Adding a public field to a struct with private \
+                             fields is allowed",
+                        )
+                    }
+                }
+                ("associated type" | "associated constant", Some(parent))
+                    if parent.inner.as_ref().unwrap().trait_.is_some() =>
+                {
+                    if item.has_default() {
+                        (
+                            Minor,
+                            "Adding an associated item with a default to a \
+                             trait is allowed",
+                        )
+                    } else {
+                        (
+                            Major,
+                            "Adding a required associated item to a trait \
+                             breaks implementations of the trait",
+                        )
+                    }
+                }
+                ("method", Some(parent))
+                    if parent.inner.as_ref().unwrap().trait_.is_some() =>
+                {
+                    let has_body = item
+                        .inner
+                        .as_ref()
+                        .and_then(|inner| inner.function.as_ref())
+                        .is_some_and(|function| function.has_body);
+                    if has_body {
+                        (
+                            Minor,
+                            "Adding a trait method with a default \
+                             implementation is allowed",
+                        )
+                    } else {
+                        (
+                            Major,
+                            "Adding a required trait method breaks \
+                             implementations of the trait",
+                        )
+                    }
+                }
+                _ => (Minor, "Adding a public item is allowed"),
+            }
+        }
+
+        (ChangeKind::Changed, Some(old_item), Some(new_item)) => {
+            let became_non_exhaustive = !old_item
+                .has_attribute(&Attribute::NonExhaustive)
+                && new_item.has_attribute(&Attribute::NonExhaustive);
+            match change.item_kind {
+                "struct" | "enum" if became_non_exhaustive => (
+                    Major,
+                    "Adding #[non_exhaustive] breaks exhaustive matches and \
+                     struct literals",
+                ),
+                "struct"
+                    if old_item.is_constructible()
+                        && !new_item.is_constructible() =>
+                    (
+                        Major,
+                        "Adding a private field to a struct with only public \
+                         fields breaks struct literals and patterns",
+                    ),
+                "struct" | "enum" | "trait"
+                    if header(&change.before) != header(&change.after) =>
+                    (
+                        Major,
+                        "Changing the definition of a type or trait may \
+                         break code which uses it",
+                    ),
+                "struct" | "enum" | "trait" => (
+                    Patch,
+                    "Only the members changed, which are classified \
+                     separately",
+                ),
+                _ => (
+                    Major,
+                    "Changing the signature of a public item may break code \
+                     which uses it",
+                ),
+            }
+        }
+
+        // Added items are in the new version, and changed items in both.
+        _ => (Major, "The item changed in an unknown way"),
+    }
+}
+
+/// The id in `to` of the item at the same path, relative to the crate root,
+/// as the item `id` in `from`.
+fn counterpart<'a>(
+    from: &RustDoc,
+    to: &'a RustDoc,
+    id: &str,
+) -> Option<&'a str> {
+    let mut path = from.public_path(id)?;
+    *path.first_mut()? = to.crate_name().to_string();
+    to.id_by_path(&path)
+}
+
+/// The lines of a type or trait definition before its members, including
+/// its attributes, e.g. `#[repr(u8)]` and `pub enum Color {`.
+fn header(signature: &Option<String>) -> Vec<&str> {
+    signature
+        .as_deref()
+        .unwrap_or_default()
+        .lines()
+        .take_while(|line| !line.starts_with("    "))
+        .collect()
+}
+
+/// The major, minor and patch components of a version, ignoring
+/// pre-release and build metadata.
+fn parse_version(version: &str) -> Option<[u64; 3]> {
+    let version = version.split(['-', '+']).next()?;
+    let mut parts = version.split('.').map(|part| part.parse().ok());
+    let version = [parts.next()??, parts.next()??, parts.next()??];
+    parts.next().is_none().then_some(version)
+}
+
+/// The bump from one version to another by Cargo's rules, where bumping the
+/// left-most non-zero component is a major bump, e.g. `0.1.0` to `0.2.0`.
+/// `None` if a version doesn't parse or the new version is lower.
+fn version_bump(old: &str, new: &str) -> Option<SemverLevel> {
+    let old = parse_version(old)?;
+    let new = parse_version(new)?;
+    if new < old {
+        return None;
+    }
+
+    // The index of the left-most non-zero component, which is the major one
+    let major = old.iter().position(|&part| part != 0).unwrap_or(2);
+    let changed = (0..3).find(|&i| old[i] != new[i]);
+    Some(match changed {
+        None => SemverLevel::Patch,
+        Some(i) if i <= major => SemverLevel::Major,
+        Some(i) if i == major + 1 => SemverLevel::Minor,
+        Some(_) => SemverLevel::Patch,
+    })
+}
+
+impl RustDocItem {
+    /// Whether an associated type or constant of a trait has a default, so
+    /// that implementations of the trait don't need to define it.
+    fn has_default(&self) -> bool {
+        let Some(inner) = &self.inner else {
+            return false;
+        };
+        inner
+            .assoc_type
+            .as_ref()
+            .is_some_and(|t| t.default.is_some())
+            || inner
+                .assoc_const
+                .as_ref()
+                .is_some_and(|c| c.default.is_some())
+    }
+
+    /// Whether a struct can be constructed with a struct literal outside its
+    /// crate, i.e. all its fields are public and it isn't `#[non_exhaustive]`.
+    fn is_constructible(&self) -> bool {
+        let Some(struct_) =
+            self.inner.as_ref().and_then(|inner| inner.struct_.as_ref())
+        else {
+            return false;
+        };
+        if self.has_attribute(&Attribute::NonExhaustive) {
+            return false;
+        }
+        match &struct_.kind {
+            Some(StructKind::Plain { plain }) => !plain.fields_stripped,
+            // Private fields of tuple structs are listed as `null`
+            Some(StructKind::Tuple { tuple }) => tuple
+                .as_ref()
+                .is_some_and(|fields| fields.iter().all(|f| !f.is_null())),
+            Some(StructKind::Unit(_)) | None => true,
+        }
+    }
+}
+
+impl SemverReport {
+    /// Whether the version bump is enough for the changes. `None` if the
+    /// versions don't parse as semver, and `false` for a downgrade.
+    pub fn is_bump_sufficient(&self) -> Option<bool> {
+        if self.is_downgrade {
+            return Some(false);
+        }
+        self.version_bump.map(|bump| bump >= self.verdict)
+    }
+
+    /// The changes which require the given bump.
+    pub fn changes_of(
+        &self,
+        level: SemverLevel,
+    ) -> impl Iterator<Item = &SemverChange> {
+        self.changes
+            .iter()
+            .filter(move |change| change.level == level)
+    }
+
+    /// Render the report as JSON, e.g. for CI.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self)
+            .expect("Serializing the report can't fail")
+    }
+
+    /// Render the report as markdown.
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        self.write_markdown(&mut out)
+            .expect("Writing to a String can't fail");
+        out
+    }
+
+    fn write_markdown(&self, out: &mut String) -> fmt::Result {
+        writeln!(
+            out,
+            "# Semver check for `{}` v{} to v{}",
+            self.crate_name, self.old_version, self.new_version
+        )?;
+        writeln!(out)?;
+
+        write!(out, "Verdict: **{}**", self.verdict)?;
+        match (self.version_bump, self.is_bump_sufficient()) {
+            _ if self.is_downgrade => writeln!(
+                out,
+                ", but the new version is lower than the old one. \
+                 Bump the {} version instead.",
+                self.verdict
+            )?,
+            (Some(bump), Some(true)) =>
+                writeln!(out, ", which the {bump} version bump allows.")?,
+            (Some(bump), _) => writeln!(
+                out,
+                ", but the version bump is only {bump}. \
+                 Bump the {} version instead.",
+                self.verdict
+            )?,
+            (None, _) => writeln!(out, ".")?,
+        }
+        writeln!(out)?;
+
+        let sections = [
+            (SemverLevel::Major, "Major changes"),
+            (SemverLevel::Minor, "Minor changes"),
+            (SemverLevel::Patch, "Patch changes"),
+        ];
+        for (level, heading) in sections {
+            if self.changes_of(level).next().is_none() {
+                continue;
+            }
+            writeln!(out, "## {heading}")?;
+            writeln!(out)?;
+            for change in self.changes_of(level) {
+                let kind = match change.change.change {
+                    ChangeKind::Removed => "Removed",
+                    ChangeKind::Changed => "Changed",
+                    ChangeKind::Added => "Added",
+                };
+                writeln!(
+                    out,
+                    "- {kind} `{}` ({}): {}.",
+                    change.change.path, change.change.item_kind, change.reason
+                )?;
+            }
+            writeln!(out)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const API_V1_JSON_STR: &str =
+        include_str!("../test-data/api_v1/rustdoc.json");
+    const API_V2_JSON_STR: &str =
+        include_str!("../test-data/api_v2/rustdoc.json");
+    const SIGNATURES_V1_JSON_STR: &str =
+        include_str!("../test-data/signatures_v1/rustdoc.json");
+    const SIGNATURES_V2_JSON_STR: &str =
+        include_str!("../test-data/signatures_v2/rustdoc.json");
+
+    fn report() -> SemverReport {
+        let old = serde_json::from_str::<RustDoc>(API_V1_JSON_STR).unwrap();
+        let new = serde_json::from_str::<RustDoc>(API_V2_JSON_STR).unwrap();
+        old.semver_check(&new)
+    }
+
+    fn level(report: &SemverReport, path: &str) -> SemverLevel {
+        report
+            .changes
+            .iter()
+            .find(|change| change.change.path == path)
+            .unwrap_or_else(|| panic!("No change to {path}"))
+            .level
+    }
+
+    /// Changes to the types of fields and to generics are breaking.
+    #[test]
+    fn test_semver_generics() {
+        let old =
+            serde_json::from_str::<RustDoc>(SIGNATURES_V1_JSON_STR).unwrap();
+        let new =
+            serde_json::from_str::<RustDoc>(SIGNATURES_V2_JSON_STR).unwrap();
+        let report = old.semver_check(&new);
+        use SemverLevel::*;
+
+        // A changed field type
+        assert_eq!(level(&report, "signatures::Id::0"), Major);
+        // An added bound, in the generics and in a where clause
+        assert_eq!(level(&report, "signatures::convert"), Major);
+        assert_eq!(level(&report, "signatures::parse"), Major);
+        // An added trait parameter
+        assert_eq!(level(&report, "signatures::Store"), Major);
+        // Reordered generics of a function break turbofish calls
+        assert_eq!(level(&report, "signatures::defaults"), Major);
+
+        assert_eq!(report.verdict, Major);
+        assert_eq!(report.is_bump_sufficient(), Some(true));
+    }
+
+    #[test]
+    fn test_semver_check() {
+        let report = report();
+        use SemverLevel::*;
+
+        assert_eq!(level(&report, "api::hello"), Major);
+        assert_eq!(level(&report, "impl Clone for Token"), Major);
+        assert_eq!(level(&report, "api::greet"), Major);
+        // Exhaustive and #[non_exhaustive] enums
+        assert_eq!(level(&report, "api::Color::Blue"), Major);
+        assert_eq!(level(&report, "api::ErrorKind::Timeout"), Minor);
+        assert_eq!(level(&report, "api::Color"), Patch);
+        // A private field added to a struct with only public fields
+        assert_eq!(level(&report, "api::Point"), Major);
+        // Required and provided trait methods
+        assert_eq!(level(&report, "api::Named::id"), Major);
+        assert_eq!(level(&report, "api::Shape::perimeter"), Minor);
+        assert_eq!(level(&report, "api::Size"), Minor);
+        // Fields of exhaustive and #[non_exhaustive] variants
+        assert_eq!(level(&report, "api::Event::Key::shift"), Major);
+        assert_eq!(level(&report, "api::Event::Click::y"), Minor);
+        assert_eq!(level(&report, "api::Event"), Patch);
+        // Required and defaulted associated types and constants of traits
+        assert_eq!(level(&report, "api::Convert::Output"), Major);
+        assert_eq!(level(&report, "api::Convert::MAX"), Major);
+        assert_eq!(level(&report, "api::Convert::BASE"), Minor);
+
+        assert_eq!(report.verdict, Major);
+        // 0.1.0 to 0.1.1 is a minor bump before 1.0
+        assert_eq!(report.version_bump, Some(Minor));
+        assert_eq!(report.is_bump_sufficient(), Some(false));
+
+        let old = serde_json::from_str::<RustDoc>(API_V1_JSON_STR).unwrap();
+        let report = old.semver_check(&old);
+        assert_eq!(report.verdict, Patch);
+        assert_eq!(report.is_bump_sufficient(), Some(true));
+
+        // 0.1.1 to 0.1.0 is a downgrade, which no changes allow
+        let new = serde_json::from_str::<RustDoc>(API_V2_JSON_STR).unwrap();
+        let report = new.semver_check(&old);
+        assert!(report.is_downgrade);
+        assert_eq!(report.version_bump, None);
+        assert_eq!(report.is_bump_sufficient(), Some(false));
+        assert!(report
+            .to_markdown()
+            .contains("but the new version is lower than the old one."));
+    }
+
+    #[test]
+    fn test_version_bump() {
+        use SemverLevel::*;
+
+        assert_eq!(version_bump("1.2.3", "2.0.0"), Some(Major));
+        assert_eq!(version_bump("1.2.3", "1.3.0"), Some(Minor));
+        assert_eq!(version_bump("1.2.3", "1.2.4"), Some(Patch));
+        assert_eq!(version_bump("0.1.0", "0.2.0"), Some(Major));
+        assert_eq!(version_bump("0.1.0", "0.1.1"), Some(Minor));
+        assert_eq!(version_bump("0.0.1", "0.0.2"), Some(Major));
+        assert_eq!(version_bump("1.0.0-beta.1", "1.0.0"), Some(Patch));
+        assert_eq!(version_bump("1.0", "2.0"), None);
+        // Downgrades aren't bumps
+        assert_eq!(version_bump("0.2.0", "0.1.0"), None);
+        assert_eq!(version_bump("1.2.3", "1.2.2"), None);
+    }
+
+    #[test]
+    fn test_semver_output() {
+        let report = report();
+
+        let markdown = report.to_markdown();
+        assert!(markdown.starts_with(
+            "# Semver check for `api` v0.1.0 to v0.1.1\n\n\
+             Verdict: **major**, but the version bump is only minor. \
+             Bump the major version instead.\n"
+        ));
+        assert!(markdown.contains(
+            "## Minor changes\n\n\
+             - Added `api::Convert::BASE` (associated constant): Adding an \
+             associated item with a default to a trait is allowed.\n\
+             - Added `api::ErrorKind::Timeout` (variant): Adding a variant \
+             to a #[non_exhaustive] enum is allowed.\n"
+        ));
+
+        let json = serde_json::from_str::<serde_json::Value>(&report.to_json())
+            .unwrap();
+        assert_eq!(json["verdict"], "major");
+        assert_eq!(json["version_bump"], "minor");
+        let blue = json["changes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|change| change["path"] == "api::Color::Blue")
+            .unwrap();
+        assert_eq!(blue["change"], "added");
+        assert_eq!(blue["level"], "major");
+        assert_eq!(blue["item_kind"], "variant");
+    }
+}
@@ -0,0 +1,1209 @@
+//! A compact "API skeleton" of a crate: one Rust code block per module, with
+//! only the signatures of its items and one-line doc summaries.
+//!
+//! The items are written as Rust source, so the same code can write other
+//! kinds of Rust source, with a different [`SourceStyle`].
+
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Write},
+};
+
+use serde::Deserialize;
+
+use crate::{
+    bounds_source, format_angle_bracketed_args, higher_ranked,
+    EnumVariantDetails, Generics, Impl, Parameter, ProcMacroKind, RustDoc,
+    RustDocItem, StructKind, TraitInfo, UnionDetails, WherePredicate,
+};
+
+/// How to write items as Rust source.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct SourceStyle {
+    /// Write all of the docs, rather than a one-line summary.
+    pub(crate) full_docs: bool,
+    /// The body of functions, e.g. `{ ... }`.
+    pub(crate) body: &'static str,
+    /// Write source which compiles: the docs of modules go in the modules'
+    /// own files, and private fields which were stripped from the JSON are
+    /// replaced with `PhantomData`.
+    pub(crate) compilable: bool,
+    /// Write the derives and impls of types, and the impls of traits for
+    /// other types, after them.
+    pub(crate) impls: bool,
+}
+
+impl SourceStyle {
+    pub(crate) const SKELETON: Self = Self {
+        full_docs: false,
+        body: "{ ... }",
+        compilable: false,
+        impls: true,
+    };
+}
+
+/// The fields of an enum variant, which rustdoc JSON lists as `"plain"`,
+/// `{"tuple": [...]}` or `{"struct": {...}}`.
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum VariantKind {
+    Plain,
+    /// The ids of the fields, or `None` for fields which were stripped.
+    Tuple(Vec<Option<String>>),
+    Struct {
+        fields: Vec<String>,
+        #[serde(default)]
+        fields_stripped: bool,
+    },
+}
+
+impl EnumVariantDetails {
+    /// The ids of the fields of the variant which weren't stripped.
+    pub(crate) fn field_ids(&self) -> Vec<String> {
+        match serde_json::from_value::<VariantKind>(self.kind.clone()) {
+            Ok(VariantKind::Tuple(fields)) =>
+                fields.into_iter().flatten().collect(),
+            Ok(VariantKind::Struct { fields, .. }) => fields,
+            Ok(VariantKind::Plain) | Err(_) => Vec::new(),
+        }
+    }
+}
+
+impl RustDocItem {
+    /// Write the signature of a function, e.g. `pub fn greet(name: &str) ->
+    /// String`, with `vis` before it.
+    pub(crate) fn write_fn_signature(
+        &self,
+        out: &mut String,
+        vis: &str,
+    ) -> fmt::Result {
+        let (Some(inner), Some(name)) = (&self.inner, &self.name) else {
+            return Ok(());
+        };
+        let Some(function) = &inner.function else {
+            return Ok(());
+        };
+
+        write!(out, "{vis}")?;
+        // Within their `extern` block, foreign functions have no qualifiers
+        if let Some(header) = &function.header {
+            if self.foreign_abi().is_none() {
+                write!(out, "{}", header.qualifiers())?;
+            }
+        }
+
+        let generics = function.generics.as_ref();
+        let params = function
+            .decl
+            .inputs
+            .iter()
+            .map(|(name, param)| param_source(name, param))
+            .collect::<Vec<_>>()
+            .join(", ");
+        write!(out, "fn {name}{}({params})", generic_params(generics))?;
+        if let Some(output) = &function.decl.output {
+            write!(out, " -> {output}")?;
+        }
+        write!(out, "{}", where_clause(generics))
+    }
+
+    /// The source of a type alias, e.g. `pub type Result<T> = Result<T,
+    /// Error>;`.
+    pub(crate) fn type_alias_source(&self) -> Option<String> {
+        let alias = self.inner.as_ref()?.type_alias.as_ref()?;
+        let name = self.name.as_ref()?;
+        let generics = alias.generics.as_ref();
+        Some(format!(
+            "{}type {name}{}{} = {};",
+            visibility(self),
+            generic_params(generics),
+            where_clause(generics),
+            alias.type_
+        ))
+    }
+
+    /// The source of a static, e.g. `pub static mut CALLS: u32 = 0;`, or
+    /// `pub static ERRNO: c_int;` in an `extern` block.
+    pub(crate) fn static_source(&self) -> Option<String> {
+        let static_ = self.inner.as_ref()?.static_.as_ref()?;
+        let name = self.name.as_ref()?;
+        let mutability = if static_.mutable { "mut " } else { "" };
+        let value = if static_.expr.is_empty() {
+            String::new()
+        } else {
+            format!(" = {}", static_.expr)
+        };
+        Some(format!(
+            "{}static {mutability}{name}: {}{value};",
+            visibility(self),
+            static_.type_
+        ))
+    }
+
+    /// The source of a macro: a `macro_rules!` macro with the bodies of its
+    /// arms elided, or how a procedural macro is invoked, as rustdoc shows
+    /// it, e.g. `#[derive(Builder)]` with the derive's helper attributes.
+    pub(crate) fn macro_source(&self) -> Option<String> {
+        let inner = self.inner.as_ref()?;
+        if let Some(source) = &inner.macro_ {
+            return Some(source.clone());
+        }
+        let proc_macro = inner.proc_macro.as_ref()?;
+        let name = self.name.as_ref()?;
+        let source = match proc_macro.kind {
+            ProcMacroKind::Bang => format!("{name}!() {{ /* proc-macro */ }}"),
+            ProcMacroKind::Attr => format!("#[{name}]"),
+            ProcMacroKind::Derive if proc_macro.helpers.is_empty() =>
+                format!("#[derive({name})]"),
+            ProcMacroKind::Derive => {
+                let helpers = proc_macro
+                    .helpers
+                    .iter()
+                    .map(|helper| format!("    #[{helper}]\n"))
+                    .collect::<String>();
+                format!(
+                    "#[derive({name})]\n{{\n    // Attributes available to \
+                     this derive:\n{helpers}}}"
+                )
+            }
+        };
+        Some(source)
+    }
+
+    /// The first paragraph of the docs, on one line.
+    pub(crate) fn summary(&self) -> Option<String> {
+        let summary = self
+            .docs
+            .as_deref()?
+            .lines()
+            .map(str::trim)
+            .take_while(|line| !line.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        (!summary.is_empty()).then_some(summary)
+    }
+}
+
+impl RustDoc {
+    /// Render the API skeleton of the crate as markdown: a Rust code block of
+    /// signatures per module.
+    pub fn to_skeleton(&self) -> String {
+        let mut out = String::new();
+        self.write_skeleton(&mut out)
+            .expect("Writing to a String can't fail");
+        out
+    }
+
+    fn write_skeleton(&self, out: &mut String) -> fmt::Result {
+        writeln!(out, "# {} v{}", self.crate_name(), self.crate_version)?;
+        writeln!(out)?;
+
+        for (path, module) in self.modules() {
+            writeln!(out, "## `{}`", path.join("::"))?;
+            writeln!(out)?;
+            writeln!(out, "```rust")?;
+            self.write_module_source(out, module, SourceStyle::SKELETON)?;
+            writeln!(out, "```")?;
+            writeln!(out)?;
+        }
+
+        Ok(())
+    }
+
+    /// The crate's modules, starting at the root, each followed by its
+    /// submodules, with their paths.
+    pub(crate) fn modules(&self) -> Vec<(Vec<String>, &RustDocItem)> {
+        fn visit<'a>(
+            doc: &'a RustDoc,
+            path: Vec<String>,
+            module: &'a RustDocItem,
+            modules: &mut Vec<(Vec<String>, &'a RustDocItem)>,
+        ) {
+            modules.push((path.clone(), module));
+            for child in doc.module_items(module) {
+                if child.inner.as_ref().is_some_and(|i| i.module.is_some()) {
+                    let mut child_path = path.clone();
+                    child_path.extend(child.name.clone());
+                    visit(doc, child_path, child, modules);
+                }
+            }
+        }
+
+        let mut modules = Vec::new();
+        if let Some(root) = self.index.get(&self.root) {
+            let path = vec![self.crate_name().to_string()];
+            visit(self, path, root, &mut modules);
+        }
+        modules
+    }
+
+    /// The items of a module which the print options let us show.
+    pub(crate) fn module_items<'a>(
+        &'a self,
+        module: &'a RustDocItem,
+    ) -> impl Iterator<Item = &'a RustDocItem> {
+        module
+            .inner
+            .as_ref()
+            .and_then(|inner| inner.module.as_ref())
+            .map(|module| module.items.as_slice())
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|id| self.index.get(id))
+            .filter(|item| self.is_shown(item))
+    }
+
+    fn is_shown(&self, item: &RustDocItem) -> bool {
+        item.omitted_reason(self).is_none()
+            && (!self.options.public_only
+                || item.import().is_some()
+                || self.is_public_api(&item.id))
+    }
+
+    /// Write the items of a module as Rust source. Submodules are declared,
+    /// e.g. `pub mod shapes;`, but not written.
+    pub(crate) fn write_module_source(
+        &self,
+        out: &mut String,
+        module: &RustDocItem,
+        style: SourceStyle,
+    ) -> fmt::Result {
+        // `use` items first, without blank lines between them. Compilable
+        // source has the items which can't be imported in their place.
+        let (imports, items) =
+            self.module_items(module).partition::<Vec<_>, _>(|item| {
+                item.import().is_some()
+                    && !(style.compilable
+                        && self.unwritten_reexport(item).is_some())
+            });
+        for import in &imports {
+            self.write_item_source(out, import, style)?;
+        }
+
+        // Foreign items go in `extern` blocks, after the other items
+        let (foreign_items, items) = items
+            .into_iter()
+            .partition::<Vec<_>, _>(|item| item.foreign_abi().is_some());
+
+        let mut first = imports.is_empty();
+        for item in items {
+            let mut source = String::new();
+            self.write_item_source(&mut source, item, style)?;
+            if source.is_empty() {
+                continue;
+            }
+            if !first {
+                writeln!(out)?;
+            }
+            out.push_str(&source);
+            first = false;
+        }
+        if !foreign_items.is_empty() {
+            if !first {
+                writeln!(out)?;
+            }
+            self.write_extern_blocks(out, &foreign_items, style)?;
+        }
+        Ok(())
+    }
+
+    /// The ABI of the `extern` block a foreign item is in. Statics don't
+    /// record theirs, so they take the ABI of a foreign function of their
+    /// module. This is a guess: it's wrong when the module has blocks of
+    /// several ABIs, and statics with no such function are `extern "C"`.
+    fn extern_abi(&self, item: &RustDocItem) -> Option<String> {
+        let abi = item.foreign_abi()?;
+        let is_function = |item: &RustDocItem| {
+            item.inner
+                .as_ref()
+                .is_some_and(|inner| inner.function.is_some())
+        };
+        if is_function(item) {
+            return Some(abi);
+        }
+        let sibling_abi = self
+            .api()
+            .module(&item.id)
+            .and_then(|id| self.index.get(id)?.inner.as_ref()?.module.as_ref())
+            .and_then(|module| {
+                module
+                    .items
+                    .iter()
+                    .filter_map(|id| self.index.get(id))
+                    .filter(|sibling| is_function(sibling))
+                    .find_map(RustDocItem::foreign_abi)
+            });
+        Some(sibling_abi.unwrap_or(abi))
+    }
+
+    /// Write foreign functions and statics in `extern` blocks, with a block
+    /// per module and ABI, e.g. `extern "C" { pub fn abs(x: c_int) -> c_int;
+    /// }`.
+    pub(crate) fn write_extern_blocks(
+        &self,
+        out: &mut String,
+        items: &[&RustDocItem],
+        style: SourceStyle,
+    ) -> fmt::Result {
+        let mut blocks = BTreeMap::<_, Vec<&RustDocItem>>::new();
+        for item in items {
+            if let Some(abi) = self.extern_abi(item) {
+                let module = self.api().module(&item.id);
+                blocks.entry((module, abi)).or_default().push(item);
+            }
+        }
+
+        for (i, ((_, abi), items)) in blocks.into_iter().enumerate() {
+            if i > 0 {
+                writeln!(out)?;
+            }
+            writeln!(out, "extern \"{abi}\" {{")?;
+            for item in items {
+                write_docs(out, item, "    ", style)?;
+                item.write_attributes(out, "    ")?;
+                if let Some(source) = item.static_source() {
+                    writeln!(out, "    {source}")?;
+                } else {
+                    write!(out, "    ")?;
+                    item.write_fn_signature(out, visibility(item))?;
+                    writeln!(out, ";")?;
+                }
+            }
+            writeln!(out, "}}")?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn write_item_source(
+        &self,
+        out: &mut String,
+        item: &RustDocItem,
+        style: SourceStyle,
+    ) -> fmt::Result {
+        let (Some(inner), Some(name)) = (&item.inner, &item.name) else {
+            if let Some(target) =
+                self.unwritten_reexport(item).filter(|_| style.compilable)
+            {
+                return self.write_inlined_source(out, item, target, style);
+            }
+            // Imports have no name of their own
+            if let Some(import) = item.import() {
+                let glob = if import.glob { "::*" } else { "" };
+                let last = import.source.rsplit("::").next();
+                let rename = if last == Some(&import.name) || import.glob {
+                    String::new()
+                } else {
+                    format!(" as {}", import.name)
+                };
+                let vis = visibility(item);
+                writeln!(out, "{vis}use {}{glob}{rename};", import.source)?;
+            }
+            return Ok(());
+        };
+        let vis = visibility(item);
+
+        if inner.module.is_some() {
+            if !style.compilable {
+                write_docs(out, item, "", style)?;
+            }
+            writeln!(out, "{vis}mod {name};")?;
+        } else if inner.function.is_some() {
+            self.write_fn_source(out, item, "", style, vis)?;
+        } else if let Some(struct_) = &inner.struct_ {
+            write_docs(out, item, "", style)?;
+            if style.impls {
+                self.write_derives(out, &struct_.impls)?;
+            }
+            item.write_attributes(out, "")?;
+            let generics = struct_.generics.as_ref();
+            let params = generic_params(generics);
+            let where_ = where_clause(generics);
+            match &struct_.kind {
+                Some(StructKind::Unit(_)) =>
+                    writeln!(out, "{vis}struct {name}{params}{where_};")?,
+                Some(StructKind::Tuple { tuple }) => {
+                    let fields = tuple
+                        .iter()
+                        .flatten()
+                        .map(|field| field.as_str().map(String::from))
+                        .collect::<Vec<_>>();
+                    let fields =
+                        self.tuple_fields_source(&fields, generics, style);
+                    writeln!(
+                        out,
+                        "{vis}struct {name}{params}({fields}){where_};"
+                    )?;
+                }
+                Some(StructKind::Plain { plain }) => {
+                    writeln!(out, "{vis}struct {name}{params}{where_} {{")?;
+                    self.write_fields_source(out, &plain.fields, style)?;
+                    match (plain.fields_stripped, style.compilable) {
+                        (true, true) => writeln!(
+                            out,
+                            "    _private: {},",
+                            phantom_data(generics)
+                        )?,
+                        (true, false) =>
+                            writeln!(out, "    // Some fields are private")?,
+                        (false, _) => {}
+                    }
+                    writeln!(out, "}}")?;
+                }
+                None => {
+                    writeln!(out, "{vis}struct {name}{params}{where_} {{")?;
+                    self.write_fields_source(out, &struct_.fields, style)?;
+                    writeln!(out, "}}")?;
+                }
+            }
+            self.write_impls_source(out, &item.id, &struct_.impls, style)?;
+        } else if let Some(enum_) = &inner.enum_ {
+            write_docs(out, item, "", style)?;
+            if style.impls {
+                self.write_derives(out, &enum_.impls)?;
+            }
+            item.write_attributes(out, "")?;
+            let generics = enum_.generics.as_ref();
+            let params = generic_params(generics);
+            let where_ = where_clause(generics);
+            writeln!(out, "{vis}enum {name}{params}{where_} {{")?;
+            for variant in enum_
+                .variants
+                .iter()
+                .filter_map(|id| self.index.get(id))
+                .filter(|variant| self.is_shown(variant))
+            {
+                self.write_variant_source(out, variant, style)?;
+            }
+            writeln!(out, "}}")?;
+            self.write_impls_source(out, &item.id, &enum_.impls, style)?;
+        } else if let Some(trait_) = &inner.trait_ {
+            write_docs(out, item, "", style)?;
+            item.write_attributes(out, "")?;
+            let generics = trait_.generics.as_ref();
+            let unsafety = if trait_.is_unsafe { "unsafe " } else { "" };
+            let params = generic_params(generics);
+            let bounds = if trait_.bounds.is_empty() {
+                String::new()
+            } else {
+                format!(": {}", bounds_source(&trait_.bounds))
+            };
+            let where_ = where_clause(generics);
+            writeln!(
+                out,
+                "{vis}{unsafety}trait {name}{params}{bounds}{where_} {{"
+            )?;
+            self.write_assoc_items_source(out, &trait_.items, style, true)?;
+            writeln!(out, "}}")?;
+
+            let for_other_types = self.impls_for_other_types(trait_);
+            self.write_impls_source(out, &item.id, &for_other_types, style)?;
+        } else if let Some(union_) = &inner.union_ {
+            write_docs(out, item, "", style)?;
+            if style.impls {
+                self.write_derives(out, &union_.impls)?;
+            }
+            self.write_union_source(out, item, union_, style)?;
+            self.write_impls_source(out, &item.id, &union_.impls, style)?;
+        } else if let Some(source) = item.type_alias_source() {
+            write_docs(out, item, "", style)?;
+            writeln!(out, "{source}")?;
+        } else if let Some(source) = item.static_source() {
+            write_docs(out, item, "", style)?;
+            item.write_attributes(out, "")?;
+            writeln!(out, "{source}")?;
+        } else if let Some(source) = item.macro_source() {
+            // Procedural macros can only be defined in proc-macro crates
+            if style.compilable && inner.proc_macro.is_some() {
+                return Ok(());
+            }
+            write_docs(out, item, "", style)?;
+            item.write_attributes(out, "")?;
+            writeln!(out, "{source}")?;
+        }
+
+        Ok(())
+    }
+
+    /// Write a union without its docs, e.g. `#[repr(C)] pub union Number {
+    /// int: i32, float: f32 }`, with its attributes and fields.
+    pub(crate) fn write_union_source(
+        &self,
+        out: &mut String,
+        item: &RustDocItem,
+        union_: &UnionDetails,
+        style: SourceStyle,
+    ) -> fmt::Result {
+        let Some(name) = &item.name else {
+            return Ok(());
+        };
+        item.write_attributes(out, "")?;
+        let vis = visibility(item);
+        let generics = union_.generics.as_ref();
+        let params = generic_params(generics);
+        let where_ = where_clause(generics);
+        writeln!(out, "{vis}union {name}{params}{where_} {{")?;
+        self.write_fields_source(out, &union_.fields, style)?;
+        match (union_.fields_stripped, style.compilable) {
+            (true, true) =>
+                writeln!(out, "    _private: {},", phantom_data(generics))?,
+            (true, false) => writeln!(out, "    // Some fields are private")?,
+            (false, _) => {}
+        }
+        writeln!(out, "}}")
+    }
+
+    /// Write a function or method, e.g. `pub fn greet(name: &str) -> String
+    /// { ... }`. `vis` is empty for items of traits and trait impls.
+    fn write_fn_source(
+        &self,
+        out: &mut String,
+        item: &RustDocItem,
+        indent: &str,
+        style: SourceStyle,
+        vis: &str,
+    ) -> fmt::Result {
+        let Some(function) =
+            item.inner.as_ref().and_then(|i| i.function.as_ref())
+        else {
+            return Ok(());
+        };
+
+        write_docs(out, item, indent, style)?;
+        item.write_attributes(out, indent)?;
+        write!(out, "{indent}")?;
+        item.write_fn_signature(out, vis)?;
+
+        // Required methods of traits have no body
+        let is_required = vis.is_empty()
+            && !function.has_body
+            && self.parent(&item.id).is_some_and(|(_, parent)| {
+                parent.inner.as_ref().is_some_and(|i| i.trait_.is_some())
+            });
+        if is_required {
+            writeln!(out, ";")
+        } else {
+            writeln!(out, " {}", style.body)
+        }
+    }
+
+    /// Write the named fields of a struct or variant, one per line.
+    fn write_fields_source(
+        &self,
+        out: &mut String,
+        field_ids: &[String],
+        style: SourceStyle,
+    ) -> fmt::Result {
+        for field in field_ids.iter().filter_map(|id| self.index.get(id)) {
+            if !self.is_shown(field) {
+                continue;
+            }
+            let (Some(name), Some(type_)) = (
+                &field.name,
+                field.inner.as_ref().and_then(|i| i.struct_field.as_ref()),
+            ) else {
+                continue;
+            };
+            write_docs(out, field, "    ", style)?;
+            let vis = visibility(field);
+            writeln!(out, "    {vis}{name}: {type_},")?;
+        }
+        Ok(())
+    }
+
+    /// The fields of a tuple struct or variant, e.g. `pub u8, String`.
+    /// Private fields which were stripped from the JSON are `()`, or
+    /// `PhantomData` of the generic parameters in compilable source.
+    fn tuple_fields_source(
+        &self,
+        field_ids: &[Option<String>],
+        generics: Option<&Generics>,
+        style: SourceStyle,
+    ) -> String {
+        field_ids
+            .iter()
+            .map(|id| {
+                let field = id.as_ref().and_then(|id| self.index.get(id));
+                let type_ = field
+                    .and_then(|field| field.inner.as_ref())
+                    .and_then(|inner| inner.struct_field.as_ref());
+                match (field, type_) {
+                    (Some(field), Some(type_)) =>
+                        format!("{}{type_}", visibility(field)),
+                    _ if style.compilable => phantom_data(generics),
+                    _ => "/* private field */ ()".to_string(),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    fn write_variant_source(
+        &self,
+        out: &mut String,
+        variant: &RustDocItem,
+        style: SourceStyle,
+    ) -> fmt::Result {
+        write_docs(out, variant, "    ", style)?;
+        self.write_variant(out, variant, "    ", style)
+    }
+
+    /// Write an enum variant without its docs, e.g. `Rect(Point, Point),`,
+    /// with its attributes, fields and discriminant.
+    pub(crate) fn write_variant(
+        &self,
+        out: &mut String,
+        variant: &RustDocItem,
+        indent: &str,
+        style: SourceStyle,
+    ) -> fmt::Result {
+        let (Some(name), Some(details)) = (
+            &variant.name,
+            variant.inner.as_ref().and_then(|i| i.variant.as_ref()),
+        ) else {
+            return Ok(());
+        };
+
+        variant.write_attributes(out, indent)?;
+        let discriminant = details
+            .discriminant
+            .as_ref()
+            .and_then(|discriminant| discriminant.get("expr"))
+            .and_then(|expr| expr.as_str())
+            .map(|expr| format!(" = {expr}"))
+            .unwrap_or_default();
+        match serde_json::from_value::<VariantKind>(details.kind.clone()) {
+            Ok(VariantKind::Tuple(fields)) => writeln!(
+                out,
+                "{indent}{name}({}){discriminant},",
+                self.tuple_fields_source(&fields, None, style)
+            ),
+            Ok(VariantKind::Struct {
+                fields,
+                fields_stripped,
+            }) => {
+                writeln!(out, "{indent}{name} {{")?;
+                let mut fields_source = String::new();
+                self.write_fields_source(&mut fields_source, &fields, style)?;
+                for line in fields_source.lines() {
+                    writeln!(out, "{indent}{line}")?;
+                }
+                if fields_stripped {
+                    writeln!(out, "{indent}    // Some fields are private")?;
+                }
+                writeln!(out, "{indent}}}{discriminant},")
+            }
+            Ok(VariantKind::Plain) | Err(_) =>
+                writeln!(out, "{indent}{name}{discriminant},"),
+        }
+    }
+
+    /// Write the `#[derive(...)]` attribute of a type, if it has derived
+    /// impls.
+    fn write_derives(
+        &self,
+        out: &mut String,
+        impl_ids: &[String],
+    ) -> fmt::Result {
+        let mut derives = Vec::new();
+        for item in impl_ids.iter().filter_map(|id| self.index.get(id)) {
+            let trait_ = item
+                .inner
+                .as_ref()
+                .and_then(|inner| inner.impl_.as_ref())
+                .and_then(|impl_| impl_.trait_.as_ref());
+            let Some(trait_) = trait_ else {
+                continue;
+            };
+            // `derive(PartialEq)` also implements `StructuralPartialEq`
+            if is_derived(item)
+                && !trait_.name.starts_with("Structural")
+                && !derives.contains(&trait_.name.as_str())
+            {
+                derives.push(trait_.name.as_str());
+            }
+        }
+        if derives.is_empty() {
+            return Ok(());
+        }
+        // The order of the impls in the JSON varies, so sort the derives:
+        // the standard ones in their usual order, then the others by name
+        derives.sort_by_key(|name| {
+            let position = STD_DERIVES.iter().position(|std| std == name);
+            (position.unwrap_or(STD_DERIVES.len()), *name)
+        });
+        writeln!(out, "#[derive({})]", derives.join(", "))
+    }
+
+    /// Write the impl blocks of a type or trait, leaving out derived impls,
+    /// impls which rustdoc adds for auto traits, and blanket impls.
+    fn write_impls_source(
+        &self,
+        out: &mut String,
+        type_id: &str,
+        impl_ids: &[String],
+        style: SourceStyle,
+    ) -> fmt::Result {
+        if !style.impls {
+            return Ok(());
+        }
+        for (_, impl_) in self.source_impls(type_id, impl_ids) {
+            let Some(for_) = &impl_.for_ else {
+                continue;
+            };
+
+            writeln!(out)?;
+            let unsafety = if impl_.is_unsafe { "unsafe " } else { "" };
+            let generics = impl_.generics.as_ref();
+            let params = generic_params(generics);
+            let where_ = where_clause(generics);
+            let trait_ = match &impl_.trait_ {
+                Some(trait_) => format!(
+                    "{}{} for ",
+                    trait_.name,
+                    format_angle_bracketed_args(trait_.args.as_ref())
+                ),
+                None => String::new(),
+            };
+            let mut items = String::new();
+            let is_trait_impl = impl_.trait_.is_some();
+            self.write_assoc_items_source(
+                &mut items,
+                &impl_.items,
+                style,
+                is_trait_impl,
+            )?;
+            if items.is_empty() {
+                writeln!(
+                    out,
+                    "{unsafety}impl{params} {trait_}{for_}{where_} {{}}"
+                )?;
+            } else {
+                writeln!(
+                    out,
+                    "{unsafety}impl{params} {trait_}{for_}{where_} {{\n{items}}}"
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// The impls of the type with the given id which are written as source,
+    /// leaving out derived impls, impls which rustdoc adds for auto traits,
+    /// and blanket impls. Impls for other local types which mention the
+    /// type, e.g. `impl AsRef<Point> for Labeled` of `Point`, are written
+    /// with those types.
+    pub(crate) fn source_impls<'a>(
+        &'a self,
+        type_id: &'a str,
+        impl_ids: &'a [String],
+    ) -> impl Iterator<Item = (&'a RustDocItem, &'a Impl)> {
+        impl_ids
+            .iter()
+            .filter_map(|id| self.index.get(id))
+            .filter_map(move |item| {
+                let impl_ = item.inner.as_ref()?.impl_.as_ref()?;
+                let for_id = match &impl_.for_ {
+                    Some(Parameter::ResolvedPath { resolved_path }) =>
+                        resolved_path.id.as_deref(),
+                    _ => None,
+                };
+                let is_for_other_type = for_id.is_some_and(|for_id| {
+                    for_id != type_id && self.index.contains_key(for_id)
+                });
+                let is_written = !impl_.synthetic
+                    && impl_.blanket_impl.is_none()
+                    && !is_derived(item)
+                    && !is_for_other_type
+                    && self.is_shown(item);
+                is_written.then_some((item, impl_))
+            })
+    }
+
+    /// The impls of a trait for types which aren't local, e.g. `impl FromHex
+    /// for Vec<u8>`, which are written after the trait. Impls for local types
+    /// are written with the type.
+    pub(crate) fn impls_for_other_types(
+        &self,
+        trait_: &TraitInfo,
+    ) -> Vec<String> {
+        trait_
+            .implementations
+            .iter()
+            .filter(|id| {
+                let for_id = self
+                    .index
+                    .get(*id)
+                    .and_then(|item| item.inner.as_ref())
+                    .and_then(|inner| inner.impl_.as_ref())
+                    .and_then(|impl_| match &impl_.for_ {
+                        Some(Parameter::ResolvedPath { resolved_path }) =>
+                            resolved_path.id.as_deref(),
+                        _ => None,
+                    });
+                !for_id.is_some_and(|for_id| self.index.contains_key(for_id))
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Write the methods, associated types and associated constants of a
+    /// trait or impl.
+    pub(crate) fn write_assoc_items_source(
+        &self,
+        out: &mut String,
+        item_ids: &[String],
+        style: SourceStyle,
+        in_trait: bool,
+    ) -> fmt::Result {
+        for item in item_ids.iter().filter_map(|id| self.index.get(id)) {
+            let (Some(inner), Some(name)) = (&item.inner, &item.name) else {
+                continue;
+            };
+            if !self.is_shown(item) && !in_trait {
+                continue;
+            }
+            let vis = if in_trait { "" } else { visibility(item) };
+
+            if inner.function.is_some() {
+                self.write_fn_source(out, item, "    ", style, vis)?;
+            } else if let Some(assoc_type) = &inner.assoc_type {
+                write_docs(out, item, "    ", style)?;
+                let generics = assoc_type.generics.as_ref();
+                let bounds = if assoc_type.bounds.is_empty() {
+                    String::new()
+                } else {
+                    format!(": {}", bounds_source(&assoc_type.bounds))
+                };
+                let default = match &assoc_type.default {
+                    Some(type_) => format!(" = {type_}"),
+                    None => String::new(),
+                };
+                writeln!(
+                    out,
+                    "    type {name}{}{bounds}{}{default};",
+                    generic_params(generics),
+                    where_clause(generics),
+                )?;
+            } else if let Some(assoc_const) = &inner.assoc_const {
+                write_docs(out, item, "    ", style)?;
+                let default = match &assoc_const.default {
+                    Some(value) => format!(" = {value}"),
+                    None => String::new(),
+                };
+                writeln!(
+                    out,
+                    "    {vis}const {name}: {}{default};",
+                    assoc_const.type_
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A placeholder for private fields which uses all of the generic parameters,
+/// e.g. `::core::marker::PhantomData<(&'a (), fn() -> *const T)>`, as
+/// unused parameters are an error.
+fn phantom_data(generics: Option<&Generics>) -> String {
+    let params = generics
+        .map(|generics| generics.params.as_slice())
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|param| {
+            let name = param.name.as_deref()?;
+            let kind = param.kind.as_ref()?;
+            if kind.lifetime.is_some() {
+                Some(format!("&{name} ()"))
+            } else if kind.type_.as_ref().is_some_and(|type_| !type_.synthetic)
+            {
+                // `*const T` also allows `T: ?Sized`
+                Some(format!("fn() -> *const {name}"))
+            } else {
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+    let params = match params.as_slice() {
+        [param] => param.clone(),
+        _ => format!("({})", params.join(", ")),
+    };
+    format!("::core::marker::PhantomData<{params}>")
+}
+
+/// The derivable traits of the standard library, in the order they are
+/// usually derived in.
+const STD_DERIVES: [&str; 9] = [
+    "Debug",
+    "Clone",
+    "Copy",
+    "Default",
+    "PartialEq",
+    "Eq",
+    "PartialOrd",
+    "Ord",
+    "Hash",
+];
+
+/// Whether an impl was generated by a `#[derive(...)]`.
+fn is_derived(item: &RustDocItem) -> bool {
+    item.attrs
+        .iter()
+        .any(|attr| attr.as_str() == Some("#[automatically_derived]"))
+}
+
+/// The visibility of an item as written in source, e.g. `pub `.
+fn visibility(item: &RustDocItem) -> &'static str {
+    match item.visibility.as_deref() {
+        Some("public") => "pub ",
+        Some("crate") => "pub(crate) ",
+        _ => "",
+    }
+}
+
+/// Write the docs of an item as `///` comments: all of them, or only the
+/// first paragraph on one line, depending on the style.
+fn write_docs(
+    out: &mut String,
+    item: &RustDocItem,
+    indent: &str,
+    style: SourceStyle,
+) -> fmt::Result {
+    let Some(docs) = item.docs.as_deref() else {
+        return Ok(());
+    };
+
+    if style.full_docs {
+        for line in docs.lines() {
+            if line.is_empty() {
+                writeln!(out, "{indent}///")?;
+            } else {
+                writeln!(out, "{indent}/// {line}")?;
+            }
+        }
+        return Ok(());
+    }
+
+    if let Some(summary) = item.summary() {
+        writeln!(out, "{indent}/// {summary}")?;
+    }
+    Ok(())
+}
+
+/// A parameter of a function, with `self` parameters in their short form,
+/// e.g. `&mut self` rather than `self: &mut Self`.
+fn param_source(name: &str, param: &Parameter) -> String {
+    if name == "self" {
+        match param {
+            Parameter::Generic { generic } if generic == "Self" =>
+                return "self".to_string(),
+            Parameter::BorrowedRef { borrowed_ref } =>
+                if let Parameter::Generic { generic } = &*borrowed_ref.type_ {
+                    if generic == "Self" {
+                        let lifetime = borrowed_ref
+                            .lifetime
+                            .as_ref()
+                            .map(|lifetime| format!("{lifetime} "))
+                            .unwrap_or_default();
+                        let mutability =
+                            if borrowed_ref.mutable { "mut " } else { "" };
+                        return format!("&{lifetime}{mutability}self");
+                    }
+                },
+            _ => {}
+        }
+    }
+    format!("{name}: {param}")
+}
+
+/// The generic parameters of an item, e.g. `<'a, T: Clone, const N: usize>`,
+/// leaving out the parameters which stand for `impl Trait` arguments.
+pub(crate) fn generic_params(generics: Option<&Generics>) -> String {
+    let Some(generics) = generics else {
+        return String::new();
+    };
+    let params = generics
+        .params
+        .iter()
+        .filter_map(|param| {
+            let name = param.name.as_deref()?;
+            let Some(kind) = &param.kind else {
+                return Some(name.to_string());
+            };
+            if let Some(lifetime) = &kind.lifetime {
+                return Some(if lifetime.outlives.is_empty() {
+                    name.to_string()
+                } else {
+                    format!("{name}: {}", lifetime.outlives.join(" + "))
+                });
+            }
+            if let Some(type_) = &kind.type_ {
+                if type_.synthetic {
+                    return None;
+                }
+                let mut param = name.to_string();
+                if !type_.bounds.is_empty() {
+                    write!(param, ": {}", bounds_source(&type_.bounds)).ok()?;
+                }
+                if let Some(default) = &type_.default {
+                    write!(param, " = {default}").ok()?;
+                }
+                return Some(param);
+            }
+            if let Some(const_) = &kind.const_ {
+                let mut param = format!("const {name}: {}", const_.type_);
+                if let Some(default) = &const_.default {
+                    write!(param, " = {default}").ok()?;
+                }
+                return Some(param);
+            }
+            Some(name.to_string())
+        })
+        .collect::<Vec<_>>();
+
+    if params.is_empty() {
+        String::new()
+    } else {
+        format!("<{}>", params.join(", "))
+    }
+}
+
+/// The `where` clause of an item, e.g. ` where T: Clone`, if it has one.
+pub(crate) fn where_clause(generics: Option<&Generics>) -> String {
+    let Some(generics) = generics else {
+        return String::new();
+    };
+    let predicates = generics
+        .where_predicates
+        .iter()
+        .filter_map(|predicate| match predicate {
+            WherePredicate::Bound { bound_predicate } => Some(format!(
+                "{}{}: {}",
+                higher_ranked(&bound_predicate.generic_params),
+                bound_predicate.type_,
+                bounds_source(&bound_predicate.bounds)
+            )),
+            WherePredicate::Lifetime { lifetime_predicate } => Some(format!(
+                "{}: {}",
+                lifetime_predicate.lifetime,
+                lifetime_predicate.outlives.join(" + ")
+            )),
+            WherePredicate::Other(_) => None,
+        })
+        .collect::<Vec<_>>();
+
+    if predicates.is_empty() {
+        String::new()
+    } else {
+        format!(" where {}", predicates.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const COMMON_JSON_STR: &str =
+        include_str!("../test-data/common/rustdoc.json");
+    const HEX_JSON_STR: &str = include_str!("../test-data/hex/rustdoc.json");
+    const FFI_JSON_STR: &str = include_str!("../test-data/ffi/rustdoc.json");
+
+    #[test]
+    fn test_skeleton() {
+        let doc = serde_json::from_str::<RustDoc>(COMMON_JSON_STR).unwrap();
+        let skeleton = doc.to_skeleton();
+
+        assert!(skeleton.starts_with("# common v0.1.0\n"));
+        assert!(skeleton.contains("## `common::shapes`\n\n```rust\n"));
+        // Docs are cut to their summary
+        assert!(skeleton.contains(
+            "/// Greets someone by name. Prefer this over the deprecated \
+             [`hello`].\n#[must_use]\npub fn greet(name: &str) -> String \
+             { ... }\n"
+        ));
+        // Derived impls become a `derive` attribute
+        assert!(skeleton.contains("#[derive(Debug)]\npub enum ConfigError {"));
+        // in a stable order
+        assert!(skeleton.contains(
+            "#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]\n\
+             #[repr(u8)]\n#[non_exhaustive]\npub enum Level {"
+        ));
+        assert!(skeleton.contains(
+            "impl Point {\n    /// Creates a new point.\n    pub fn new(x: \
+             i32, y: i32) -> Self { ... }\n"
+        ));
+        // Required and provided trait methods
+        assert!(skeleton.contains("    fn draw(&self) -> String;\n"));
+        assert!(skeleton.contains(
+            "/// Logs a message at the given level.\n#[macro_export]\n\
+             macro_rules! log {\n"
+        ));
+        assert!(
+            skeleton.contains("    fn draw_debug(&self) -> String { ... }\n")
+        );
+        assert!(skeleton.contains("impl Error for ConfigError {}\n"));
+        assert!(skeleton.contains("    Rect(Point, Point),\n"));
+        assert!(skeleton.contains("    Error = 1,\n"));
+        assert!(skeleton.contains("pub use crate::shapes::*;\n"));
+        // Auto trait and blanket impls are left out
+        assert!(!skeleton.contains("impl Send"));
+        assert!(!skeleton.contains("impl<T> From<T>"));
+        // Impls which mention another type are written once
+        assert_eq!(
+            skeleton.matches("impl AsRef<Point> for Labeled").count(),
+            1
+        );
+        assert!(skeleton.contains("impl From<Point> for [i32; 2] {"));
+        assert!(skeleton
+            .contains("pub type ConfigResult<T> = Result<T, ConfigError>;\n"));
+    }
+
+    #[test]
+    fn test_skeleton_generics() {
+        let doc = serde_json::from_str::<RustDoc>(HEX_JSON_STR).unwrap();
+        let skeleton = doc.to_skeleton();
+
+        assert!(skeleton.contains(
+            "pub const fn decode_const<const N: usize>(hex: &[u8]) -> [u8; \
+             N] { ... }\n"
+        ));
+        assert!(skeleton.contains("pub trait FromHex: Sized {\n"));
+        // Impls of local traits for foreign types follow the trait
+        assert!(skeleton.contains("impl FromHex for Vec<u8> {\n"));
+        assert!(skeleton.contains("impl<const N: usize> FromHex for [u8; N]"));
+    }
+
+    #[test]
+    fn test_skeleton_ffi() {
+        let doc = serde_json::from_str::<RustDoc>(FFI_JSON_STR).unwrap();
+        let skeleton = doc.to_skeleton();
+
+        assert!(skeleton.contains(
+            "#[derive(Clone, Copy)]\n#[repr(C)]\npub union Number {\n    /// \
+             The value as an integer.\n    pub int: std::os::raw::c_int,\n"
+        ));
+        assert!(skeleton.contains("pub static VERSION: &str = \"1.0\";\n"));
+        assert!(skeleton.contains(
+            "    pub on_error: extern \"C\" fn(code: std::os::raw::c_int),\n"
+        ));
+        assert!(skeleton.contains(
+            "    pub log: Option<unsafe extern \"C\" fn(format: *const \
+             std::os::raw::c_char, ...)>,\n"
+        ));
+        // Foreign items are in their `extern` block, without qualifiers
+        assert!(skeleton.contains(
+            "extern \"C\" {\n    /// Returns the absolute value of `x`.\n    \
+             pub fn abs(x: c_int) -> c_int;\n"
+        ));
+        assert!(skeleton.contains(
+            "#[no_mangle]\npub extern \"C\" fn add(a: std::os::raw::c_int"
+        ));
+        // Statics are in the block of their module's foreign functions
+        assert!(skeleton.contains(
+            "extern \"system\" {\n    /// Returns the code of the calling \
+             thread's last error.\n    pub fn get_last_error() -> u32;\n    \
+             /// The version of the loaded API.\n    pub static api_version: \
+             u32;\n}\n"
+        ));
+        assert!(!skeleton.contains("extern \"C\" {\n    /// The version"));
+    }
+}
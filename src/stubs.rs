@@ -0,0 +1,476 @@
+//! A stub crate generated from the docs: the crate's modules as files, with
+//! the full signatures and docs of its items, and `unimplemented!()` bodies.
+//!
+//! Some AI tools work better with Rust source than with markdown, and the
+//! stubs can be type-checked, e.g. to check code written against the API.
+
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Write},
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    skeleton::SourceStyle, visit::TypePath, RustDoc, RustDocItem, StructKind,
+};
+
+/// Public modules of the standard library within other modules, whose items
+/// are imported from the module itself, e.g. `std::os::raw::c_int`.
+const NESTED_STD_MODULES: &[&[&str]] =
+    &[&["os", "raw"], &["sync", "atomic"], &["sync", "mpsc"]];
+
+/// The names which the standard library prelude brings into scope, which the
+/// stubs needn't import.
+const PRELUDE: &[&str] = &[
+    "AsMut",
+    "AsRef",
+    "Box",
+    "Clone",
+    "Copy",
+    "Default",
+    "DoubleEndedIterator",
+    "Drop",
+    "Eq",
+    "ExactSizeIterator",
+    "Extend",
+    "Fn",
+    "FnMut",
+    "FnOnce",
+    "From",
+    "FromIterator",
+    "Into",
+    "IntoIterator",
+    "Iterator",
+    "Option",
+    "Ord",
+    "PartialEq",
+    "PartialOrd",
+    "Result",
+    "Send",
+    "Sized",
+    "String",
+    "Sync",
+    "ToOwned",
+    "ToString",
+    "TryFrom",
+    "TryInto",
+    "Unpin",
+    "Vec",
+];
+
+const STUB: SourceStyle = SourceStyle {
+    full_docs: true,
+    body: "{ unimplemented!() }",
+    compilable: true,
+    impls: true,
+};
+
+impl RustDoc {
+    /// The files of a stub crate, e.g. `src/lib.rs` and `src/shapes.rs`,
+    /// with their contents. The manifest is left out, as the JSON doesn't
+    /// list the crate's dependencies.
+    pub fn to_stub_files(&self) -> Vec<(PathBuf, String)> {
+        self.modules()
+            .into_iter()
+            .map(|(path, module)| {
+                let file = match path.as_slice() {
+                    [_] => PathBuf::from("src/lib.rs"),
+                    [_, modules @ ..] => {
+                        let mut file = PathBuf::from("src");
+                        file.extend(modules);
+                        file.set_extension("rs");
+                        file
+                    }
+                    [] => unreachable!("Module paths start with the crate"),
+                };
+                let mut source = String::new();
+                self.write_stub_module(&mut source, &path, module)
+                    .expect("Writing to a String can't fail");
+                (file, source)
+            })
+            .collect()
+    }
+
+    /// Write a stub crate to `dir`, which compiles with `cargo check` once
+    /// the crate's dependencies are added to its `Cargo.toml`. A minimal
+    /// `Cargo.toml` is written if `dir` doesn't have one yet.
+    pub fn write_stub_crate(&self, dir: impl AsRef<Path>) -> io::Result<()> {
+        let dir = dir.as_ref();
+        let manifest = dir.join("Cargo.toml");
+        if !manifest.exists() {
+            fs::create_dir_all(dir)?;
+            let manifest_contents = format!(
+                "[package]\nname = \"{}\"\nversion = \"{}\"\nedition = \
+                 \"2021\"\n\n[workspace]\n\n[dependencies]\n",
+                self.crate_name(),
+                self.crate_version
+            );
+            fs::write(manifest, manifest_contents)?;
+        }
+
+        for (file, source) in self.to_stub_files() {
+            let file = dir.join(file);
+            if let Some(parent) = file.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(file, source)?;
+        }
+        Ok(())
+    }
+
+    fn write_stub_module(
+        &self,
+        out: &mut String,
+        path: &[String],
+        module: &RustDocItem,
+    ) -> fmt::Result {
+        if let Some(docs) = &module.docs {
+            for line in docs.lines() {
+                if line.is_empty() {
+                    writeln!(out, "//!")?;
+                } else {
+                    writeln!(out, "//! {line}")?;
+                }
+            }
+            writeln!(out)?;
+        }
+        // The parameters and private items of stubs go unused
+        if path.len() == 1 {
+            writeln!(out, "#![allow(unused)]")?;
+            writeln!(out)?;
+        }
+
+        let mut imports = self
+            .stub_imports(path, module)
+            .into_values()
+            .collect::<Vec<_>>();
+        imports.sort();
+        for import in &imports {
+            writeln!(out, "{import}")?;
+        }
+        if !imports.is_empty() {
+            writeln!(out)?;
+        }
+
+        self.write_module_source(out, module, STUB)
+    }
+
+    /// The `use` items which bring the names in the module's signatures into
+    /// scope, by the names they bring into scope. rustdoc keeps the names as
+    /// written, e.g. `fmt::Formatter`, but not the `use` items which they
+    /// refer to, so these are made from the canonical paths of the items,
+    /// e.g. `use core::fmt;`.
+    fn stub_imports(
+        &self,
+        path: &[String],
+        module: &RustDocItem,
+    ) -> BTreeMap<String, String> {
+        let mut items = Vec::new();
+        for item in self.module_items(module) {
+            let item = self.unwritten_reexport(item).unwrap_or(item);
+            self.collect_written_items(item, &mut items);
+        }
+
+        // Names which the module already has, e.g. from `pub use` items
+        let mut taken = self
+            .module_items(module)
+            .filter_map(|item| match item.import() {
+                Some(import) => Some(import.name.clone()),
+                None => item.name.clone(),
+            })
+            .collect::<Vec<_>>();
+
+        let mut imports = BTreeMap::new();
+        for type_path in items.iter().flat_map(|item| item.type_paths()) {
+            let Some((name, import)) = self.stub_import(path, type_path) else {
+                continue;
+            };
+            if !taken.contains(&name) {
+                taken.push(name.clone());
+                imports.insert(name, import);
+            }
+        }
+        imports
+    }
+
+    /// The items which are written as source within an item, including the
+    /// item itself, e.g. a struct with its fields and impls.
+    fn collect_written_items<'a>(
+        &'a self,
+        item: &'a RustDocItem,
+        items: &mut Vec<&'a RustDocItem>,
+    ) {
+        items.push(item);
+        let Some(inner) = &item.inner else {
+            return;
+        };
+
+        let mut children = Vec::new();
+        let mut impls = Vec::new();
+        if let Some(struct_) = &inner.struct_ {
+            children.extend(struct_.field_ids());
+            if let Some(StructKind::Tuple { tuple: Some(tuple) }) =
+                &struct_.kind
+            {
+                let ids = tuple.iter().filter_map(|field| field.as_str());
+                children.extend(ids.map(String::from));
+            }
+            impls = self.source_impls(&item.id, &struct_.impls).collect();
+        } else if let Some(enum_) = &inner.enum_ {
+            children.extend(enum_.variants.iter().cloned());
+            impls = self.source_impls(&item.id, &enum_.impls).collect();
+        } else if let Some(union_) = &inner.union_ {
+            children.extend(union_.fields.iter().cloned());
+            impls = self.source_impls(&item.id, &union_.impls).collect();
+        } else if let Some(variant) = &inner.variant {
+            children.extend(variant.field_ids());
+        } else if let Some(trait_) = &inner.trait_ {
+            children.extend(trait_.items.iter().cloned());
+            children.extend(self.impls_for_other_types(trait_));
+        } else if let Some(impl_) = &inner.impl_ {
+            children.extend(impl_.items.iter().cloned());
+        }
+
+        for child in children.iter().filter_map(|id| self.index.get(id)) {
+            self.collect_written_items(child, items);
+        }
+        for (impl_item, _) in impls {
+            self.collect_written_items(impl_item, items);
+        }
+    }
+
+    /// The `use` item for a name in a signature within the module at `path`,
+    /// with the name it brings into scope, e.g. `fmt` and `use core::fmt;`
+    /// for `fmt::Formatter`, unless the name is in scope already.
+    fn stub_import(
+        &self,
+        path: &[String],
+        type_path: TypePath<'_>,
+    ) -> Option<(String, String)> {
+        let written = type_path.name.split("::").collect::<Vec<_>>();
+        let first = *written.first()?;
+        if ["crate", "self", "super", "Self"].contains(&first) {
+            return None;
+        }
+        let summary = self.paths.get(type_path.id)?;
+        let canonical = &summary.path;
+        // The canonical path of the first name, e.g. `core::fmt`
+        let len = canonical.len().checked_sub(written.len() - 1)?;
+        let mut import = canonical[..len].to_vec();
+
+        let is_local = summary.crate_id == 0;
+        let is_std = ["core", "alloc", "std"].contains(&import[0].as_str());
+        if import.len() < 2
+            || (is_std && written.len() == 1 && PRELUDE.contains(&first))
+            || (written.len() > 1 && self.is_crate_name(first))
+            || (is_local && import[..len - 1] == *path)
+        {
+            // A crate, a name from the prelude, or an item of this module
+            return None;
+        }
+        // The canonical paths of the standard library go through private
+        // modules, e.g. `core::ops::deref::Deref`, whose items are re-exported
+        // by their parent, e.g. `core::ops::Deref`
+        let depth = NESTED_STD_MODULES
+            .iter()
+            .find(|module| {
+                import.len() > module.len()
+                    && module.iter().zip(&import[1..]).all(|(a, b)| a == b)
+            })
+            .map_or(1, |module| module.len());
+        if is_std && import.len() > depth + 2 {
+            import.drain(depth + 1..import.len() - 1);
+        }
+        if is_local {
+            import[0] = "crate".to_string();
+        } else if import[0] == "alloc" && self.is_crate_name("std") {
+            // `alloc` is only in scope with `extern crate alloc;`
+            import[0] = "std".to_string();
+        }
+
+        let last = import.last()?;
+        let import = if last == first {
+            format!("use {};", import.join("::"))
+        } else {
+            format!("use {} as {first};", import.join("::"))
+        };
+        Some((first.to_string(), import))
+    }
+
+    /// The item which a `use` item re-exports, if the module which defines
+    /// it isn't written, e.g. a private module which rustdoc leaves out of
+    /// the JSON. Such items are written where they are re-exported, as the
+    /// `use` item wouldn't compile.
+    pub(crate) fn unwritten_reexport(
+        &self,
+        item: &RustDocItem,
+    ) -> Option<&RustDocItem> {
+        let import = item.import().filter(|import| !import.glob)?;
+        let target = self.index.get(import.id.as_ref()?)?;
+        let target_inner = target.inner.as_ref()?;
+        if target_inner.module.is_some() || target_inner.import.is_some() {
+            return None;
+        }
+        let is_written = self.modules().iter().any(|(_, module)| {
+            self.module_items(module).any(|child| child.id == target.id)
+        });
+        (!is_written).then_some(target)
+    }
+
+    /// Write the source of a re-exported item in place of the `use` item
+    /// which re-exports it, under the name and visibility of the `use`.
+    pub(crate) fn write_inlined_source(
+        &self,
+        out: &mut String,
+        reexport: &RustDocItem,
+        target: &RustDocItem,
+        style: SourceStyle,
+    ) -> fmt::Result {
+        let Some(import) = reexport.import() else {
+            return Ok(());
+        };
+        // Items aren't `Clone`, so copy it through JSON
+        let json = serde_json::to_value(target).expect("Items serialize");
+        let mut inlined = serde_json::from_value::<RustDocItem>(json)
+            .expect("Items deserialize");
+        inlined.name = Some(import.name.clone());
+        inlined.visibility.clone_from(&reexport.visibility);
+        self.write_item_source(out, &inlined, style)?;
+
+        // Its impls and signatures refer to it by its own name
+        match &target.name {
+            Some(name) if *name != import.name =>
+                writeln!(out, "use self::{} as {name};", import.name),
+            _ => Ok(()),
+        }
+    }
+
+    /// Whether `name` is the name of this crate or one it depends on.
+    fn is_crate_name(&self, name: &str) -> bool {
+        self.crate_name() == name
+            || self
+                .external_crates
+                .values()
+                .any(|external| external.name == name)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, process::Command};
+
+    use super::*;
+
+    const COMMON_JSON_STR: &str =
+        include_str!("../test-data/common/rustdoc.json");
+    const HEX_JSON_STR: &str = include_str!("../test-data/hex/rustdoc.json");
+    const INLINE_JSON_STR: &str =
+        include_str!("../test-data/inline/rustdoc.json");
+
+    fn stub_files(json: &str) -> BTreeMap<PathBuf, String> {
+        let doc = serde_json::from_str::<RustDoc>(json).unwrap();
+        doc.to_stub_files().into_iter().collect()
+    }
+
+    #[test]
+    fn test_stub_files() {
+        let files = stub_files(COMMON_JSON_STR);
+        let paths = files.keys().map(|path| path.to_str().unwrap());
+        assert_eq!(
+            paths.collect::<Vec<_>>(),
+            [
+                "src/imp.rs",
+                "src/lib.rs",
+                "src/prelude.rs",
+                "src/shapes.rs"
+            ]
+        );
+
+        let shapes = &files[Path::new("src/shapes.rs")];
+        assert!(shapes.starts_with("//! Simple geometry types.\n"));
+        // `impl Draw for shapes::Point` is written within `shapes`
+        assert!(shapes.contains("use crate::Draw;\nuse crate::shapes;\n"));
+        assert!(shapes.contains(
+            "    /// Creates a new point.\n    pub fn new(x: i32, y: i32) -> \
+             Self { unimplemented!() }\n"
+        ));
+
+        let lib = &files[Path::new("src/lib.rs")];
+        assert!(lib.contains("\npub mod shapes;\n"));
+        assert!(lib.contains("use core::fmt;\n"));
+        // Not `core::ops::deref::Deref`, which is private
+        assert!(shapes.contains("use core::ops::Deref;\n"));
+        // Names defined in the module aren't imported
+        assert!(!lib.contains("use crate::ConfigError;"));
+    }
+
+    /// Generate stubs from the hex fixture and type-check them, which checks
+    /// that signatures are rendered as valid Rust.
+    #[test]
+    fn test_stubs_compile() {
+        let files = stub_files(HEX_JSON_STR);
+        let lib = &files[Path::new("src/lib.rs")];
+        assert!(lib.contains(
+            "pub const fn decode_const<const N: usize>(hex: &[u8]) -> [u8; \
+             N] { unimplemented!() }\n"
+        ));
+        assert!(lib.contains(
+            "pub struct HexDisplay<'a>(::core::marker::PhantomData<&'a ()>);\n"
+        ));
+        assert!(lib.contains("use std::borrow::Cow;\n"));
+
+        check_stubs(HEX_JSON_STR, "hex");
+    }
+
+    /// Items re-exported from private modules, which rustdoc leaves out of
+    /// the JSON, are written where they are re-exported.
+    #[test]
+    fn test_stubs_of_private_modules_compile() {
+        let files = stub_files(INLINE_JSON_STR);
+        let lib = &files[Path::new("src/lib.rs")];
+        assert!(!lib.contains("pub use"));
+        assert!(lib.contains(
+            "/// Finds the first occurrence of a byte in a haystack.\npub fn \
+             find(haystack: &[u8], needle: u8) -> Option<usize> { \
+             unimplemented!() }\n"
+        ));
+        assert!(lib.contains("use core::iter::FusedIterator;\n"));
+        assert!(lib.contains("impl<'h> FusedIterator for Matches<'h> {}\n"));
+
+        // Renamed items keep their own name in scope for their impls
+        let options = &files[Path::new("src/options.rs")];
+        assert!(options.contains("pub enum Prefilter {\n"));
+        assert!(options.contains("use self::Prefilter as PrefilterConfig;\n"));
+        // Function pointers and tuples are written as types, not `Self`
+        assert!(options.contains("    pub on_match: fn(usize) -> bool,\n"));
+        assert!(lib.contains(
+            "pub fn find_either(haystack: &[u8], needles: (u8, u8)) -> \
+             Option<usize> { unimplemented!() }\n"
+        ));
+
+        check_stubs(INLINE_JSON_STR, "inline");
+    }
+
+    /// Write the stubs of a fixture and type-check them, which checks that
+    /// signatures are rendered as valid Rust.
+    fn check_stubs(json: &str, name: &str) {
+        let doc = serde_json::from_str::<RustDoc>(json).unwrap();
+        let dir = env::temp_dir()
+            .join(format!("ai-rustdoc-stubs-{name}-{}", std::process::id()));
+        doc.write_stub_crate(&dir).unwrap();
+        let cargo = env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+        let output = Command::new(cargo)
+            .args(["check", "--quiet", "--offline"])
+            .current_dir(&dir)
+            .env("CARGO_TARGET_DIR", dir.join("target"))
+            .output()
+            .unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(
+            output.status.success(),
+            "The stubs of {name} don't compile:\n{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+}
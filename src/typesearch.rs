@@ -0,0 +1,846 @@
+//! Finding functions by the types they take and return, e.g. `&str` to
+//! `Result<Vec<u8>, _>`, as in rustdoc's type-based search.
+//!
+//! Types are compared by the last segment of their paths. The generic
+//! parameters of functions are unified with the types of the query, and
+//! references and common wrappers such as `Option` match more loosely, so
+//! that close matches are found too, ranked after exact ones.
+
+use std::collections::HashMap;
+
+use crate::{
+    search::Match, FunctionPointer, GenericArg, GenericArgs, Generics, Impl,
+    Parameter, ResolvedPath, ReturnType, RustDoc, RustDocItem, TraitBound,
+    TraitBoundInfo, WherePredicate,
+};
+
+const PRIMITIVES: &[&str] = &[
+    "bool", "char", "str", "u8", "u16", "u32", "u64", "u128", "usize", "i8",
+    "i16", "i32", "i64", "i128", "isize", "f32", "f64",
+];
+
+/// Types which a wanted type can be wrapped in, e.g. a function which
+/// returns `Option<String>` is a close match for one which returns `String`,
+/// and one which returns `Result<Self, E>` makes a `Self`.
+pub(crate) const WRAPPERS: &[&str] =
+    &["Option", "Result", "Box", "Rc", "Arc", "Cow"];
+
+/// Traits for which a generic `T: AsRef<[u8]>` takes a `[u8]`.
+const CONVERSION_TRAITS: &[&str] = &["AsRef", "AsMut", "Borrow", "Into"];
+
+/// A type simplified for matching: paths by their last segment, without
+/// lifetimes or const generics.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Ty {
+    Path {
+        name: String,
+        args: Vec<Ty>,
+    },
+    Ref {
+        mutable: bool,
+        inner: Box<Ty>,
+    },
+    Slice(Box<Ty>),
+    Array(Box<Ty>),
+    Tuple(Vec<Ty>),
+    Primitive(String),
+    /// A generic parameter of a function, or of a query, e.g. `T`.
+    Generic(String),
+    /// `impl Trait` or `dyn Trait`, by its traits.
+    Traits(Vec<Ty>),
+    /// `_` in a query, which matches any type, or a type of a function
+    /// which we can't read.
+    Infer,
+}
+
+impl RustDoc {
+    /// The functions and methods which take the given types, in any order,
+    /// and return the given type, best match first. Types are written as in
+    /// Rust, e.g. `find_fns(&["&str"], Some("Result<Vec<u8>, _>"))`, where
+    /// `_` matches any type and single capital letters, e.g. `T`, are
+    /// generic. Fails if a type can't be parsed.
+    pub fn find_fns(
+        &self,
+        inputs: &[&str],
+        output: Option<&str>,
+    ) -> Result<Vec<Match>, String> {
+        let inputs = inputs
+            .iter()
+            .map(|input| parse_type(input))
+            .collect::<Result<Vec<_>, _>>()?;
+        let output = output.map(parse_type).transpose()?;
+
+        let mut matches = self
+            .index
+            .iter()
+            .filter(|(id, item)| self.is_listed(id, item))
+            // Including the methods of trait impls, e.g. `FromStr::from_str`
+            .filter(|(_, item)| item.omitted_reason(self).is_none())
+            .filter_map(|(id, item)| {
+                // The impl of a method, to know what `Self` is
+                let impl_ = self.method_impl(id);
+                let cost = fn_cost(item, impl_, &inputs, output.as_ref())?;
+                let path = self
+                    .public_path(id)
+                    .or_else(|| self.item_path(id))?
+                    .join("::");
+                Some((path, item, cost))
+            })
+            .collect::<Vec<_>>();
+        matches.sort_by(|(a_path, _, a_cost), (b_path, _, b_cost)| {
+            a_cost
+                .cmp(b_cost)
+                .then(a_path.len().cmp(&b_path.len()))
+                .then(a_path.cmp(b_path))
+        });
+
+        Ok(matches
+            .into_iter()
+            .map(|(path, item, cost)| Match {
+                id: item.id.clone(),
+                path,
+                kind: item.kind_name(self),
+                score: 100u32.saturating_sub(cost),
+                signature: item.signature(self),
+                summary: item.summary(),
+            })
+            .collect())
+    }
+}
+
+/// Parse a type signature query, e.g. `&str, usize -> String`, into its
+/// inputs and output.
+pub(crate) fn parse_type_signature(
+    query: &str,
+) -> Result<(Vec<String>, Option<String>), String> {
+    // The last top-level arrow, so that `Fn(u8) -> bool` stays whole
+    let mut depth = 0i32;
+    let mut arrow = None;
+    for (i, c) in query.char_indices() {
+        match c {
+            '(' | '<' | '[' => depth += 1,
+            ')' | ']' => depth -= 1,
+            '>' if query[..i].ends_with('-') =>
+                if depth == 0 {
+                    arrow = Some(i - 1);
+                },
+            '>' => depth -= 1,
+            _ => {}
+        }
+    }
+    let (inputs, output) = match arrow {
+        Some(i) => (&query[..i], Some(query[i + 2..].trim().to_string())),
+        None => (query, None),
+    };
+    let mut parser = Parser::new(inputs)?;
+    let mut input_types = Vec::new();
+    while !parser.is_done() {
+        let start = parser.position;
+        parser.parse()?;
+        input_types.push(parser.source(start));
+        if !parser.eat(&Token::Punct(',')) && !parser.is_done() {
+            return Err(format!("Expected `,` in `{inputs}`"));
+        }
+    }
+    Ok((input_types, output.filter(|output| !output.is_empty())))
+}
+
+/// The cost of matching a function to the query, i.e. how loose the match
+/// is, or `None` if it doesn't match.
+fn fn_cost(
+    item: &RustDocItem,
+    impl_: Option<&Impl>,
+    inputs: &[Ty],
+    output: Option<&Ty>,
+) -> Option<u32> {
+    let function = item.inner.as_ref()?.function.as_ref()?;
+    let self_type = impl_.and_then(|impl_| impl_.for_.as_ref()).map(Ty::from);
+    let with_self = |ty: Ty| match &self_type {
+        Some(self_type) => ty.replace_self(self_type),
+        None => ty,
+    };
+    let fn_inputs = function
+        .decl
+        .inputs
+        .iter()
+        .map(|(_, parameter)| with_self(Ty::from(parameter)))
+        .collect::<Vec<_>>();
+    let fn_output = with_self(match &function.decl.output {
+        Some(output) => Ty::from(output),
+        None => Ty::Tuple(Vec::new()),
+    });
+    if inputs.len() > fn_inputs.len() {
+        return None;
+    }
+
+    let mut bounds = HashMap::new();
+    for generics in [function.generics.as_ref()]
+        .into_iter()
+        .chain([impl_.and_then(|impl_| impl_.generics.as_ref())])
+        .flatten()
+    {
+        collect_bounds(generics, &mut bounds);
+    }
+    let unifier = Unifier {
+        bounds: &bounds,
+        fn_bindings: HashMap::new(),
+        query_bindings: HashMap::new(),
+    };
+    let mut used = vec![false; fn_inputs.len()];
+    let cost =
+        unifier.match_inputs(inputs, &fn_inputs, &mut used, |unifier| {
+            match output {
+                Some(output) => unifier.unify(output, &fn_output),
+                None => Some(0),
+            }
+        })?;
+    // Functions which take more than the query are looser matches
+    Some(cost + (fn_inputs.len() - inputs.len()) as u32)
+}
+
+/// The traits which the generic parameters of a function are bound by, e.g.
+/// `AsRef<[u8]>` for `T` of `fn encode<T: AsRef<[u8]>>(data: T)`.
+fn collect_bounds(generics: &Generics, bounds: &mut HashMap<String, Vec<Ty>>) {
+    let traits = |infos: &[TraitBoundInfo]| {
+        infos
+            .iter()
+            .flat_map(|info| &info.trait_bound)
+            .map(|bound| Ty::from(&bound.trait_))
+            .collect::<Vec<_>>()
+    };
+    for param in &generics.params {
+        let Some(name) = &param.name else {
+            continue;
+        };
+        let Some(type_) = param.kind.as_ref().and_then(|k| k.type_.as_ref())
+        else {
+            continue;
+        };
+        let entry = bounds.entry(name.clone()).or_default();
+        entry.extend(traits(&type_.bounds));
+    }
+    for predicate in &generics.where_predicates {
+        if let WherePredicate::Bound { bound_predicate } = predicate {
+            if let ReturnType::Generic { generic } = &bound_predicate.type_ {
+                let entry = bounds.entry(generic.clone()).or_default();
+                entry.extend(traits(&bound_predicate.bounds));
+            }
+        }
+    }
+}
+
+/// The bindings of generic parameters while matching a function.
+#[derive(Clone)]
+struct Unifier<'a> {
+    /// The bounds of the function's generic parameters.
+    bounds: &'a HashMap<String, Vec<Ty>>,
+    /// The query types which the function's generic parameters stand for.
+    fn_bindings: HashMap<String, Ty>,
+    /// The function types which the query's generic parameters stand for.
+    query_bindings: HashMap<String, Ty>,
+}
+
+impl Unifier<'_> {
+    /// The cheapest way to match each query input to a different function
+    /// input, and then whatever `rest` matches with the bindings.
+    fn match_inputs(
+        &self,
+        inputs: &[Ty],
+        fn_inputs: &[Ty],
+        used: &mut [bool],
+        rest: impl Fn(&mut Self) -> Option<u32> + Copy,
+    ) -> Option<u32> {
+        let Some((input, inputs)) = inputs.split_first() else {
+            return rest(&mut self.clone());
+        };
+        let mut best = None;
+        for (i, fn_input) in fn_inputs.iter().enumerate() {
+            if used[i] {
+                continue;
+            }
+            let mut unifier = self.clone();
+            let Some(cost) = unifier.unify(input, fn_input) else {
+                continue;
+            };
+            used[i] = true;
+            let rest_cost = unifier.match_inputs(inputs, fn_inputs, used, rest);
+            used[i] = false;
+            if let Some(rest_cost) = rest_cost {
+                let total = cost + rest_cost;
+                best = Some(best.map_or(total, |best: u32| best.min(total)));
+            }
+        }
+        best
+    }
+
+    /// The cost of matching a query type to a function's type, binding
+    /// generic parameters on the way, or `None` if they don't match.
+    fn unify(&mut self, query: &Ty, ty: &Ty) -> Option<u32> {
+        match (query, ty) {
+            (Ty::Infer, _) => Some(0),
+            (Ty::Generic(name), _) => match self.query_bindings.get(name) {
+                Some(bound) => (bound == ty).then_some(0),
+                None => {
+                    self.query_bindings.insert(name.clone(), ty.clone());
+                    Some(0)
+                }
+            },
+            (_, Ty::Generic(name)) => match self.fn_bindings.get(name) {
+                Some(bound) => (bound == query).then_some(0),
+                None => {
+                    let bounds = self.bounds.get(name).cloned();
+                    let cost =
+                        self.bounds_cost(query, &bounds.unwrap_or_default());
+                    self.fn_bindings.insert(name.clone(), query.clone());
+                    Some(cost)
+                }
+            },
+            (_, Ty::Traits(traits)) => Some(self.bounds_cost(query, traits)),
+            (
+                Ty::Ref {
+                    mutable: query_mutable,
+                    inner: query_inner,
+                },
+                Ty::Ref { mutable, inner },
+            ) => {
+                // A `&mut T` can be passed for a `&T`, but not the other way
+                if *mutable && !query_mutable {
+                    return None;
+                }
+                let cost = self.unify(query_inner, inner)?;
+                Some(cost + u32::from(query_mutable != mutable))
+            }
+            (Ty::Ref { inner, .. }, _) => Some(self.unify(inner, ty)? + 1),
+            (_, Ty::Ref { inner, .. }) => Some(self.unify(query, inner)? + 1),
+            (
+                Ty::Path {
+                    name: query_name,
+                    args: query_args,
+                },
+                Ty::Path { name, args },
+            ) if query_name == name => {
+                let mut cost = 0;
+                for (query_arg, arg) in query_args.iter().zip(args) {
+                    cost += self.unify(query_arg, arg)?;
+                }
+                // e.g. `Result<T>` for `io::Result<T>`
+                Some(cost + u32::from(query_args.len() != args.len()))
+            }
+            (_, Ty::Path { name, args })
+                if WRAPPERS.contains(&name.as_str()) =>
+                Some(self.unify(query, args.first()?)? + 2),
+            (Ty::Slice(query_inner), Ty::Slice(inner))
+            | (Ty::Array(query_inner), Ty::Array(inner)) =>
+                self.unify(query_inner, inner),
+            (Ty::Slice(query_inner), Ty::Array(inner))
+            | (Ty::Array(query_inner), Ty::Slice(inner)) =>
+                Some(self.unify(query_inner, inner)? + 1),
+            (Ty::Tuple(query_types), Ty::Tuple(types))
+                if query_types.len() == types.len() =>
+            {
+                let mut cost = 0;
+                for (query_type, type_) in query_types.iter().zip(types) {
+                    cost += self.unify(query_type, type_)?;
+                }
+                Some(cost)
+            }
+            (Ty::Primitive(query_name), Ty::Primitive(name)) =>
+                (query_name == name).then_some(0),
+            // `str` and `String` are close
+            (Ty::Primitive(primitive), Ty::Path { name, .. })
+            | (Ty::Path { name, .. }, Ty::Primitive(primitive))
+                if primitive == "str" && name == "String" =>
+                Some(1),
+            _ => None,
+        }
+    }
+
+    /// The cost of passing a query type for a generic with the given trait
+    /// bounds: none if a bound converts from it, e.g. `AsRef<[u8]>` from
+    /// `&[u8]`, and otherwise a little, as the bounds aren't checked.
+    fn bounds_cost(&mut self, query: &Ty, bounds: &[Ty]) -> u32 {
+        let query_inner = match query {
+            Ty::Ref { inner, .. } => inner,
+            query => query,
+        };
+        for bound in bounds {
+            let Ty::Path { name, args } = bound else {
+                continue;
+            };
+            let Some(target) = args.first() else {
+                continue;
+            };
+            if !CONVERSION_TRAITS.contains(&name.as_str()) {
+                continue;
+            }
+            let mut unifier = self.clone();
+            if unifier.unify(query_inner, target) == Some(0) {
+                *self = unifier;
+                return 0;
+            }
+        }
+        1
+    }
+}
+
+impl Ty {
+    /// The type with `Self` replaced, e.g. by the type of an impl.
+    fn replace_self(self, self_type: &Ty) -> Ty {
+        let replace = |types: Vec<Ty>| {
+            types
+                .into_iter()
+                .map(|ty| ty.replace_self(self_type))
+                .collect()
+        };
+        match self {
+            Ty::Generic(name) if name == "Self" => self_type.clone(),
+            Ty::Path { name, args } => Ty::Path {
+                name,
+                args: replace(args),
+            },
+            Ty::Ref { mutable, inner } => Ty::Ref {
+                mutable,
+                inner: Box::new(inner.replace_self(self_type)),
+            },
+            Ty::Slice(inner) =>
+                Ty::Slice(Box::new(inner.replace_self(self_type))),
+            Ty::Array(inner) =>
+                Ty::Array(Box::new(inner.replace_self(self_type))),
+            Ty::Tuple(types) => Ty::Tuple(replace(types)),
+            ty => ty,
+        }
+    }
+
+    fn from_args(name: &str, args: Option<&GenericArgs>) -> Ty {
+        let name = name.rsplit("::").next().unwrap_or(name).to_string();
+        let args = match args {
+            Some(GenericArgs::AngleBracketed { angle_bracketed }) =>
+                angle_bracketed
+                    .args
+                    .iter()
+                    .filter_map(|arg| match arg {
+                        GenericArg::Type { type_inner } =>
+                            Some(type_inner.into()),
+                        _ => None,
+                    })
+                    .collect(),
+            Some(GenericArgs::Parenthesized { parenthesized }) => parenthesized
+                .inputs
+                .iter()
+                .map(Ty::from)
+                .chain(parenthesized.output.as_deref().map(Ty::from))
+                .collect(),
+            None => Vec::new(),
+        };
+        Ty::Path { name, args }
+    }
+
+    fn from_traits<'a>(traits: impl IntoIterator<Item = &'a TraitBound>) -> Ty {
+        Ty::Traits(
+            traits
+                .into_iter()
+                .map(|bound| (&bound.trait_).into())
+                .collect(),
+        )
+    }
+}
+
+impl From<&ResolvedPath> for Ty {
+    fn from(path: &ResolvedPath) -> Self {
+        Ty::from_args(&path.name, path.args.as_ref())
+    }
+}
+
+/// A function pointer, e.g. `fn(u8) -> bool`, as a `fn` path with the
+/// inputs and the output as arguments, like `Fn(u8) -> bool`.
+impl From<&FunctionPointer> for Ty {
+    fn from(function_pointer: &FunctionPointer) -> Self {
+        let decl = &function_pointer.decl;
+        Ty::Path {
+            name: "fn".to_string(),
+            args: decl
+                .inputs
+                .iter()
+                .map(|(_, input)| input.into())
+                .chain(decl.output.as_ref().map(Ty::from))
+                .collect(),
+        }
+    }
+}
+
+impl From<&Parameter> for Ty {
+    fn from(parameter: &Parameter) -> Self {
+        match parameter {
+            Parameter::BorrowedRef { borrowed_ref } => Ty::Ref {
+                mutable: borrowed_ref.mutable,
+                inner: Box::new((&*borrowed_ref.type_).into()),
+            },
+            Parameter::Primitive { primitive } =>
+                Ty::Primitive(primitive.clone()),
+            Parameter::Generic { generic } => Ty::Generic(generic.clone()),
+            Parameter::ResolvedPath { resolved_path } =>
+                (&**resolved_path).into(),
+            // e.g. `Self::Item`, which stands for some type
+            Parameter::Qualified { qualified_path } =>
+                Ty::Generic(qualified_path.to_string()),
+            Parameter::Slice { slice } =>
+                Ty::Slice(Box::new((&**slice).into())),
+            Parameter::Array { array } =>
+                Ty::Array(Box::new((&*array.type_).into())),
+            Parameter::RawPointer { raw_pointer } => Ty::Ref {
+                mutable: raw_pointer.mutable,
+                inner: Box::new((&*raw_pointer.type_).into()),
+            },
+            Parameter::ImplTrait { impl_trait } => Ty::from_traits(
+                impl_trait.iter().flat_map(|info| &info.trait_bound),
+            ),
+            Parameter::DynTrait { dyn_trait } =>
+                Ty::from_traits(&dyn_trait.traits),
+            Parameter::Tuple { tuple } =>
+                Ty::Tuple(tuple.iter().map(Ty::from).collect()),
+            Parameter::FunctionPointer { function_pointer } =>
+                (&**function_pointer).into(),
+            Parameter::Unknown(_) => Ty::Infer,
+        }
+    }
+}
+
+impl From<&ReturnType> for Ty {
+    fn from(type_: &ReturnType) -> Self {
+        match type_ {
+            ReturnType::ResolvedPath { resolved_path } =>
+                (&**resolved_path).into(),
+            ReturnType::BorrowedRef { borrowed_ref } => Ty::Ref {
+                mutable: borrowed_ref.mutable,
+                inner: Box::new((&*borrowed_ref.type_).into()),
+            },
+            ReturnType::Primitive { primitive } =>
+                Ty::Primitive(primitive.clone()),
+            ReturnType::Generic { generic } => Ty::Generic(generic.clone()),
+            ReturnType::Qualified { qualified_path } =>
+                Ty::Generic(qualified_path.to_string()),
+            ReturnType::Array { array } =>
+                Ty::Array(Box::new((&*array.type_).into())),
+            ReturnType::Tuple { tuple } =>
+                Ty::Tuple(tuple.iter().map(Ty::from).collect()),
+            ReturnType::Slice { slice } =>
+                Ty::Slice(Box::new((&**slice).into())),
+            ReturnType::RawPointer { raw_pointer } => Ty::Ref {
+                mutable: raw_pointer.mutable,
+                inner: Box::new((&*raw_pointer.type_).into()),
+            },
+            ReturnType::ImplTrait { impl_trait } => Ty::from_traits(
+                impl_trait.iter().flat_map(|info| &info.trait_bound),
+            ),
+            ReturnType::DynTrait { dyn_trait } =>
+                Ty::from_traits(&dyn_trait.traits),
+            ReturnType::FunctionPointer { function_pointer } =>
+                (&**function_pointer).into(),
+            ReturnType::Unknown(_) => Ty::Infer,
+        }
+    }
+}
+
+/// Parse a type of a query, e.g. `Result<Vec<u8>, _>`.
+fn parse_type(source: &str) -> Result<Ty, String> {
+    let mut parser = Parser::new(source)?;
+    let ty = parser.parse()?;
+    if parser.is_done() {
+        Ok(ty)
+    } else {
+        Err(format!("Unexpected tokens after the type in `{source}`"))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Token {
+    Ident(String),
+    Lifetime,
+    Arrow,
+    Punct(char),
+}
+
+/// A recursive descent parser of Rust types.
+struct Parser<'a> {
+    source: &'a str,
+    /// The tokens with their start and end in the source.
+    tokens: Vec<(Token, usize, usize)>,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(source: &'a str) -> Result<Self, String> {
+        let mut tokens = Vec::new();
+        let mut chars = source.char_indices().peekable();
+        while let Some((start, c)) = chars.next() {
+            let token = if c.is_whitespace() {
+                continue;
+            } else if c.is_alphanumeric() || c == '_' || c == '\'' {
+                let mut end = start + c.len_utf8();
+                while let Some(&(i, next)) = chars.peek() {
+                    if !(next.is_alphanumeric() || next == '_') {
+                        break;
+                    }
+                    end = i + next.len_utf8();
+                    chars.next();
+                }
+                if c == '\'' {
+                    Token::Lifetime
+                } else {
+                    Token::Ident(source[start..end].to_string())
+                }
+            } else if c == '-' && chars.peek().map(|&(_, c)| c) == Some('>') {
+                chars.next();
+                Token::Arrow
+            } else if "<>,&()[];*:+=!".contains(c) {
+                Token::Punct(c)
+            } else {
+                return Err(format!("Unexpected `{c}` in `{source}`"));
+            };
+            let end = chars.peek().map_or(source.len(), |&(i, _)| i);
+            tokens.push((token, start, end));
+        }
+        Ok(Self {
+            source,
+            tokens,
+            position: 0,
+        })
+    }
+
+    fn is_done(&self) -> bool {
+        self.position >= self.tokens.len()
+    }
+
+    /// The source from the token at `start` to the current token.
+    fn source(&self, start: usize) -> String {
+        let (_, start, _) = self.tokens[start];
+        let (_, _, end) = self.tokens[self.position - 1];
+        self.source[start..end].trim().to_string()
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(token, _, _)| token)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.peek().cloned();
+        self.position += 1;
+        token
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        let is_next = self.peek() == Some(token);
+        if is_next {
+            self.position += 1;
+        }
+        is_next
+    }
+
+    fn eat_ident(&mut self, ident: &str) -> bool {
+        self.eat(&Token::Ident(ident.to_string()))
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), String> {
+        if self.eat(&Token::Punct(c)) {
+            Ok(())
+        } else {
+            Err(format!("Expected `{c}` in `{}`", self.source))
+        }
+    }
+
+    fn parse(&mut self) -> Result<Ty, String> {
+        let source = self.source;
+        let unexpected = || format!("Expected a type in `{source}`");
+        match self.next().ok_or_else(unexpected)? {
+            Token::Punct('&') => {
+                self.eat(&Token::Lifetime);
+                let mutable = self.eat_ident("mut");
+                let inner = Box::new(self.parse()?);
+                Ok(Ty::Ref { mutable, inner })
+            }
+            Token::Punct('*') => {
+                let mutable = self.eat_ident("mut");
+                if !mutable && !self.eat_ident("const") {
+                    return Err(unexpected());
+                }
+                let inner = Box::new(self.parse()?);
+                Ok(Ty::Ref { mutable, inner })
+            }
+            Token::Punct('[') => {
+                let inner = Box::new(self.parse()?);
+                if self.eat(&Token::Punct(';')) {
+                    // The length, which isn't matched
+                    while !matches!(self.peek(), Some(Token::Punct(']')) | None)
+                    {
+                        self.position += 1;
+                    }
+                    self.expect(']')?;
+                    return Ok(Ty::Array(inner));
+                }
+                self.expect(']')?;
+                Ok(Ty::Slice(inner))
+            }
+            Token::Punct('(') => {
+                let types = self.parse_list(')')?;
+                Ok(Ty::Tuple(types))
+            }
+            Token::Ident(ident) if ident == "impl" || ident == "dyn" => {
+                let mut traits = vec![self.parse()?];
+                while self.eat(&Token::Punct('+')) {
+                    if !self.eat(&Token::Lifetime) {
+                        traits.push(self.parse()?);
+                    }
+                }
+                Ok(Ty::Traits(traits))
+            }
+            Token::Ident(ident) if ident == "_" => Ok(Ty::Infer),
+            Token::Ident(mut name) => {
+                while self.eat(&Token::Punct(':')) {
+                    self.expect(':')?;
+                    match self.next() {
+                        Some(Token::Ident(segment)) => name = segment,
+                        _ => return Err(unexpected()),
+                    }
+                }
+                self.parse_path_args(name)
+            }
+            Token::Punct('!') => Ok(Ty::Path {
+                name: "!".to_string(),
+                args: Vec::new(),
+            }),
+            _ => Err(unexpected()),
+        }
+    }
+
+    /// The rest of a path type after its name, e.g. `<u8>` of `Vec<u8>`.
+    fn parse_path_args(&mut self, name: String) -> Result<Ty, String> {
+        let mut args = Vec::new();
+        if self.eat(&Token::Punct('<')) {
+            while !self.eat(&Token::Punct('>')) {
+                if self.eat(&Token::Lifetime) {
+                } else if matches!(
+                    self.tokens.get(self.position + 1),
+                    Some((Token::Punct('='), _, _))
+                ) {
+                    // An associated type, e.g. `Item = u8`, isn't matched
+                    self.position += 2;
+                    self.parse()?;
+                } else {
+                    args.push(self.parse()?);
+                }
+                if !self.eat(&Token::Punct(',')) {
+                    self.expect('>')?;
+                    break;
+                }
+            }
+        } else if self.eat(&Token::Punct('(')) {
+            // e.g. `Fn(u8) -> bool`
+            args = self.parse_list(')')?;
+            if self.eat(&Token::Arrow) {
+                args.push(self.parse()?);
+            }
+        }
+
+        let is_generic = {
+            let mut chars = name.chars();
+            chars.next().is_some_and(|c| c.is_ascii_uppercase())
+                && chars.all(|c| c.is_ascii_digit())
+        };
+        Ok(if PRIMITIVES.contains(&name.as_str()) && args.is_empty() {
+            Ty::Primitive(name)
+        } else if is_generic && args.is_empty() {
+            Ty::Generic(name)
+        } else {
+            Ty::Path { name, args }
+        })
+    }
+
+    /// Types separated by commas, up to the closing `end`.
+    fn parse_list(&mut self, end: char) -> Result<Vec<Ty>, String> {
+        let mut types = Vec::new();
+        while !self.eat(&Token::Punct(end)) {
+            types.push(self.parse()?);
+            if !self.eat(&Token::Punct(',')) {
+                self.expect(end)?;
+                break;
+            }
+        }
+        Ok(types)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const COMMON_JSON_STR: &str =
+        include_str!("../test-data/common/rustdoc.json");
+    const HEX_JSON_STR: &str = include_str!("../test-data/hex/rustdoc.json");
+
+    fn find(json: &str, inputs: &[&str], output: Option<&str>) -> Vec<String> {
+        let doc = serde_json::from_str::<RustDoc>(json).unwrap();
+        doc.find_fns(inputs, output)
+            .unwrap()
+            .into_iter()
+            .map(|found| found.path)
+            .collect()
+    }
+
+    #[test]
+    fn test_find_fns() {
+        // Exact matches first, then matches through wrappers
+        let found = find(HEX_JSON_STR, &["&str"], Some("Result<Vec<u8>, _>"));
+        assert_eq!(found[0], "hex::decode");
+        let found = find(HEX_JSON_STR, &["&[u8]"], Some("String"));
+        assert_eq!(found[0], "hex::encode");
+        let found = find(HEX_JSON_STR, &["&[u8]"], Some("Vec<u8>"));
+        assert!(found.is_empty(), "{found:?}");
+
+        // `Self` is the type of the impl
+        let found = find(COMMON_JSON_STR, &["i32", "i32"], Some("Point"));
+        assert_eq!(found[0], "common::shapes::Point::new");
+        let found = find(COMMON_JSON_STR, &["&Point"], Some("i32"));
+        assert!(found.contains(&"common::shapes::Point::manhattan".to_string()));
+        let found = find(COMMON_JSON_STR, &["&Point"], Some("String"));
+        assert_eq!(found[0], "common::shapes::Point::draw");
+
+        // Generic queries unify consistently
+        let found = find(COMMON_JSON_STR, &["T", "T"], Some("T"));
+        assert!(!found.contains(&"common::shapes::Point::new".to_string()));
+    }
+
+    #[test]
+    fn test_parse_type() {
+        let vec_u8 = Ty::Path {
+            name: "Vec".to_string(),
+            args: vec![Ty::Primitive("u8".to_string())],
+        };
+        assert_eq!(
+            parse_type("std::result::Result<Vec<u8>, _>").unwrap(),
+            Ty::Path {
+                name: "Result".to_string(),
+                args: vec![vec_u8, Ty::Infer],
+            }
+        );
+        assert_eq!(
+            parse_type("&'a mut [T; 4]").unwrap(),
+            Ty::Ref {
+                mutable: true,
+                inner: Box::new(Ty::Array(Box::new(Ty::Generic(
+                    "T".to_string()
+                )))),
+            }
+        );
+        assert!(parse_type("impl Iterator<Item = u8> + 'static").is_ok());
+        assert!(parse_type("Vec<u8").is_err());
+
+        assert_eq!(
+            parse_type_signature("&str, impl Fn(u8) -> bool -> Vec<u8>")
+                .unwrap(),
+            (
+                vec!["&str".to_string(), "impl Fn(u8) -> bool".to_string()],
+                Some("Vec<u8>".to_string())
+            )
+        );
+    }
+}
@@ -0,0 +1,276 @@
+//! Where each type is used: in the parameters and return types of
+//! functions, in fields, in generic bounds and in impls, to show how the
+//! types of a crate connect.
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt::{self, Write},
+};
+
+use serde::Serialize;
+
+use crate::{conversions::impl_label, Parameter, RustDoc};
+
+/// How many usages of each kind to list for a type, before "and N more".
+const MAX_USAGES_PER_KIND: usize = 10;
+
+/// Where a type appears in an item.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub enum UsageKind {
+    /// In the type of a function's parameter.
+    Parameter,
+    /// In the return type of a function.
+    ReturnType,
+    /// In the type of a field.
+    Field,
+    /// In a generic bound, e.g. `T: Draw`, or a supertrait.
+    Bound,
+    /// In the trait or type of an impl, e.g. `impl From<Point> for [i32;
+    /// 2]`. A type's own impls aren't counted.
+    Impl,
+}
+
+impl UsageKind {
+    /// The heading of a list of usages of this kind.
+    fn heading(self) -> &'static str {
+        match self {
+            Self::Parameter => "Parameters",
+            Self::ReturnType => "Return types",
+            Self::Field => "Fields",
+            Self::Bound => "Bounds",
+            Self::Impl => "Impls",
+        }
+    }
+}
+
+/// An item which uses a type.
+#[derive(Clone, Debug, Serialize)]
+pub struct Usage {
+    pub kind: UsageKind,
+    /// The rustdoc id of the item which uses the type.
+    pub id: String,
+    /// The path of the item, e.g. `common::shapes::Labeled::new`, or the
+    /// impl as written, e.g. `impl AsRef<Point> for Labeled`.
+    pub label: String,
+}
+
+/// The items which use each type, by the id of the type.
+pub(crate) type TypeUsages = BTreeMap<String, Vec<(UsageKind, String)>>;
+
+impl RustDoc {
+    /// The items which use the type or trait with the given id, by kind of
+    /// usage and then by label.
+    pub fn usages(&self, id: &str) -> Vec<Usage> {
+        let usages = self.type_usages.get_or_init(|| self.find_usages());
+        let mut usages = usages
+            .get(id)
+            .into_iter()
+            .flatten()
+            .filter_map(|(kind, user_id)| {
+                let item = self.index.get(user_id)?;
+                if item.omitted_reason(self).is_some() {
+                    return None;
+                }
+                let label = match item.inner.as_ref()?.impl_.as_ref() {
+                    Some(impl_) => impl_label(impl_),
+                    None => self
+                        .public_path(user_id)
+                        .or_else(|| self.item_path(user_id))?
+                        .join("::"),
+                };
+                Some(Usage {
+                    kind: *kind,
+                    id: user_id.clone(),
+                    label,
+                })
+            })
+            .collect::<Vec<_>>();
+        usages.sort_by(|a, b| a.kind.cmp(&b.kind).then(a.label.cmp(&b.label)));
+        usages
+    }
+
+    /// Record where every type appears in the signatures of the crate.
+    fn find_usages(&self) -> TypeUsages {
+        // The members of trait impls are covered by their impls
+        let trait_impl_members = self
+            .index
+            .values()
+            .filter_map(|item| item.inner.as_ref()?.impl_.as_ref())
+            .filter(|impl_| impl_.trait_.is_some())
+            .flat_map(|impl_| &impl_.items)
+            .collect::<HashSet<_>>();
+
+        let mut usages = TypeUsages::new();
+        for (id, item) in &self.index {
+            let Some(inner) = &item.inner else {
+                continue;
+            };
+            if !self.is_listed(id, item) || trait_impl_members.contains(id) {
+                continue;
+            }
+
+            let mut paths = Vec::new();
+            if let Some(function) = &inner.function {
+                for (_, param) in &function.decl.inputs {
+                    paths.extend(
+                        param
+                            .type_paths()
+                            .into_iter()
+                            .map(|path| (UsageKind::Parameter, path.id)),
+                    );
+                }
+                if let Some(output) = &function.decl.output {
+                    paths.extend(
+                        output
+                            .type_paths()
+                            .into_iter()
+                            .map(|path| (UsageKind::ReturnType, path.id)),
+                    );
+                }
+            } else if let Some(type_) = &inner.struct_field {
+                paths.extend(
+                    type_
+                        .type_paths()
+                        .into_iter()
+                        .map(|path| (UsageKind::Field, path.id)),
+                );
+            } else if let Some(impl_) = &inner.impl_ {
+                let Some(trait_) = &impl_.trait_ else {
+                    continue;
+                };
+                if impl_.blanket_impl.is_some() || impl_.synthetic {
+                    continue;
+                }
+                // Leaving out the trait, and the type the impl is for
+                let for_id = match &impl_.for_ {
+                    Some(Parameter::ResolvedPath { resolved_path }) =>
+                        resolved_path.id.as_deref(),
+                    _ => None,
+                };
+                paths.extend(
+                    impl_
+                        .type_paths()
+                        .into_iter()
+                        .filter(|path| {
+                            Some(path.id) != trait_.id.as_deref()
+                                && Some(path.id) != for_id
+                        })
+                        .map(|path| (UsageKind::Impl, path.id)),
+                );
+            }
+            paths.extend(
+                item.bound_paths()
+                    .into_iter()
+                    .map(|path| (UsageKind::Bound, path.id)),
+            );
+
+            for (kind, type_id) in paths {
+                if type_id == id {
+                    continue;
+                }
+                let users = usages.entry(type_id.to_string()).or_default();
+                let usage = (kind, id.clone());
+                if !users.contains(&usage) {
+                    users.push(usage);
+                }
+            }
+        }
+        usages
+    }
+
+    /// Write where a type is used, as a list with a line per kind of usage.
+    pub(crate) fn write_usages(
+        &self,
+        out: &mut String,
+        id: &str,
+    ) -> fmt::Result {
+        let usages = self.usages(id);
+        if usages.is_empty() {
+            return Ok(());
+        }
+
+        writeln!(out, "**Used in:**")?;
+        let mut by_kind = BTreeMap::<UsageKind, Vec<String>>::new();
+        for usage in usages {
+            by_kind
+                .entry(usage.kind)
+                .or_default()
+                .push(format!("`{}`", usage.label));
+        }
+        for (kind, mut labels) in by_kind {
+            let more = labels.len().saturating_sub(MAX_USAGES_PER_KIND);
+            labels.truncate(MAX_USAGES_PER_KIND);
+            write!(out, "- {}: {}", kind.heading(), labels.join(", "))?;
+            match more {
+                0 => writeln!(out)?,
+                more => writeln!(out, ", and {more} more")?,
+            }
+        }
+        writeln!(out)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const COMMON_JSON_STR: &str =
+        include_str!("../test-data/common/rustdoc.json");
+
+    fn usages(doc: &RustDoc, name: &str) -> Vec<(UsageKind, String)> {
+        let (id, _) = doc
+            .index
+            .iter()
+            .find(|(_, item)| {
+                item.name.as_deref() == Some(name)
+                    && item.inner.as_ref().is_some_and(|inner| {
+                        inner.struct_.is_some() || inner.trait_.is_some()
+                    })
+            })
+            .unwrap();
+        doc.usages(id)
+            .into_iter()
+            .map(|usage| (usage.kind, usage.label))
+            .collect()
+    }
+
+    #[test]
+    fn test_usages() {
+        let doc = serde_json::from_str::<RustDoc>(COMMON_JSON_STR).unwrap();
+        let point = usages(&doc, "Point");
+        assert!(point.contains(&(
+            UsageKind::Parameter,
+            "common::shapes::Labeled::new".into()
+        )));
+        assert!(point.contains(&(
+            UsageKind::Field,
+            "common::shapes::Labeled::point".into()
+        )));
+        // Including the fields of enum variants
+        assert!(point.contains(&(
+            UsageKind::Field,
+            "common::shapes::Shape::Circle::center".into()
+        )));
+        assert!(point.contains(&(
+            UsageKind::Impl,
+            "impl AsRef<Point> for Labeled".into()
+        )));
+        // The type's own impls aren't usages
+        assert!(!point
+            .iter()
+            .any(|(_, label)| label == "impl Draw for Point"));
+        assert!(!point.iter().any(|(_, label)| label.contains("from_str")));
+
+        let draw = usages(&doc, "Draw");
+        assert_eq!(draw, [(UsageKind::Bound, "common::draw_all".into())]);
+    }
+
+    #[test]
+    fn test_write_usages() {
+        let doc = serde_json::from_str::<RustDoc>(COMMON_JSON_STR).unwrap();
+        let markdown = doc.to_markdown();
+        assert!(
+            markdown.contains("**Used in:**\n- Bounds: `common::draw_all`\n")
+        );
+    }
+}
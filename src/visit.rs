@@ -0,0 +1,304 @@
+//! Walking the types in signatures, to find the items they refer to.
+
+use crate::{
+    BindingKind, FunctionDecl, GenericArg, GenericArgs, Generics, Impl,
+    Parameter, QualifiedPath, ResolvedPath, ReturnType, RustDocItem,
+    TraitBound, TraitBoundInfo, WherePredicate,
+};
+
+/// A path in a type which refers to an item, e.g. `Vec` in `Vec<u8>`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct TypePath<'a> {
+    /// The path as written, e.g. `Vec` or `fmt::Formatter`.
+    pub(crate) name: &'a str,
+    /// The id of the item the path refers to.
+    pub(crate) id: &'a str,
+}
+
+impl RustDocItem {
+    /// The paths in the signature of an item, not counting its members, e.g.
+    /// the fields of a struct: its generics, and the types of a function,
+    /// field, impl, associated type or associated constant.
+    pub(crate) fn type_paths(&self) -> Vec<TypePath<'_>> {
+        let mut paths = Vec::new();
+        let Some(inner) = &self.inner else {
+            return paths;
+        };
+
+        let generics = if let Some(function) = &inner.function {
+            paths.extend(function.decl.type_paths());
+            function.generics.as_ref()
+        } else if let Some(impl_) = &inner.impl_ {
+            paths.extend(impl_.type_paths());
+            impl_.generics.as_ref()
+        } else if let Some(struct_) = &inner.struct_ {
+            struct_.generics.as_ref()
+        } else if let Some(enum_) = &inner.enum_ {
+            enum_.generics.as_ref()
+        } else if let Some(union_) = &inner.union_ {
+            union_.generics.as_ref()
+        } else if let Some(trait_) = &inner.trait_ {
+            collect_bound_paths(&trait_.bounds, &mut paths);
+            trait_.generics.as_ref()
+        } else if let Some(assoc_type) = &inner.assoc_type {
+            collect_bound_paths(&assoc_type.bounds, &mut paths);
+            if let Some(default) = &assoc_type.default {
+                default.collect_paths(&mut paths);
+            }
+            assoc_type.generics.as_ref()
+        } else if let Some(assoc_const) = &inner.assoc_const {
+            assoc_const.type_.collect_paths(&mut paths);
+            None
+        } else if let Some(type_) = &inner.struct_field {
+            type_.collect_paths(&mut paths);
+            None
+        } else if let Some(static_) = &inner.static_ {
+            static_.type_.collect_paths(&mut paths);
+            None
+        } else {
+            None
+        };
+        if let Some(generics) = generics {
+            generics.collect_paths(&mut paths);
+        }
+        paths
+    }
+
+    /// The paths in the bounds of an item: in its generics, and the
+    /// supertraits of a trait.
+    pub(crate) fn bound_paths(&self) -> Vec<TypePath<'_>> {
+        let mut paths = Vec::new();
+        let Some(inner) = &self.inner else {
+            return paths;
+        };
+        if let Some(trait_) = &inner.trait_ {
+            collect_bound_paths(&trait_.bounds, &mut paths);
+        }
+        let generics = match (
+            &inner.function,
+            &inner.impl_,
+            &inner.struct_,
+            &inner.enum_,
+            &inner.trait_,
+        ) {
+            (Some(function), ..) => function.generics.as_ref(),
+            (_, Some(impl_), ..) => impl_.generics.as_ref(),
+            (_, _, Some(struct_), ..) => struct_.generics.as_ref(),
+            (_, _, _, Some(enum_), _) => enum_.generics.as_ref(),
+            (.., Some(trait_)) => trait_.generics.as_ref(),
+            _ => inner.union_.as_ref().and_then(|u| u.generics.as_ref()),
+        };
+        if let Some(generics) = generics {
+            generics.collect_paths(&mut paths);
+        }
+        paths
+    }
+}
+
+impl FunctionDecl {
+    /// The paths in the types of the inputs and output, in order.
+    pub(crate) fn type_paths(&self) -> Vec<TypePath<'_>> {
+        let mut paths = Vec::new();
+        for (_, param) in &self.inputs {
+            param.collect_paths(&mut paths);
+        }
+        if let Some(output) = &self.output {
+            output.collect_paths(&mut paths);
+        }
+        paths
+    }
+}
+
+impl Impl {
+    /// The paths in the trait and the type of an impl block.
+    pub(crate) fn type_paths(&self) -> Vec<TypePath<'_>> {
+        let mut paths = Vec::new();
+        if let Some(trait_) = &self.trait_ {
+            if let Some(id) = &trait_.id {
+                paths.push(TypePath {
+                    name: &trait_.name,
+                    id,
+                });
+            }
+            if let Some(args) = &trait_.args {
+                args.collect_paths(&mut paths);
+            }
+        }
+        if let Some(for_) = &self.for_ {
+            for_.collect_paths(&mut paths);
+        }
+        paths
+    }
+}
+
+impl ReturnType {
+    /// The paths in a type, e.g. the type of a struct field.
+    pub(crate) fn type_paths(&self) -> Vec<TypePath<'_>> {
+        let mut paths = Vec::new();
+        self.collect_paths(&mut paths);
+        paths
+    }
+}
+
+impl Parameter {
+    /// The paths in a type, e.g. the type of a function input.
+    pub(crate) fn type_paths(&self) -> Vec<TypePath<'_>> {
+        let mut paths = Vec::new();
+        self.collect_paths(&mut paths);
+        paths
+    }
+
+    fn collect_paths<'a>(&'a self, paths: &mut Vec<TypePath<'a>>) {
+        match self {
+            Self::BorrowedRef { borrowed_ref } =>
+                borrowed_ref.type_.collect_paths(paths),
+            Self::ResolvedPath { resolved_path } =>
+                resolved_path.collect_paths(paths),
+            Self::Qualified { qualified_path } =>
+                qualified_path.collect_paths(paths),
+            Self::Slice { slice } => slice.collect_paths(paths),
+            Self::Array { array } => array.type_.collect_paths(paths),
+            Self::RawPointer { raw_pointer } =>
+                raw_pointer.type_.collect_paths(paths),
+            Self::ImplTrait { impl_trait } =>
+                collect_bound_paths(impl_trait, paths),
+            Self::DynTrait { dyn_trait } =>
+                for bound in &dyn_trait.traits {
+                    bound.collect_paths(paths);
+                },
+            Self::Tuple { tuple } =>
+                for type_ in tuple {
+                    type_.collect_paths(paths);
+                },
+            Self::FunctionPointer { function_pointer } =>
+                paths.extend(function_pointer.decl.type_paths()),
+            Self::Primitive { .. }
+            | Self::Generic { .. }
+            | Self::Unknown(_) => {}
+        }
+    }
+}
+
+impl Generics {
+    fn collect_paths<'a>(&'a self, paths: &mut Vec<TypePath<'a>>) {
+        for param in &self.params {
+            let Some(kind) = &param.kind else {
+                continue;
+            };
+            if let Some(type_) = &kind.type_ {
+                collect_bound_paths(&type_.bounds, paths);
+                if let Some(default) = &type_.default {
+                    default.collect_paths(paths);
+                }
+            }
+            if let Some(const_) = &kind.const_ {
+                const_.type_.collect_paths(paths);
+            }
+        }
+        for predicate in &self.where_predicates {
+            if let WherePredicate::Bound { bound_predicate } = predicate {
+                bound_predicate.type_.collect_paths(paths);
+                collect_bound_paths(&bound_predicate.bounds, paths);
+            }
+        }
+    }
+}
+
+fn collect_bound_paths<'a>(
+    bounds: &'a [TraitBoundInfo],
+    paths: &mut Vec<TypePath<'a>>,
+) {
+    for bound in bounds.iter().flat_map(|bound| &bound.trait_bound) {
+        bound.collect_paths(paths);
+    }
+}
+
+impl ReturnType {
+    fn collect_paths<'a>(&'a self, paths: &mut Vec<TypePath<'a>>) {
+        match self {
+            Self::ResolvedPath { resolved_path } =>
+                resolved_path.collect_paths(paths),
+            Self::BorrowedRef { borrowed_ref } =>
+                borrowed_ref.type_.collect_paths(paths),
+            Self::Qualified { qualified_path } =>
+                qualified_path.collect_paths(paths),
+            Self::Array { array } => array.type_.collect_paths(paths),
+            Self::Tuple { tuple } =>
+                for type_ in tuple {
+                    type_.collect_paths(paths);
+                },
+            Self::Slice { slice } => slice.collect_paths(paths),
+            Self::RawPointer { raw_pointer } =>
+                raw_pointer.type_.collect_paths(paths),
+            Self::ImplTrait { impl_trait } =>
+                collect_bound_paths(impl_trait, paths),
+            Self::DynTrait { dyn_trait } =>
+                for bound in &dyn_trait.traits {
+                    bound.collect_paths(paths);
+                },
+            Self::FunctionPointer { function_pointer } =>
+                paths.extend(function_pointer.decl.type_paths()),
+            Self::Primitive { .. }
+            | Self::Generic { .. }
+            | Self::Unknown(_) => {}
+        }
+    }
+}
+
+impl ResolvedPath {
+    fn collect_paths<'a>(&'a self, paths: &mut Vec<TypePath<'a>>) {
+        if let Some(id) = &self.id {
+            paths.push(TypePath {
+                name: &self.name,
+                id,
+            });
+        }
+        if let Some(args) = &self.args {
+            args.collect_paths(paths);
+        }
+    }
+}
+
+impl QualifiedPath {
+    fn collect_paths<'a>(&'a self, paths: &mut Vec<TypePath<'a>>) {
+        self.self_type.collect_paths(paths);
+        if let Some(trait_) = &self.trait_ {
+            trait_.collect_paths(paths);
+        }
+    }
+}
+
+impl TraitBound {
+    fn collect_paths<'a>(&'a self, paths: &mut Vec<TypePath<'a>>) {
+        self.trait_.collect_paths(paths);
+    }
+}
+
+impl GenericArgs {
+    fn collect_paths<'a>(&'a self, paths: &mut Vec<TypePath<'a>>) {
+        match self {
+            Self::AngleBracketed { angle_bracketed } => {
+                for arg in &angle_bracketed.args {
+                    if let GenericArg::Type { type_inner } = arg {
+                        type_inner.collect_paths(paths);
+                    }
+                }
+                for binding in &angle_bracketed.bindings {
+                    if let Some(args) = &binding.args {
+                        args.collect_paths(paths);
+                    }
+                    let BindingKind::Equality { equality } = &binding.binding;
+                    equality.type_.collect_paths(paths);
+                }
+            }
+            Self::Parenthesized { parenthesized } => {
+                for input in &parenthesized.inputs {
+                    input.collect_paths(paths);
+                }
+                if let Some(output) = &parenthesized.output {
+                    output.collect_paths(paths);
+                }
+            }
+        }
+    }
+}
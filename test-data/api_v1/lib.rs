@@ -0,0 +1,80 @@
+//! Version 1 of an API, used with `api_v2` as a test fixture for API diffs.
+
+/// Greets someone by name.
+pub fn greet(name: &str) -> String {
+    format!("Hello, {name}!")
+}
+
+/// Greets the world.
+#[deprecated(since = "1.1.0", note = "use `greet` instead")]
+pub fn hello() -> String {
+    greet("world")
+}
+
+/// Adds two numbers.
+pub fn add(a: i32, b: i32) -> i32 {
+    a + b
+}
+
+/// A primary color.
+pub enum Color {
+    Red,
+    Green,
+}
+
+/// The kind of an error.
+#[non_exhaustive]
+pub enum ErrorKind {
+    NotFound,
+    Invalid,
+}
+
+/// A point with public coordinates.
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+/// Options, with some private state.
+pub struct Options {
+    pub verbose: bool,
+    level: u8,
+}
+
+impl Options {
+    /// The log level.
+    pub fn level(&self) -> u8 {
+        self.level
+    }
+}
+
+/// A shape with an area.
+pub trait Shape {
+    /// The area of the shape.
+    fn area(&self) -> f64;
+}
+
+/// Something with a name.
+pub trait Named {
+    /// The name.
+    fn name(&self) -> String;
+}
+
+/// A token which can be cloned.
+#[derive(Clone)]
+pub struct Token;
+
+/// An input event.
+pub enum Event {
+    /// A key press.
+    Key { code: u32 },
+    /// A mouse click.
+    #[non_exhaustive]
+    Click { x: i32 },
+}
+
+/// A conversion to a number.
+pub trait Convert {
+    /// Converts the value.
+    fn convert(&self) -> i64;
+}
@@ -0,0 +1,110 @@
+//! Version 2 of an API, used with `api_v1` as a test fixture for API diffs.
+
+/// Greets someone by name, optionally with excitement.
+pub fn greet(name: &str, excited: bool) -> String {
+    let punctuation = if excited { "!" } else { "." };
+    format!("Hello, {name}{punctuation}")
+}
+
+/// Adds two integers.
+pub fn add(a: i32, b: i32) -> i32 {
+    a + b
+}
+
+/// Subtracts `b` from `a`.
+pub fn subtract(a: i32, b: i32) -> i32 {
+    a - b
+}
+
+/// A primary color.
+pub enum Color {
+    Red,
+    Green,
+    Blue,
+}
+
+/// The kind of an error.
+#[non_exhaustive]
+pub enum ErrorKind {
+    NotFound,
+    Invalid,
+    Timeout,
+}
+
+/// A point with public coordinates, and a private cache.
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+    norm: Option<f64>,
+}
+
+/// Options, with some private state.
+pub struct Options {
+    pub verbose: bool,
+    level: u8,
+    cache: bool,
+}
+
+impl Options {
+    /// The log level.
+    pub fn level(&self) -> u8 {
+        if self.cache {
+            self.level
+        } else {
+            0
+        }
+    }
+}
+
+/// A shape with an area.
+pub trait Shape {
+    /// The area of the shape.
+    fn area(&self) -> f64;
+
+    /// The perimeter of the shape, if known.
+    fn perimeter(&self) -> Option<f64> {
+        None
+    }
+}
+
+/// Something with a name.
+pub trait Named {
+    /// The name.
+    fn name(&self) -> String;
+
+    /// A unique id.
+    fn id(&self) -> u64;
+}
+
+/// A token which can no longer be cloned.
+pub struct Token;
+
+/// The size of a shape.
+pub struct Size {
+    pub width: u32,
+    pub height: u32,
+}
+
+/// An input event.
+pub enum Event {
+    /// A key press, with whether shift was held.
+    Key { code: u32, shift: bool },
+    /// A mouse click.
+    #[non_exhaustive]
+    Click { x: i32, y: i32 },
+}
+
+/// A conversion to a number.
+pub trait Convert {
+    /// The type of the converted number.
+    type Output;
+
+    /// The largest value a conversion returns.
+    const MAX: i64;
+
+    /// The base of the number, 10 unless overridden.
+    const BASE: u32 = 10;
+
+    /// Converts the value.
+    fn convert(&self) -> i64;
+}
@@ -0,0 +1,316 @@
+//! Common API patterns, used as a test fixture for `ai-rustdoc`.
+//!
+//! ```
+//! let greeting = common::greet("world");
+//! assert_eq!(greeting, "Hello, world!");
+//! ```
+
+use std::fmt;
+
+pub mod shapes {
+    //! Simple geometry types.
+
+    /// A point on a 2D integer grid.
+    ///
+    /// ```
+    /// # use common::shapes::Point;
+    /// let p = Point::new(1, 2);
+    /// assert_eq!(p.x + p.y, 3);
+    /// ```
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+    pub struct Point {
+        /// The horizontal coordinate.
+        pub x: i32,
+        /// The vertical coordinate.
+        pub y: i32,
+    }
+
+    impl Point {
+        /// Creates a new point.
+        pub fn new(x: i32, y: i32) -> Self {
+            Self { x, y }
+        }
+
+        /// Returns the manhattan distance from the origin.
+        ///
+        /// ```rust
+        /// # fn main() {
+        /// let p = common::shapes::Point::new(-3, 4);
+        /// assert_eq!(p.manhattan(), 7);
+        /// # }
+        /// ```
+        pub fn manhattan(&self) -> i32 {
+            self.x.abs() + self.y.abs()
+        }
+    }
+
+    impl From<[i32; 2]> for Point {
+        fn from(xy: [i32; 2]) -> Self {
+            Self::new(xy[0], xy[1])
+        }
+    }
+
+    impl From<Point> for [i32; 2] {
+        fn from(point: Point) -> Self {
+            [point.x, point.y]
+        }
+    }
+
+    /// Parses a point from `x,y`.
+    impl std::str::FromStr for Point {
+        type Err = std::num::ParseIntError;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            let (x, y) = s.split_once(',').unwrap_or((s, ""));
+            Ok(Self::new(x.trim().parse()?, y.trim().parse()?))
+        }
+    }
+
+    /// A point with a label, which derefs to its point.
+    pub struct Labeled {
+        /// The label shown next to the point.
+        pub label: String,
+        point: Point,
+    }
+
+    impl Labeled {
+        /// Labels a point.
+        pub fn new(label: &str, point: Point) -> Self {
+            Self {
+                label: label.to_string(),
+                point,
+            }
+        }
+    }
+
+    impl AsRef<Point> for Labeled {
+        fn as_ref(&self) -> &Point {
+            &self.point
+        }
+    }
+
+    impl std::ops::Deref for Labeled {
+        type Target = Point;
+
+        fn deref(&self) -> &Point {
+            &self.point
+        }
+    }
+
+    /// Creates a [`Point`] from its coordinates, or the origin.
+    ///
+    /// ```
+    /// let origin = common::pt!();
+    /// let p = common::pt!(1, 2);
+    /// assert_eq!(p.manhattan(), 3);
+    /// ```
+    #[macro_export]
+    macro_rules! pt {
+        () => {
+            $crate::shapes::Point::default()
+        };
+        ($x:expr, $y:expr) => {
+            $crate::shapes::Point::new($x, $y)
+        };
+    }
+
+    /// A shape on the grid.
+    pub enum Shape {
+        /// A circle around a center point.
+        Circle { center: Point, radius: u32 },
+        /// An axis-aligned rectangle between two corners.
+        Rect(Point, Point),
+        /// The empty shape.
+        Empty,
+    }
+}
+
+/// Greets someone by name. Prefer this over the deprecated [`hello`].
+#[must_use]
+pub fn greet(name: &str) -> String {
+    let _ = private_helper();
+    format!("Hello, {name}!")
+}
+
+/// Greets the world.
+#[deprecated(since = "0.2.0", note = "use `greet` instead")]
+pub fn hello() -> String {
+    greet("world")
+}
+
+/// Implementation detail of the `log!` macro.
+#[doc(hidden)]
+pub fn __log_impl(message: &str) {
+    let _ = message;
+}
+
+/// Logs a message at the given level.
+///
+/// ```
+/// common::log!(common::Level::Info, "{} points", 2);
+/// ```
+#[macro_export]
+macro_rules! log {
+    ($level:expr, $($arg:tt)+) => {{
+        let _ = $level;
+        $crate::__log_impl(&format!($($arg)+))
+    }};
+}
+
+/// Doubles an expression, for use within the crate only.
+macro_rules! double {
+    ($e:expr) => {
+        $e * 2
+    };
+}
+
+mod imp {
+    /// A widget, defined in a private module and re-exported at the root.
+    pub struct Widget {
+        /// The widget's label.
+        pub label: String,
+    }
+
+    impl Widget {
+        /// Creates a widget with the given label.
+        pub fn new(label: &str) -> Self {
+            Self {
+                label: label.to_string(),
+            }
+        }
+    }
+
+    /// Public, but unreachable: nothing re-exports it.
+    pub fn unreachable_helper() {}
+}
+
+pub use imp::Widget;
+
+/// Re-exports of the most commonly used items.
+pub mod prelude {
+    pub use std::fmt::Display;
+
+    pub use crate::{shapes::*, Draw, Level::*};
+}
+
+/// A crate-private helper.
+fn private_helper() -> u32 {
+    double!(21)
+}
+
+/// A raw handle used by the `log!` macro.
+#[doc(hidden)]
+pub struct RawHandle;
+
+/// Something which can be drawn as text.
+pub trait Draw {
+    /// Draws the value.
+    fn draw(&self) -> String;
+
+    /// Draws the value with extra debugging info.
+    #[deprecated(since = "0.3.0", note = "use `draw` instead")]
+    fn draw_debug(&self) -> String {
+        self.draw()
+    }
+}
+
+impl Draw for shapes::Point {
+    fn draw(&self) -> String {
+        format!("({}, {})", self.x, self.y)
+    }
+}
+
+impl Draw for RawHandle {
+    fn draw(&self) -> String {
+        String::from("<raw>")
+    }
+}
+
+/// Draws every item, one per line.
+pub fn draw_all<T: Draw>(items: &[T]) -> String {
+    items.iter().map(Draw::draw).collect::<Vec<_>>().join("\n")
+}
+
+/// The log level of a message.
+#[repr(u8)]
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Error = 1,
+    Warn = 2,
+    Info = 3,
+}
+
+/// Application configuration.
+///
+/// ```no_run
+/// let config = common::Config::load("app.toml")?;
+/// println!("{}", config.name());
+/// # Ok::<(), common::ConfigError>(())
+/// ```
+///
+/// The file format is a list of `key = value` lines:
+///
+/// ```text
+/// name = demo
+/// ```
+///
+/// ```ignore
+/// ## This attribute-looking line is escaped and kept.
+/// let config = load_somehow();
+/// ```
+pub struct Config {
+    name: String,
+}
+
+impl Config {
+    /// Loads the configuration at `path`.
+    pub fn load(path: &str) -> Result<Self, ConfigError> {
+        let _ = path;
+        Ok(Self {
+            name: String::from("demo"),
+        })
+    }
+
+    /// Parses a configuration from `key = value` lines.
+    pub fn parse(source: &str) -> ConfigResult<Self> {
+        match source.split_once('=') {
+            Some((_, name)) => Ok(Self {
+                name: name.trim().to_string(),
+            }),
+            None => Err(ConfigError::Parse { line: 1 }),
+        }
+    }
+
+    /// The configured application name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// Errors which can occur while loading a [`Config`].
+#[derive(Debug)]
+pub enum ConfigError {
+    /// The config file was not found.
+    NotFound,
+    /// A line could not be parsed.
+    #[non_exhaustive]
+    Parse {
+        /// The number of the line, from 1.
+        line: usize,
+    },
+}
+
+/// The result of loading a [`Config`].
+pub type ConfigResult<T> = Result<T, ConfigError>;
+
+impl std::error::Error for ConfigError {}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound => write!(f, "config file not found"),
+            Self::Parse { line } => write!(f, "parse error on line {line}"),
+        }
+    }
+}
@@ -0,0 +1,10 @@
+//! A thin facade over `common`, used as a test fixture for re-exports of
+//! items from other crates.
+
+pub use common::greet;
+pub use common::shapes::*;
+
+/// Draws a point as text, using [`common::Draw`].
+pub fn draw(point: Point) -> String {
+    common::Draw::draw(&point)
+}
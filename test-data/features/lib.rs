@@ -0,0 +1,51 @@
+//! Items behind Cargo features, used as a test fixture for `ai-rustdoc`.
+
+#![cfg_attr(docsrs, feature(doc_cfg))]
+#![allow(unexpected_cfgs)]
+
+/// Opens a connection, with any features.
+pub fn connect(address: &str) -> Connection {
+    let _ = address;
+    Connection
+}
+
+/// A connection to a server.
+pub struct Connection;
+
+impl Connection {
+    /// Sends the contents of a file.
+    #[cfg(feature = "fs")]
+    pub fn send_file(&self, path: &str) {
+        let _ = path;
+    }
+}
+
+/// Filesystem helpers.
+#[cfg(feature = "fs")]
+#[cfg_attr(docsrs, doc(cfg(feature = "fs")))]
+pub mod fs {
+    /// Reads a file to a string.
+    pub fn read(path: &str) -> String {
+        path.to_string()
+    }
+}
+
+/// A connection over TLS, with either TLS backend.
+#[cfg(all(feature = "tls", any(feature = "rustls", feature = "native-tls")))]
+pub struct TlsConnection;
+
+/// Watches a directory for changes.
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+pub fn watch(path: &str) {
+    let _ = path;
+}
+
+/// Reads a file without blocking, using io_uring.
+#[cfg(all(feature = "fs", target_os = "linux"))]
+pub fn read_uring(path: &str) -> String {
+    path.to_string()
+}
+
+/// A fallback for when the `fs` feature is disabled.
+#[cfg(not(feature = "fs"))]
+pub fn no_fs() {}
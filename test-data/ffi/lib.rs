@@ -0,0 +1,72 @@
+//! Bindings to a small C library, used as a test fixture for FFI items:
+//! `repr` types, unions, `extern` blocks and statics.
+
+use std::os::raw::{c_char, c_int};
+
+/// A point with the layout of a C struct.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct CPoint {
+    pub x: c_int,
+    pub y: c_int,
+}
+
+/// The callbacks the C library calls into.
+#[repr(C)]
+pub struct Callbacks {
+    /// Called with the code of each error.
+    pub on_error: extern "C" fn(code: c_int),
+    /// Called to log a message, if set.
+    pub log: Option<unsafe extern "C" fn(format: *const c_char, ...)>,
+}
+
+/// A value which is either an integer or a float.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub union Number {
+    /// The value as an integer.
+    pub int: c_int,
+    /// The value as a float.
+    pub float: f32,
+}
+
+/// How to open a file, as the C library's flags.
+#[repr(u8)]
+pub enum Mode {
+    Read = 1,
+    Write = 2,
+}
+
+/// The number of calls into the library so far.
+pub static mut CALLS: u32 = 0;
+
+/// The version of the bindings.
+pub static VERSION: &str = "1.0";
+
+extern "C" {
+    /// Returns the absolute value of `x`.
+    pub fn abs(x: c_int) -> c_int;
+
+    /// Writes `point` as text to `out`, returning the length written.
+    pub fn format_point(point: *const CPoint, out: *mut c_char, len: usize) -> c_int;
+
+    /// The code of the last error.
+    pub static last_error: c_int;
+}
+
+/// Adds two numbers, for calling from C.
+#[no_mangle]
+pub extern "C" fn add(a: c_int, b: c_int) -> c_int {
+    a + b
+}
+
+/// Bindings to the Windows API, which uses the `system` ABI.
+pub mod windows {
+    extern "system" {
+        /// Returns the code of the calling thread's last error.
+        pub fn get_last_error() -> u32;
+
+        /// The version of the loaded API.
+        pub static api_version: u32;
+    }
+}
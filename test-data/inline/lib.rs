@@ -0,0 +1,84 @@
+//! A byte search crate which defines its items in private modules and
+//! re-exports them, used as a test fixture for stubs of docs generated
+//! without `--document-private-items`.
+
+mod searcher {
+    use core::iter::FusedIterator;
+
+    /// Finds the first occurrence of a byte in a haystack.
+    pub fn find(haystack: &[u8], needle: u8) -> Option<usize> {
+        haystack.iter().position(|&byte| byte == needle)
+    }
+
+    /// An iterator over the positions of a byte in a haystack.
+    #[derive(Clone, Debug)]
+    pub struct Matches<'h> {
+        haystack: &'h [u8],
+        needle: u8,
+        position: usize,
+    }
+
+    impl<'h> Matches<'h> {
+        /// Creates an iterator over the positions of `needle`.
+        pub fn new(haystack: &'h [u8], needle: u8) -> Matches<'h> {
+            Matches { haystack, needle, position: 0 }
+        }
+    }
+
+    impl<'h> Iterator for Matches<'h> {
+        type Item = usize;
+
+        fn next(&mut self) -> Option<usize> {
+            let found = find(&self.haystack[self.position..], self.needle)?;
+            let position = self.position + found;
+            self.position = position + 1;
+            Some(position)
+        }
+    }
+
+    impl<'h> FusedIterator for Matches<'h> {}
+
+    /// Whether to use a heuristic to find candidates quickly.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum PrefilterConfig {
+        /// Never use a heuristic.
+        Never,
+        /// Use a heuristic when it is likely to help.
+        Auto,
+    }
+
+    impl Default for PrefilterConfig {
+        fn default() -> PrefilterConfig {
+            PrefilterConfig::Auto
+        }
+    }
+}
+
+pub use searcher::{find, Matches};
+
+/// Finds the first occurrence of either of two bytes in a haystack.
+pub fn find_either(haystack: &[u8], needles: (u8, u8)) -> Option<usize> {
+    haystack
+        .iter()
+        .position(|&byte| byte == needles.0 || byte == needles.1)
+}
+
+/// Searching with options.
+pub mod options {
+    pub use crate::searcher::PrefilterConfig as Prefilter;
+
+    /// Options for a search.
+    #[derive(Clone, Copy, Debug, Default)]
+    pub struct Options {
+        /// The prefilter to search with.
+        pub prefilter: Prefilter,
+    }
+
+    /// Reports each match of a search to a callback.
+    #[derive(Clone, Copy, Debug)]
+    pub struct Reporter {
+        /// Called with the position of each match, returning whether to
+        /// keep searching.
+        pub on_match: fn(usize) -> bool,
+    }
+}
@@ -0,0 +1,25 @@
+//! Procedural macros, used as a test fixture for `ai-rustdoc`.
+
+use proc_macro::TokenStream;
+
+/// Derives a builder for a struct.
+///
+/// Fields can be renamed with `#[builder(rename = "...")]`.
+#[proc_macro_derive(Builder, attributes(builder))]
+pub fn derive_builder(input: TokenStream) -> TokenStream {
+    let _ = input;
+    TokenStream::new()
+}
+
+/// Registers a function as the handler of a route.
+#[proc_macro_attribute]
+pub fn route(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let _ = attr;
+    item
+}
+
+/// Checks an SQL query at compile time.
+#[proc_macro]
+pub fn sql(input: TokenStream) -> TokenStream {
+    input
+}
@@ -0,0 +1,31 @@
+//! Version 1 of an API, used with `signatures_v2` as a test fixture for
+//! changes to field types and generics.
+
+use std::str::FromStr;
+
+/// An identifier.
+pub struct Id(pub u32);
+
+/// Converts a value by cloning it.
+pub fn convert<T: Clone>(x: &T) -> T {
+    x.clone()
+}
+
+/// Parses a value.
+pub fn parse<T>(s: &str) -> Option<T>
+where
+    T: FromStr,
+{
+    s.parse().ok()
+}
+
+/// An array of default values.
+pub fn defaults<T: Default, const N: usize>() -> [T; N] {
+    std::array::from_fn(|_| T::default())
+}
+
+/// A store of values by key.
+pub trait Store<K> {
+    /// The value of a key.
+    fn get(&self, key: &K) -> Option<u32>;
+}
@@ -0,0 +1,31 @@
+//! Version 2 of an API, used with `signatures_v1` as a test fixture for
+//! changes to field types and generics.
+
+use std::str::FromStr;
+
+/// An identifier.
+pub struct Id(pub u64);
+
+/// Converts a value by cloning it.
+pub fn convert<T: Clone + Send>(x: &T) -> T {
+    x.clone()
+}
+
+/// Parses a value.
+pub fn parse<T>(s: &str) -> Option<T>
+where
+    T: FromStr + Default,
+{
+    s.parse().ok()
+}
+
+/// An array of default values.
+pub fn defaults<const N: usize, T: Default>() -> [T; N] {
+    std::array::from_fn(|_| T::default())
+}
+
+/// A store of values by key.
+pub trait Store<K, V> {
+    /// The value of a key.
+    fn get(&self, key: &K) -> Option<V>;
+}